@@ -2,11 +2,17 @@ use std::path::PathBuf;
 
 use super::plan::BLUEPRINT_FILE;
 use crate::commands::helps::build;
+use crate::core::catalog::Catalog;
 use crate::core::context::Context;
+use crate::core::hook;
+use crate::core::hook::Stage;
 use crate::core::ip::Ip;
+use crate::core::lockfile::LockError;
 use crate::core::plugin::Plugin;
 use crate::core::plugin::PluginError;
 use crate::core::plugin::Process;
+use crate::core::profile;
+use crate::core::variable::VariableTable;
 use crate::util::anyerror::AnyError;
 use crate::util::environment;
 use crate::util::environment::EnvVar;
@@ -22,12 +28,15 @@ use clif::Error as CliError;
 #[derive(Debug, PartialEq)]
 pub struct Build {
     alias: Option<String>,
+    profile: Option<String>,
     list: bool,
     force: bool,
     command: Option<String>,
     build_dir: Option<String>,
     args: Vec<String>,
     verbose: bool,
+    locked: bool,
+    offline: bool,
 }
 
 impl FromCli for Build {
@@ -38,8 +47,11 @@ impl FromCli for Build {
             list: cli.check_flag(Flag::new("list"))?,
             verbose: cli.check_flag(Flag::new("verbose"))?,
             force: cli.check_flag(Flag::new("force"))?,
+            locked: cli.check_flag(Flag::new("locked"))?,
+            offline: cli.check_flag(Flag::new("offline"))?,
             // Options
             alias: cli.check_option(Optional::new("plugin").value("alias"))?,
+            profile: cli.check_option(Optional::new("profile").value("name"))?,
             build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
             command: cli.check_option(Optional::new("command").value("cmd"))?,
             // Remaining args
@@ -90,6 +102,28 @@ impl Command<Context> for Build {
         // verify running from an IP directory and enter IP's root directory
         c.goto_ip_path()?;
 
+        let target = Ip::load(c.get_ip_path().unwrap().clone(), true)?;
+
+        // a frozen lockfile must already agree with the manifest
+        if (self.locked == true || c.is_locked() == true) && target.can_use_lock() == false {
+            return Err(LockError::OutOfDate)?;
+        }
+
+        // every dependency must already be installed since nothing can be fetched
+        if self.offline == true || c.is_offline() == true {
+            let catalog = Catalog::new()
+                .installations(c.get_cache_path())?
+                .layers(c.get_cache_layers())?;
+            if let Some(entry) = target.get_lock().inner().iter().find(|e| {
+                e.get_sum().is_some() && catalog.is_cached_slot(&e.to_cache_slot_key()) == false
+            }) {
+                return Err(AnyError(format!(
+                    "ip {} is not installed and cannot be fetched while offline",
+                    entry.to_ip_spec()
+                )))?;
+            }
+        }
+
         // determine the build directory based on cli priority
         let default_build_dir = c.get_build_dir();
         let b_dir = self.build_dir.as_ref().unwrap_or(&default_build_dir);
@@ -108,14 +142,15 @@ impl Command<Context> for Build {
             return Err(AnyError(format!("No blueprint file to build from in directory '{}'\n\nTry `orbit plan --build-dir {0}` to generate a blueprint file", b_dir)))?;
         }
 
-        Environment::new()
+        let env = Environment::new()
             // read config.toml for setting any env variables
             .from_config(c.get_config())?
             // read ip manifest for env variables
-            .from_ip(&Ip::load(c.get_ip_path().unwrap().clone(), true)?)?
+            .from_ip(&target)?
             .add(EnvVar::new().key(ORBIT_BLUEPRINT).value(BLUEPRINT_FILE))
-            .add(EnvVar::new().key(ORBIT_BUILD_DIR).value(b_dir))
-            .initialize();
+            .add(EnvVar::new().key(ORBIT_BUILD_DIR).value(b_dir));
+        let vtable = VariableTable::new().load_environment(&env)?;
+        env.initialize();
 
         // load from .env file from the correct build dir
         let envs = match Environment::new().from_env_file(&c.get_ip_path().unwrap().join(b_dir)) {
@@ -126,13 +161,40 @@ impl Command<Context> for Build {
             },
         };
 
+        // use the profile from the command-line or else the one recorded during planning
+        let profile = match &self.profile {
+            Some(name) => Some(profile::find(name, c.get_config(), target.get_man())?),
+            None => match envs.get(environment::ORBIT_PROFILE) {
+                Some(name) => Some(profile::find(
+                    name.get_value(),
+                    c.get_config(),
+                    target.get_man(),
+                )?),
+                None => None,
+            },
+        };
+
         // check if ORBIT_PLUGIN was set and no command option was set
         let plug = match plug {
             // already configured from the command-line
             Some(plg) => Some(plg),
             // was not set on the command-line
             None => {
-                if let Some(plug) = envs.get(environment::ORBIT_PLUGIN) {
+                let selected = match self.profile.is_some() {
+                    // a profile from the command-line overrides the planned plugin
+                    true => profile.and_then(|p| p.get_plugin()).map(|p| p.as_str()),
+                    false => None,
+                };
+                if let Some(plug) = selected {
+                    if self.command.is_none() {
+                        match c.get_config().get_plugins().get(plug) {
+                            Some(&p) => Some(p),
+                            None => return Err(PluginError::Missing(plug.to_string()))?,
+                        }
+                    } else {
+                        None
+                    }
+                } else if let Some(plug) = envs.get(environment::ORBIT_PLUGIN) {
                     // verify there was no command option to override default plugin call
                     if self.command.is_none() {
                         match c.get_config().get_plugins().get(plug.get_value()) {
@@ -150,8 +212,21 @@ impl Command<Context> for Build {
             }
         };
 
+        let mut envs = envs;
+        if let Some(p) = profile {
+            p.get_env().into_iter().for_each(|e| {
+                envs.insert(e);
+            });
+        }
+        let vtable = vtable.load_environment(&envs)?;
         envs.initialize();
 
+        // pass the profile's arguments before the arguments from the command-line
+        let args = match profile {
+            Some(p) => [p.get_args(), self.args.clone()].concat(),
+            None => self.args.clone(),
+        };
+
         if plug.is_none() && self.command.is_none() {
             return Err(AnyError(format!(
                 "Building requires a plugin or a command to process"
@@ -163,20 +238,41 @@ impl Command<Context> for Build {
             std::fs::create_dir(&b_dir)?;
         }
 
-        // start command from the build directory
-        self.run(plug, &b_dir)
+        // start command from the build directory with its hooks
+        hook::run_hooks(
+            Stage::PreBuild,
+            &target,
+            plug,
+            &vtable,
+            self.verbose,
+            &b_dir,
+        )?;
+        self.run(plug, &b_dir, &args)?;
+        hook::run_hooks(
+            Stage::PostBuild,
+            &target,
+            plug,
+            &vtable,
+            self.verbose,
+            &b_dir,
+        )?;
+        Ok(())
     }
 }
 
 impl Build {
-    fn run(&self, plug: Option<&Plugin>, dir: &str) -> Result<(), Box<dyn std::error::Error>> {
+    fn run(
+        &self,
+        plug: Option<&Plugin>,
+        dir: &str,
+        args: &Vec<String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         // if there is a match run with the plugin then run it
         if let Some(p) = plug {
-            p.execute(&self.args, self.verbose, dir)
+            p.execute(args, self.verbose, dir)
         } else if let Some(cmd) = &self.command {
             if self.verbose == true {
-                let s = self
-                    .args
+                let s = args
                     .iter()
                     .fold(String::new(), |x, y| x + "\"" + &y + "\" ");
                 println!("info: Running: {} {}", cmd, s);
@@ -184,7 +280,7 @@ impl Build {
             let mut proc = crate::util::filesystem::invoke(
                 dir,
                 cmd,
-                &self.args,
+                args,
                 Context::enable_windows_bat_file_match(),
             )?;
             let exit_code = proc.wait()?;
@@ -0,0 +1,453 @@
+use crate::commands::helps::cache;
+use crate::core::catalog::CacheSlot;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::iparchive::ARCHIVE_EXT;
+use crate::core::lockfile::{LockFile, IP_LOCK_FILE};
+use crate::core::manifest;
+use crate::core::manifest::FromFile;
+use crate::core::signature;
+use crate::util::anyerror::AnyError;
+use crate::util::anyerror::Fault;
+use crate::util::filesystem;
+use crate::util::filesystem::Unit;
+use crate::OrbitResult;
+use clif::arg::{Flag, Optional, Positional};
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+const SECS_PER_DAY: u64 = 60 * 60 * 24;
+
+#[derive(Debug, PartialEq)]
+pub struct Cache {
+    command: Option<CacheSubcommand>,
+}
+
+impl FromCli for Cache {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(cache::HELP).ref_usage(2..4))?;
+        let command = Ok(Cache {
+            command: cli.check_command(Positional::new("command"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Cache {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        match &self.command {
+            Some(cmd) => cmd.exec(c),
+            // if no command is given then print default help
+            None => Ok(println!("{}", cache::HELP)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum CacheSubcommand {
+    List(List),
+    Gc(Gc),
+}
+
+impl FromCli for CacheSubcommand {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        match cli.match_command(&["list", "gc"])?.as_ref() {
+            "list" => Ok(CacheSubcommand::List(List::from_cli(cli)?)),
+            "gc" => Ok(CacheSubcommand::Gc(Gc::from_cli(cli)?)),
+            _ => panic!("an unimplemented command was passed through!"),
+        }
+    }
+}
+
+impl Command<Context> for CacheSubcommand {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        match self {
+            CacheSubcommand::List(cmd) => cmd.exec(c),
+            CacheSubcommand::Gc(cmd) => cmd.exec(c),
+        }
+    }
+}
+
+/// An installed slot in the cache or an archive in the downloads.
+#[derive(Debug, PartialEq, Clone)]
+struct Item {
+    path: PathBuf,
+    /// Names that a lockfile entry can reference this item by.
+    keys: Vec<String>,
+    last_used: Option<SystemTime>,
+}
+
+impl Item {
+    /// Collects every valid cache slot within the `cache` directory.
+    fn from_cache(cache: &PathBuf) -> Result<Vec<Self>, Fault> {
+        let mut items = Vec::new();
+        if cache.is_dir() == false {
+            return Ok(items);
+        }
+        for entry in fs::read_dir(cache)? {
+            let path = entry?.path();
+            if path.is_dir() == false {
+                continue;
+            }
+            let slot = match CacheSlot::try_from_str(&path.file_name().unwrap().to_string_lossy()) {
+                Some(s) => s,
+                None => continue,
+            };
+            let mut keys = vec![slot.to_string()];
+            // dynamic variants belong to whichever slot shares their name and version
+            if Ip::load(path.clone(), false).map_or(false, |ip| ip.is_dynamic()) == true {
+                keys.push(format!("{}-{}", slot.get_name(), slot.get_version()));
+            }
+            let last_used = match Ip::read_last_used_from_metadata(&path) {
+                Some(t) => Some(t),
+                None => fs::metadata(&path).and_then(|m| m.modified()).ok(),
+            };
+            items.push(Self {
+                path,
+                keys,
+                last_used,
+            });
+        }
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(items)
+    }
+
+    /// Collects every ip archive within the `downloads` directory.
+    fn from_downloads(downloads: &PathBuf) -> Result<Vec<Self>, Fault> {
+        let mut items = Vec::new();
+        if downloads.is_dir() == false {
+            return Ok(items);
+        }
+        for entry in fs::read_dir(downloads)? {
+            let path = entry?.path();
+            if path.is_file() == false
+                || path.extension().map_or(false, |e| e == ARCHIVE_EXT) == false
+            {
+                continue;
+            }
+            items.push(Self {
+                keys: vec![path.file_name().unwrap().to_string_lossy().to_string()],
+                last_used: fs::metadata(&path).and_then(|m| m.modified()).ok(),
+                path,
+            });
+        }
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(items)
+    }
+
+    fn get_name(&self) -> String {
+        self.path.file_name().unwrap().to_string_lossy().to_string()
+    }
+
+    fn is_referenced(&self, refs: &HashSet<String>) -> bool {
+        self.keys.iter().any(|k| refs.contains(k) == true)
+    }
+
+    fn is_older_than(&self, cutoff: &SystemTime) -> bool {
+        match &self.last_used {
+            Some(t) => t < cutoff,
+            None => true,
+        }
+    }
+
+    /// Computes the size of the item on disk in megabytes.
+    fn get_size(&self) -> Result<f32, Fault> {
+        filesystem::compute_size(&self.path, Unit::MegaBytes)
+    }
+}
+
+/// Formats how long ago `time` was with a resolution of days.
+fn fmt_age(time: &Option<SystemTime>, now: &SystemTime) -> String {
+    let days = match time {
+        Some(t) => now.duration_since(*t).unwrap_or_default().as_secs() / SECS_PER_DAY,
+        None => return String::from("-"),
+    };
+    match days {
+        0 => String::from("today"),
+        1 => String::from("1 day ago"),
+        n => format!("{} days ago", n),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct List {
+    downloads: bool,
+}
+
+impl FromCli for List {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(cache::HELP).ref_usage(2..4))?;
+        let command = Ok(List {
+            downloads: cli.check_flag(Flag::new("downloads"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for List {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        let items = match self.downloads {
+            true => Item::from_downloads(c.get_downloads_path())?,
+            false => Item::from_cache(c.get_cache_path())?,
+        };
+        let mut rows = Vec::with_capacity(items.len());
+        for item in &items {
+            rows.push((item, item.get_size()?));
+        }
+        println!("{}", Self::fmt_table(rows, &SystemTime::now()));
+        Ok(())
+    }
+}
+
+impl List {
+    fn fmt_table(items: Vec<(&Item, f32)>, now: &SystemTime) -> String {
+        let header = format!(
+            "\
+{:<44}{:<12}{}
+{3:->44}{3:->12}{3:->14}\n",
+            "Slot", "Size (MB)", "Last used", " "
+        );
+        let mut body = String::new();
+        for (item, size) in &items {
+            body.push_str(&format!(
+                "{:<44}{:<12.2}{}\n",
+                item.get_name(),
+                size,
+                fmt_age(&item.last_used, now),
+            ));
+        }
+        if items.is_empty() == false {
+            body.push_str(&format!(
+                "\n{} items using {:.2} MB\n",
+                items.len(),
+                items.iter().map(|(_, s)| s).sum::<f32>()
+            ));
+        }
+        header + &body
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Gc {
+    roots: Vec<PathBuf>,
+    older_than: Option<u64>,
+    dry_run: bool,
+}
+
+impl FromCli for Gc {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(cache::HELP).ref_usage(2..4))?;
+        let command = Ok(Gc {
+            dry_run: cli.check_flag(Flag::new("dry-run"))?,
+            roots: cli
+                .check_option_all(Optional::new("root").value("path"))?
+                .unwrap_or(Vec::new()),
+            older_than: cli.check_option(Optional::new("older-than").value("days"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Gc {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // the current ip is always a project root when inside one
+        let mut roots = self.roots.clone();
+        if let Some(p) = c.get_ip_path() {
+            roots.push(p.clone());
+        }
+        if roots.is_empty() == true && self.older_than.is_none() == true {
+            return Err(AnyError(format!(
+                "no project roots to search for lockfiles\n\nTry providing a root: `orbit cache gc --root <path>` or an age: `orbit cache gc --older-than <days>`"
+            )))?;
+        }
+
+        let refs = match roots.is_empty() {
+            true => None,
+            false => Some(Self::collect_references(&roots)?),
+        };
+        let cutoff = match self.older_than {
+            Some(days) => Some(SystemTime::now() - Duration::from_secs(days * SECS_PER_DAY)),
+            None => None,
+        };
+
+        let mut items = Item::from_cache(c.get_cache_path())?;
+        items.append(&mut Item::from_downloads(c.get_downloads_path())?);
+
+        self.run(Self::select(items, refs.as_ref(), cutoff.as_ref()))
+    }
+}
+
+impl Gc {
+    fn run(&self, items: Vec<Item>) -> Result<(), Fault> {
+        let mut total = 0.0;
+        for item in &items {
+            let size = item.get_size()?;
+            total += size;
+            match self.dry_run {
+                true => println!("info: Would remove {} ({:.2} MB)", item.get_name(), size),
+                false => {
+                    match item.path.is_dir() {
+                        true => fs::remove_dir_all(&item.path)?,
+                        false => {
+                            fs::remove_file(&item.path)?;
+                            signature::remove_detached(&item.path)?;
+                        }
+                    }
+                    println!("info: Removed {} ({:.2} MB)", item.get_name(), size);
+                }
+            }
+        }
+        match self.dry_run {
+            true => println!(
+                "info: Would free {:.2} MB from {} items",
+                total,
+                items.len()
+            ),
+            false => println!("info: Freed {:.2} MB from {} items", total, items.len()),
+        }
+        Ok(())
+    }
+
+    /// Collects the slot names referenced by every lockfile found under the `roots`.
+    fn collect_references(roots: &Vec<PathBuf>) -> Result<HashSet<String>, Fault> {
+        let mut refs = HashSet::new();
+        for root in roots {
+            if root.exists() == false {
+                return Err(AnyError(format!(
+                    "project root {} does not exist",
+                    filesystem::into_std_str(root.clone())
+                )))?;
+            }
+            for path in manifest::find_file(root, IP_LOCK_FILE, true)? {
+                let lock = LockFile::from_file(&path)?;
+                for entry in lock.inner() {
+                    if entry.get_sum().is_some() == true {
+                        refs.insert(entry.to_cache_slot_key().to_string());
+                    }
+                    refs.insert(entry.to_download_slot_key().as_ref().to_string());
+                    refs.insert(format!("{}-{}", entry.get_name(), entry.get_version()));
+                }
+            }
+        }
+        Ok(refs)
+    }
+
+    /// Chooses the items that are not referenced by `refs` and were last used
+    /// before the `cutoff`. A missing criterion is always satisfied.
+    fn select(
+        items: Vec<Item>,
+        refs: Option<&HashSet<String>>,
+        cutoff: Option<&SystemTime>,
+    ) -> Vec<Item> {
+        items
+            .into_iter()
+            .filter(|i| refs.map_or(true, |r| i.is_referenced(r) == false))
+            .filter(|i| cutoff.map_or(true, |t| i.is_older_than(t)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn item(name: &str, keys: Vec<&str>, days_ago: u64, now: &SystemTime) -> Item {
+        Item {
+            path: PathBuf::from(name),
+            keys: keys.into_iter().map(|k| k.to_string()).collect(),
+            last_used: Some(*now - Duration::from_secs(days_ago * SECS_PER_DAY)),
+        }
+    }
+
+    #[test]
+    fn select_items() {
+        let now = SystemTime::now();
+        let items = vec![
+            item(
+                "gates-1.0.0-aaaaaaaaaa",
+                vec!["gates-1.0.0-aaaaaaaaaa"],
+                2,
+                &now,
+            ),
+            item(
+                "gates-1.0.0-bbbbbbbbbb",
+                vec!["gates-1.0.0-bbbbbbbbbb", "gates-1.0.0"],
+                40,
+                &now,
+            ),
+            item(
+                "lab1-0.1.0-cccccccccc",
+                vec!["lab1-0.1.0-cccccccccc"],
+                40,
+                &now,
+            ),
+        ];
+        let refs: HashSet<String> = vec!["gates-1.0.0-aaaaaaaaaa", "gates-1.0.0"]
+            .into_iter()
+            .map(|s| s.to_string())
+            .collect();
+        let cutoff = now - Duration::from_secs(30 * SECS_PER_DAY);
+
+        // unreferenced only (dynamic variants follow their original slot)
+        let names: Vec<String> = Gc::select(items.clone(), Some(&refs), None)
+            .iter()
+            .map(|i| i.get_name())
+            .collect();
+        assert_eq!(names, vec!["lab1-0.1.0-cccccccccc"]);
+
+        // unused only
+        let names: Vec<String> = Gc::select(items.clone(), None, Some(&cutoff))
+            .iter()
+            .map(|i| i.get_name())
+            .collect();
+        assert_eq!(
+            names,
+            vec!["gates-1.0.0-bbbbbbbbbb", "lab1-0.1.0-cccccccccc"]
+        );
+
+        // both criteria must hold
+        let names: Vec<String> = Gc::select(items, Some(&refs), Some(&cutoff))
+            .iter()
+            .map(|i| i.get_name())
+            .collect();
+        assert_eq!(names, vec!["lab1-0.1.0-cccccccccc"]);
+    }
+
+    #[test]
+    fn format_age() {
+        let now = SystemTime::now();
+        assert_eq!(fmt_age(&None, &now), "-");
+        assert_eq!(fmt_age(&Some(now), &now), "today");
+        assert_eq!(
+            fmt_age(&Some(now - Duration::from_secs(SECS_PER_DAY)), &now),
+            "1 day ago"
+        );
+        assert_eq!(
+            fmt_age(&Some(now - Duration::from_secs(9 * SECS_PER_DAY)), &now),
+            "9 days ago"
+        );
+    }
+
+    #[test]
+    fn fmt_table() {
+        let t = List::fmt_table(Vec::new(), &SystemTime::now());
+        let table = "\
+Slot                                        Size (MB)   Last used
+------------------------------------------- ----------- ------------- 
+";
+        assert_eq!(t, table);
+    }
+}
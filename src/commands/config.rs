@@ -3,16 +3,19 @@ use std::str::FromStr;
 
 use crate::commands::helps::config;
 use crate::core::config::ConfigDocument;
+use crate::core::config::Locality;
 use crate::core::config::CONFIG_FILE;
 use crate::core::context::Context;
 use crate::core::manifest::FromFile;
 use crate::util::anyerror::AnyError;
+use crate::util::anyerror::Fault;
 use crate::OrbitResult;
-use clif::arg::{Flag, Optional};
+use clif::arg::{Flag, Optional, Positional};
 use clif::cmd::{Command, FromCli};
 use clif::Cli;
 use clif::Error as CliError;
 use colored::*;
+use toml_edit::{value, Array, Table};
 
 #[derive(Debug, PartialEq)]
 pub struct Entry(String, String);
@@ -32,9 +35,12 @@ impl FromStr for Entry {
 pub struct Config {
     global: bool,
     local: bool,
+    list: bool,
+    show_origin: bool,
     append: Vec<Entry>,
     set: Vec<Entry>,
     unset: Vec<String>,
+    command: Option<ConfigSubcommand>,
 }
 
 impl FromCli for Config {
@@ -44,6 +50,8 @@ impl FromCli for Config {
             // Flags
             global: cli.check_flag(Flag::new("global"))?,
             local: cli.check_flag(Flag::new("local"))?,
+            list: cli.check_flag(Flag::new("list"))?,
+            show_origin: cli.check_flag(Flag::new("show-origin"))?,
             // Options
             append: cli
                 .check_option_all(Optional::new("append").value("key=value"))?
@@ -54,6 +62,8 @@ impl FromCli for Config {
             unset: cli
                 .check_option_all(Optional::new("unset").value("key"))?
                 .unwrap_or(Vec::new()),
+            // Subcommands
+            command: cli.check_command(Positional::new("command"))?,
         });
         command
     }
@@ -63,40 +73,87 @@ impl Command<Context> for Config {
     type Status = OrbitResult;
 
     fn exec(&self, c: &Context) -> Self::Status {
-        // check if we are using global or local
-        if self.local == true && self.global == true {
+        if let Some(cmd) = &self.command {
+            return cmd.exec(c);
+        }
+        let lvl = select_locality(self.global, self.local)?;
+        if self.show_origin == true && self.list == false {
             return Err(AnyError(format!(
-                "'{}' and '{}' cannot be set at the same time",
-                "--local".yellow(),
-                "--global".yellow()
+                "'{}' can only be used with '{}'",
+                "--show-origin".yellow(),
+                "--list".yellow()
             )))?;
         }
-        let (mut cfg, file) = if self.local == true {
-            match c.get_ip_path() {
-                Some(path) => {
-                    let file = path.join(".orbit").join(CONFIG_FILE);
-                    (ConfigDocument::from_file(&file)?, file)
-                }
-                None => {
-                    return Err(AnyError(format!(
-                        "no ip detected in the current directory to modify local configurations"
-                    )))?
-                }
+        if self.list == true {
+            if self.append.is_empty() == false
+                || self.set.is_empty() == false
+                || self.unset.is_empty() == false
+            {
+                return Err(AnyError(format!(
+                    "'{}' cannot be used while modifying entries",
+                    "--list".yellow()
+                )))?;
             }
-        } else {
-            // duplicate the configuration so we can potentially mutate it
-            let file = c.get_all_configs().get_global().0.clone();
-            (
-                ConfigDocument::from_file(&file).expect("already should be parsed correctly"),
-                file,
-            )
-        };
+            self.list(c.get_all_configs().list(lvl.as_ref()));
+            return Ok(());
+        }
+        let (mut cfg, file) = open_document(c, self.local)?;
         // modify the settings for cfg file
         self.run(&mut cfg, &file)
     }
 }
 
+/// Determines which configuration file to access from the `--global` and
+/// `--local` flags.
+///
+/// Returns `None` when neither flag is set.
+fn select_locality(global: bool, local: bool) -> Result<Option<Locality>, Fault> {
+    match (global, local) {
+        (true, true) => Err(AnyError(format!(
+            "'{}' and '{}' cannot be set at the same time",
+            "--local".yellow(),
+            "--global".yellow()
+        )))?,
+        (true, false) => Ok(Some(Locality::Global)),
+        (false, true) => Ok(Some(Locality::Local)),
+        (false, false) => Ok(None),
+    }
+}
+
+/// Opens the configuration file to modify, which is the global file unless
+/// `local` is set.
+fn open_document(c: &Context, local: bool) -> Result<(ConfigDocument, PathBuf), Fault> {
+    if local == true {
+        match c.get_ip_path() {
+            Some(path) => {
+                let file = path.join(".orbit").join(CONFIG_FILE);
+                Ok((ConfigDocument::from_file(&file)?, file))
+            }
+            None => Err(AnyError(format!(
+                "no ip detected in the current directory to modify local configurations"
+            )))?,
+        }
+    } else {
+        // duplicate the configuration so we can potentially mutate it
+        let file = c.get_all_configs().get_global().0.clone();
+        Ok((
+            ConfigDocument::from_file(&file).expect("already should be parsed correctly"),
+            file,
+        ))
+    }
+}
+
 impl Config {
+    /// Prints every effective entry, and optionally where it was defined.
+    fn list(&self, entries: Vec<(String, toml::Value, String)>) {
+        for (key, value, origin) in entries {
+            match self.show_origin {
+                true => println!("{}\t{} = {}", origin, key, value),
+                false => println!("{} = {}", key, value),
+            }
+        }
+    }
+
     fn run(
         &self,
         cfg: &mut ConfigDocument,
@@ -106,6 +163,7 @@ impl Config {
         for entry in &self.append {
             match entry.0.as_ref() {
                 "include" => cfg.append_include(&entry.1),
+                "vendor" => cfg.append_vendor(&entry.1),
                 _ => {
                     return Err(AnyError(format!(
                         "unsupported key '{}' cannot be appended",
@@ -139,3 +197,192 @@ impl Config {
         cfg.write(&file)
     }
 }
+
+#[derive(Debug, PartialEq)]
+enum ConfigSubcommand {
+    Get(Get),
+    Plugin(TableSubcommand),
+    Protocol(TableSubcommand),
+}
+
+impl FromCli for ConfigSubcommand {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        match cli.match_command(&["get", "plugin", "protocol"])?.as_ref() {
+            "get" => Ok(ConfigSubcommand::Get(Get::from_cli(cli)?)),
+            "plugin" => Ok(ConfigSubcommand::Plugin(TableSubcommand::from_cli(cli)?)),
+            "protocol" => Ok(ConfigSubcommand::Protocol(TableSubcommand::from_cli(cli)?)),
+            _ => panic!("an unimplemented command was passed through!"),
+        }
+    }
+}
+
+impl Command<Context> for ConfigSubcommand {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        match self {
+            ConfigSubcommand::Get(cmd) => cmd.exec(c),
+            ConfigSubcommand::Plugin(cmd) => cmd.run(c, "plugin"),
+            ConfigSubcommand::Protocol(cmd) => cmd.run(c, "protocol"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Get {
+    key: String,
+    global: bool,
+    local: bool,
+    show_origin: bool,
+}
+
+impl FromCli for Get {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(config::HELP).ref_usage(2..4))?;
+        let command = Ok(Get {
+            global: cli.check_flag(Flag::new("global"))?,
+            local: cli.check_flag(Flag::new("local"))?,
+            show_origin: cli.check_flag(Flag::new("show-origin"))?,
+            key: cli.require_positional(Positional::new("key"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Get {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        let lvl = select_locality(self.global, self.local)?;
+        let prefix = format!("{}.", self.key);
+        let entries: Vec<(String, toml::Value, String)> = c
+            .get_all_configs()
+            .list(lvl.as_ref())
+            .into_iter()
+            .filter(|(k, _, _)| k == &self.key || k.starts_with(&prefix) == true)
+            .collect();
+        if entries.is_empty() == true {
+            return Err(AnyError(format!(
+                "key '{}' is not set in any configuration",
+                self.key
+            )))?;
+        }
+        for (key, value, origin) in entries {
+            // print a lone value as-is and any entries of a table with their keys
+            let text = match (key == self.key, value) {
+                (true, toml::Value::String(s)) => s,
+                (true, v) => v.to_string(),
+                (false, v) => format!("{} = {}", key, v),
+            };
+            match self.show_origin {
+                true => println!("{}\t{}", origin, text),
+                false => println!("{}", text),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The operations for the entries of an array of tables, such as `[[plugin]]`.
+#[derive(Debug, PartialEq)]
+enum TableSubcommand {
+    Add(AddTable),
+    Remove(RemoveTable),
+}
+
+impl FromCli for TableSubcommand {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        match cli.match_command(&["add", "remove"])?.as_ref() {
+            "add" => Ok(TableSubcommand::Add(AddTable::from_cli(cli)?)),
+            "remove" => Ok(TableSubcommand::Remove(RemoveTable::from_cli(cli)?)),
+            _ => panic!("an unimplemented command was passed through!"),
+        }
+    }
+}
+
+impl TableSubcommand {
+    /// Edits the array of tables at `key` in the selected configuration file.
+    fn run(&self, c: &Context, key: &str) -> Result<(), Fault> {
+        match self {
+            TableSubcommand::Add(cmd) => {
+                select_locality(cmd.global, cmd.local)?;
+                let (mut cfg, file) = open_document(c, cmd.local)?;
+                cfg.add_named_table(key, cmd.to_table())?;
+                cfg.write(&file)?;
+                println!("info: added {} '{}'", key, cmd.name);
+            }
+            TableSubcommand::Remove(cmd) => {
+                select_locality(cmd.global, cmd.local)?;
+                let (mut cfg, file) = open_document(c, cmd.local)?;
+                cfg.remove_named_table(key, &cmd.name)?;
+                cfg.write(&file)?;
+                println!("info: removed {} '{}'", key, cmd.name);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct AddTable {
+    name: String,
+    command: String,
+    description: Option<String>,
+    global: bool,
+    local: bool,
+    args: Vec<String>,
+}
+
+impl FromCli for AddTable {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(config::HELP).ref_usage(2..4))?;
+        let command = Ok(AddTable {
+            global: cli.check_flag(Flag::new("global"))?,
+            local: cli.check_flag(Flag::new("local"))?,
+            description: cli.check_option(Optional::new("description").value("text"))?,
+            name: cli.require_positional(Positional::new("name"))?,
+            command: cli.require_positional(Positional::new("command"))?,
+            // Remaining args
+            args: cli.check_remainder()?,
+        });
+        command
+    }
+}
+
+impl AddTable {
+    /// Creates the table to insert into the configuration file.
+    fn to_table(&self) -> Table {
+        let mut table = Table::new();
+        table.insert("name", value(&self.name));
+        table.insert("command", value(&self.command));
+        if self.args.is_empty() == false {
+            table.insert(
+                "args",
+                value(self.args.iter().map(|a| a.as_str()).collect::<Array>()),
+            );
+        }
+        if let Some(text) = &self.description {
+            table.insert("description", value(text));
+        }
+        table
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct RemoveTable {
+    name: String,
+    global: bool,
+    local: bool,
+}
+
+impl FromCli for RemoveTable {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(config::HELP).ref_usage(2..4))?;
+        let command = Ok(RemoveTable {
+            global: cli.check_flag(Flag::new("global"))?,
+            local: cli.check_flag(Flag::new("local"))?,
+            name: cli.require_positional(Positional::new("name"))?,
+        });
+        command
+    }
+}
@@ -1,32 +1,47 @@
 use crate::commands::helps::download;
 use crate::core::catalog::Catalog;
 use crate::core::catalog::DownloadSlot;
+use crate::core::config::Network;
 use crate::core::context::Context;
 use crate::core::ip::Ip;
 use crate::core::ip::IpSpec;
 use crate::core::iparchive::IpArchive;
 use crate::core::lockfile::LockEntry;
+use crate::core::lockfile::LockError;
 use crate::core::lockfile::LockFile;
 use crate::core::manifest;
 use crate::core::manifest::IP_MANIFEST_FILE;
 use crate::core::plugin::Process;
 use crate::core::protocol::Protocol;
+use crate::core::protocol::GIT_PROTOCOL;
+use crate::core::signature::Signer;
 use crate::core::source::Source;
 use crate::core::variable::VariableTable;
 use crate::util::anyerror::AnyError;
 use crate::util::anyerror::Fault;
 use crate::util::environment::Environment;
 use crate::util::filesystem::Standardize;
+use crate::util::sha256::{self, Sha256Hash};
 use crate::OrbitResult;
 use clif::arg::{Flag, Optional};
 use clif::cmd::{Command, FromCli};
 use clif::Cli;
 use clif::Error as CliError;
+use colored::Colorize;
 use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
 use tempfile::TempDir;
 
+/// The directory within the downloads that holds incomplete transfers.
+pub const PARTIAL_DIR: &str = ".partial";
+
+/// Number of hex digits of the url's hash kept in a partial file's name.
+const PARTIAL_HASH_LEN: usize = 8;
+
 #[derive(Debug, PartialEq)]
 pub struct Download {
     all: bool,
@@ -35,6 +50,9 @@ pub struct Download {
     queue_dir: Option<PathBuf>,
     verbose: bool,
     force: bool,
+    locked: bool,
+    offline: bool,
+    jobs: Option<usize>,
 }
 
 impl FromCli for Download {
@@ -47,8 +65,11 @@ impl FromCli for Download {
             list: cli.check_flag(Flag::new("list"))?,
             force: cli.check_flag(Flag::new("force"))?,
             verbose: cli.check_flag(Flag::new("verbose"))?,
+            locked: cli.check_flag(Flag::new("locked"))?,
+            offline: cli.check_flag(Flag::new("offline"))?,
             // Options
             queue_dir: cli.check_option(Optional::new("queue").value("dir"))?,
+            jobs: cli.check_option(Optional::new("jobs").value("n"))?,
         });
         command
     }
@@ -82,7 +103,7 @@ impl Command<Context> for Download {
                 cat.set_downloads_path(c.get_downloads_path());
                 cat
             }
-            false => Catalog::new().downloads(c.get_downloads_path())?,
+            false => Catalog::new().downloads(c.get_downloads_path(), &c.get_trust()?)?,
         };
 
         // verify running from an IP directory and enter IP's root directory
@@ -95,6 +116,11 @@ impl Command<Context> for Download {
             panic!("cannot download due to missing lockfile")
         }
 
+        // a frozen lockfile must already agree with the manifest
+        if (self.locked == true || c.is_locked() == true) && ip.can_use_lock() == false {
+            return Err(LockError::OutOfDate)?;
+        }
+
         let env = Environment::new()
             // read config.toml for setting any env variables
             .from_config(c.get_config())?
@@ -113,21 +139,38 @@ impl Command<Context> for Download {
         // determine whether to filter out or keep the dev dependencies from the lock file
         let lf = ip.get_lock().keep_dev_dep_entries(&ip, self.all);
 
-        let downloads =
-            Self::compile_download_list(&LockEntry::from((&ip, true)), &lf, &catalog, missing_only);
+        let downloads = Self::compile_download_list(
+            &LockEntry::from((&ip, true)),
+            &lf,
+            &catalog,
+            missing_only,
+        )?;
         // print to console
         if to_stdout == true {
             downloads.iter().for_each(|(_, src)| println!("{}", src));
+        // refuse to reach out to the internet
+        } else if (self.offline == true || c.is_offline() == true) && downloads.is_empty() == false
+        {
+            return Err(AnyError(format!(
+                "unable to fetch {} ip from the internet while offline:\n{}",
+                downloads.len(),
+                downloads
+                    .iter()
+                    .fold(String::new(), |sum, x| sum + &format!("    {}\n", x.0))
+            )))?;
         // execute the command
         } else {
             Self::download_all(
                 &downloads,
                 &proto_map,
+                &c.get_config().get_network(),
+                c.get_signer()?.as_ref(),
                 vtable,
                 self.verbose,
                 self.queue_dir.as_ref(),
                 c.get_downloads_path(),
                 self.force,
+                self.jobs.unwrap_or(c.get_download_jobs()),
             )?;
         }
         Ok(())
@@ -143,7 +186,7 @@ impl Download {
         lf: &'a LockFile,
         catalog: &Catalog,
         missing_only: bool,
-    ) -> Vec<(IpSpec, Source)> {
+    ) -> Result<Vec<(IpSpec, Source)>, Fault> {
         let mut vtable = VariableTable::new();
         lf.inner()
             .iter()
@@ -157,8 +200,11 @@ impl Download {
                 let spec = f.to_ip_spec();
                 vtable.add("orbit.ip.name", spec.get_name().as_ref());
                 vtable.add("orbit.ip.version", &spec.get_version().to_string());
-                let processed_src = f.get_source().unwrap().clone().replace_vars_in_url(&vtable);
-                (spec, processed_src)
+                let processed_src = f
+                    .get_pinned_source()
+                    .unwrap()
+                    .replace_vars_in_url(&vtable)?;
+                Ok((spec, processed_src))
             })
             .collect()
     }
@@ -172,9 +218,24 @@ impl Download {
         queue: Option<&PathBuf>,
         download_dir: &PathBuf,
         protocols: &HashMap<&str, &Protocol>,
+        network: &Network,
+        signer: Option<&Signer>,
         verbose: bool,
         _force: bool,
     ) -> Result<(), Fault> {
+        // redirect the source to a mirror if one is available
+        let mirrored: Source;
+        let src = match network.rewrite(src.get_url()) {
+            Some(url) => {
+                if verbose == true {
+                    println!("info: Using mirror {} for {}", url, src.get_url());
+                }
+                mirrored = src.clone().url(url);
+                &mirrored
+            }
+            None => src,
+        };
+
         // use the user-provided queue directory or simply use a temporary directory
         let queue = match queue {
             Some(q) => {
@@ -184,6 +245,11 @@ impl Download {
             None => TempDir::into_path(TempDir::new()?),
         };
 
+        // the commit the source resolved to, if known
+        let mut commit: Option<String> = None;
+        // the digest of the archive fetched for the source, if known
+        let mut digest: Option<Sha256Hash> = None;
+
         // access the protocol
         if let Some(proto) = src.get_protocol() {
             match protocols.get(proto.as_str()) {
@@ -204,12 +270,48 @@ impl Download {
                         src.get_tag().unwrap_or(&String::new()),
                     );
                     // allow the user to handle placing the code in the queue
-                    let entry: Protocol = entry.clone().replace_vars_in_args(&vtable);
+                    let entry: Protocol = match entry.clone().replace_vars_in_args(&vtable) {
+                        Ok(e) => e,
+                        Err(err) => {
+                            fs::remove_dir_all(queue)?;
+                            return Err(err)?;
+                        }
+                    };
                     if let Err(err) = entry.execute(&[], verbose, &std_queue.to_str().unwrap()) {
                         fs::remove_dir_all(queue)?;
                         return Err(err);
                     }
                 }
+                // fall back to the built-in git protocol
+                None if proto == GIT_PROTOCOL => {
+                    vtable.add("orbit.ip.name", spec.get_name().as_ref());
+                    vtable.add("orbit.ip.version", &spec.get_version().to_string());
+                    let processed_src = match src.clone().replace_vars_in_url(&vtable) {
+                        Ok(s) => s,
+                        Err(err) => {
+                            fs::remove_dir_all(queue)?;
+                            return Err(err)?;
+                        }
+                    };
+
+                    println!(
+                        "info: Downloading {} over built-in \"{}\" protocol ...",
+                        spec, &proto
+                    );
+                    match Protocol::git_download(
+                        processed_src.get_url(),
+                        src.get_tag().map(|t| t.as_str()),
+                        src.get_commit().map(|c| c.as_str()),
+                        &queue,
+                        network.get_http(),
+                    ) {
+                        Ok(c) => commit = Some(c),
+                        Err(err) => {
+                            fs::remove_dir_all(queue)?;
+                            return Err(err);
+                        }
+                    }
+                }
                 None => {
                     // potential to use --force here to avoid this error and try with default but not currently implemented that way
                     fs::remove_dir_all(queue)?;
@@ -224,16 +326,51 @@ impl Download {
             vtable.add("orbit.ip.name", spec.get_name().as_ref());
             vtable.add("orbit.ip.version", &spec.get_version().to_string());
 
-            let processed_src = src.clone().replace_vars_in_url(&vtable);
+            let processed_src = match src.clone().replace_vars_in_url(&vtable) {
+                Ok(s) => s,
+                Err(err) => {
+                    fs::remove_dir_all(queue)?;
+                    return Err(err)?;
+                }
+            };
+
+            // keep the partial file outside the queue to resume it after an interruption
+            let partials = download_dir.join(PARTIAL_DIR);
+            std::fs::create_dir_all(&partials)?;
+            let partial = partials.join(Self::partial_name(spec, processed_src.get_url()));
 
             println!("info: Downloading {} ...", spec);
-            if let Err(err) = Protocol::single_download(processed_src.get_url(), &queue) {
-                fs::remove_dir_all(queue)?;
-                return Err(err);
+            match Protocol::single_download(
+                processed_src.get_url(),
+                &queue,
+                &partial,
+                src.get_sha256(),
+                network.get_http(),
+            ) {
+                Ok(d) => digest = Some(d),
+                Err(err) => {
+                    fs::remove_dir_all(queue)?;
+                    return Err(err);
+                }
             }
+        // only the default protocol fetches a single archive to verify
+        } else if src.get_sha256().is_some() == true {
+            println!(
+                "{}: unable to verify sha256 of ip {} fetched over \"{}\" protocol",
+                "warning".yellow().bold(),
+                spec,
+                src.get_protocol().unwrap()
+            );
         }
         // move the IP to the downloads folder
-        if let Err(err) = Self::move_to_download_dir(&queue, download_dir, spec) {
+        if let Err(err) = Self::move_to_download_dir(
+            &queue,
+            download_dir,
+            spec,
+            commit.as_deref(),
+            digest.as_ref(),
+            signer,
+        ) {
             fs::remove_dir_all(queue)?;
             return Err(err);
         }
@@ -242,10 +379,32 @@ impl Download {
         Ok(())
     }
 
+    /// Names the partial file for downloading `spec` from `url`.
+    ///
+    /// The name includes a short hash of the url so a partial file is never
+    /// resumed from a different location, such as after switching mirrors.
+    fn partial_name(spec: &IpSpec, url: &str) -> String {
+        let hash = sha256::compute_sha256(url.as_bytes()).to_string();
+        format!(
+            "{}-{}-{}.part",
+            spec.get_name(),
+            spec.get_version(),
+            &hash[..PARTIAL_HASH_LEN]
+        )
+    }
+
+    /// Archives the ip matching `spec` found within the `queue` into the
+    /// `downloads` directory.
+    ///
+    /// The `commit` the ip's source resolved to and the `digest` of the archive
+    /// it was fetched as are recorded in its metadata.
     pub fn move_to_download_dir(
         queue: &PathBuf,
         downloads: &PathBuf,
         spec: &IpSpec,
+        commit: Option<&str>,
+        digest: Option<&Sha256Hash>,
+        signer: Option<&Signer>,
     ) -> Result<(), Fault> {
         // code is in the queue now, move it to the downloads/ folder
 
@@ -258,11 +417,17 @@ impl Download {
                     if temp.get_man().get_ip().get_name() == spec.get_name()
                         && temp.get_man().get_ip().get_version() == spec.get_version()
                     {
+                        if let Some(c) = commit {
+                            Ip::write_commit_to_metadata(temp.get_root(), c)?;
+                        }
+                        if let Some(d) = digest {
+                            Ip::write_digest_to_metadata(temp.get_root(), d)?;
+                        }
                         // zip the project to the downloads directory
                         let download_slot_name =
                             DownloadSlot::new(spec.get_name(), spec.get_version(), temp.get_uuid());
                         let full_download_path = downloads.join(&download_slot_name.as_ref());
-                        IpArchive::write(&temp, &full_download_path)?;
+                        IpArchive::write(&temp, &full_download_path, signer)?;
                         return Ok(());
                     }
                 }
@@ -273,16 +438,26 @@ impl Download {
         Err(AnyError(format!("Failed to detect/load the IP's manifest")))?
     }
 
+    /// Downloads every entry in `downloads`, running up to `jobs` downloads
+    /// at the same time.
+    ///
+    /// Every entry is attempted even if an earlier one fails. A user-provided
+    /// `queue` directory is shared by all entries, so it limits the downloads
+    /// to one at a time.
     pub fn download_all(
         downloads: &Vec<(IpSpec, Source)>,
         proto_map: &HashMap<&str, &Protocol>,
+        network: &Network,
+        signer: Option<&Signer>,
         vtable: VariableTable,
         verbose: bool,
         queue: Option<&PathBuf>,
         download_dir: &PathBuf,
         force: bool,
+        jobs: usize,
     ) -> Result<(), Fault> {
-        match downloads.len() {
+        let total = downloads.len();
+        match total {
             0 => {
                 println!("info: No missing downloads");
                 return Ok(());
@@ -291,30 +466,75 @@ impl Download {
                 println!("info: Downloading 1 package ...")
             }
             _ => {
-                println!("info: Downloading {} packages ...", downloads.len())
+                println!("info: Downloading {} packages ...", total)
             }
         }
-        let mut vtable = vtable;
-        let mut results = downloads.iter().filter_map(|e| {
-            match Self::download(
-                &mut vtable,
-                &e.0,
-                &e.1,
-                queue,
-                &download_dir,
-                &proto_map,
-                verbose,
-                force,
-            ) {
-                Ok(_) => None,
-                Err(e) => Some(e),
+        let jobs = match queue {
+            Some(_) => 1,
+            None => jobs.clamp(1, total),
+        };
+        let start = Instant::now();
+
+        let next = AtomicUsize::new(0);
+        let completed = AtomicUsize::new(0);
+        let failures: Mutex<Vec<(&IpSpec, String)>> = Mutex::new(Vec::new());
+        std::thread::scope(|s| {
+            for _ in 0..jobs {
+                let mut vtable = vtable.clone();
+                let (next, completed, failures) = (&next, &completed, &failures);
+                s.spawn(move || {
+                    // take the next remaining entry until there are none left
+                    while let Some((spec, src)) = downloads.get(next.fetch_add(1, Ordering::SeqCst))
+                    {
+                        let result = Self::download(
+                            &mut vtable,
+                            spec,
+                            src,
+                            queue,
+                            download_dir,
+                            proto_map,
+                            network,
+                            signer,
+                            verbose,
+                            force,
+                        );
+                        let count = completed.fetch_add(1, Ordering::SeqCst) + 1;
+                        match result {
+                            Ok(()) => println!("info: [{}/{}] Downloaded {}", count, total, spec),
+                            Err(e) => {
+                                println!(
+                                    "{}: [{}/{}] Failed to download {}: {}",
+                                    "warning".yellow().bold(),
+                                    count,
+                                    total,
+                                    spec,
+                                    e
+                                );
+                                failures.lock().unwrap().push((spec, e.to_string()));
+                            }
+                        }
+                    }
+                });
             }
         });
-        if let Some(n) = results.next() {
-            return Err(n);
-        }
+        let failures = failures.into_inner().unwrap();
 
-        Ok(())
+        println!(
+            "info: Downloaded {} of {} packages in {:.2}s",
+            total - failures.len(),
+            total,
+            start.elapsed().as_secs_f32()
+        );
+        match failures.is_empty() {
+            true => Ok(()),
+            false => Err(AnyError(format!(
+                "failed to download {} of {} packages:\n{}",
+                failures.len(),
+                total,
+                failures.iter().fold(String::new(), |sum, (spec, e)| sum
+                    + &format!("    {}: {}\n", spec, e))
+            )))?,
+        }
     }
 }
 
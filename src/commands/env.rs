@@ -7,11 +7,12 @@ use crate::core::ip::Ip;
 use crate::util::environment;
 use crate::util::environment::EnvVar;
 use crate::util::environment::Environment;
+use crate::util::environment::ExportFormat;
 use crate::util::environment::ORBIT_BLUEPRINT;
 use crate::util::environment::ORBIT_WIN_LITERAL_CMD;
 use crate::util::filesystem::Standardize;
 use crate::OrbitResult;
-use clif::arg::Positional;
+use clif::arg::{Optional, Positional};
 use clif::cmd::{Command, FromCli};
 use clif::Cli;
 use clif::Error as CliError;
@@ -19,17 +20,22 @@ use clif::Error as CliError;
 #[derive(Debug, PartialEq)]
 pub struct Env {
     keys: Vec<String>,
+    export: Option<ExportFormat>,
 }
 
 impl FromCli for Env {
     fn from_cli(cli: &mut Cli) -> Result<Self, CliError> {
         cli.check_help(clif::Help::new().quick_text(env::HELP).ref_usage(2..4))?;
+        let export = cli.check_option(Optional::new("export").value("format"))?;
         // collect all positional arguments
         let mut keys: Vec<String> = Vec::new();
         while let Some(c) = cli.check_positional(Positional::new("key"))? {
             keys.push(c);
         }
-        let command = Ok(Env { keys: keys });
+        let command = Ok(Env {
+            keys: keys,
+            export: export,
+        });
         command
     }
 }
@@ -47,6 +53,11 @@ impl Command<Context> for Env {
             EnvVar::new()
                 .key(environment::ORBIT_CACHE)
                 .value(PathBuf::standardize(c.get_cache_path()).to_str().unwrap()),
+            EnvVar::new().key(environment::ORBIT_CACHE_PATH).value(
+                std::env::join_paths(c.get_cache_layers())?
+                    .to_str()
+                    .unwrap(),
+            ),
             EnvVar::new().key(environment::ORBIT_DOWNLOADS).value(
                 PathBuf::standardize(c.get_downloads_path())
                     .to_str()
@@ -101,6 +112,22 @@ impl Env {
     fn run(&self, env: Environment) -> Result<(), Box<dyn std::error::Error>> {
         let mut result = String::new();
 
+        // write the variables as statements for another program to evaluate
+        if let Some(format) = &self.export {
+            env.iter()
+                .filter(|e| {
+                    self.keys.is_empty() == true || self.keys.iter().any(|k| env.get(k) == Some(e))
+                })
+                // skip variables that are not set to avoid clearing existing values
+                .filter(|e| e.get_value().is_empty() == false)
+                .for_each(|e| {
+                    result.push_str(&e.to_export(format));
+                    result.push('\n');
+                });
+            print!("{}", result);
+            return Ok(());
+        }
+
         match self.keys.is_empty() {
             // print debugging output (all variables)
             true => {
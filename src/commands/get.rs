@@ -80,12 +80,13 @@ impl Command<Context> for Get {
         let catalog = Catalog::new()
             // .store(c.get_store_path())
             // .development(c.get_development_path().unwrap())?
-            .installations(c.get_cache_path())?;
+            .installations(c.get_cache_path())?
+            .layers(c.get_cache_layers())?;
 
         // try to auto-determine the ip (check if in a working ip)
         let ip_path = if let Some(spec) = &self.ip {
             // @todo: find the path to the provided ip by searching through the catalog
-            if let Some(lvl) = catalog.inner().get(spec.get_name()) {
+            if let Some(lvl) = catalog.lookup(spec.get_name())? {
                 if let Some(slot) = lvl.get_install(spec.get_version()) {
                     slot.get_root().clone()
                 } else {
@@ -28,6 +28,13 @@ enum Topic {
     Config,
     Uninstall,
     Read,
+    Vendor,
+    Update,
+    Outdated,
+    Verify,
+    Inspect,
+    Unpack,
+    Cache,
 }
 
 impl std::str::FromStr for Topic {
@@ -49,6 +56,13 @@ impl std::str::FromStr for Topic {
             "config" => Self::Config,
             "uninstall" => Self::Uninstall,
             "read" => Self::Read,
+            "vendor" => Self::Vendor,
+            "update" => Self::Update,
+            "outdated" => Self::Outdated,
+            "verify" => Self::Verify,
+            "inspect" => Self::Inspect,
+            "unpack" => Self::Unpack,
+            "cache" => Self::Cache,
             _ => return Err(AnyError(format!("topic '{}' not found", s))),
         })
     }
@@ -74,6 +88,13 @@ impl Topic {
             Config => manuals::config::MANUAL,
             Uninstall => manuals::remove::MANUAL,
             Read => manuals::read::MANUAL,
+            Vendor => manuals::vendor::MANUAL,
+            Update => manuals::update::MANUAL,
+            Outdated => manuals::outdated::MANUAL,
+            Verify => manuals::verify::MANUAL,
+            Inspect => manuals::inspect::MANUAL,
+            Unpack => manuals::unpack::MANUAL,
+            Cache => manuals::cache::MANUAL,
         }
     }
 }
@@ -6,10 +6,13 @@ Usage:
 
 Options:
     --plugin <name>    plugin to execute
+    --profile <name>    select a profile to build with
     --command <cmd>     command to execute
     --list              view available plugins
     --build-dir <dir>   set the output build directory
     --verbose           display the command being executed
+    --locked            fail if the lock file is out of date
+    --offline           fail if a dependency is not installed
     args                arguments to pass to the requested command
 
 Use 'orbit help build' to read more about the command.
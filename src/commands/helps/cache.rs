@@ -0,0 +1,18 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Manage the installed and downloaded ip.
+
+Usage:
+    orbit cache <command> [options]
+
+Commands:
+    list                display all slots in the cache
+    gc                  remove unreferenced or unused entries
+
+Options:
+    --downloads         list the archives in the downloads instead
+    --root <path>...    keep entries referenced by lockfiles under the path
+    --older-than <days> only remove entries unused for this many days
+    --dry-run           report the entries to remove without deleting them
+
+Use 'orbit help cache' to read more about the command.
+"#;
@@ -2,11 +2,23 @@
 pub const HELP: &str = r#"Modify configuration values.
 
 Usage:
-    orbit config [options]
+    orbit config [options] [<command>]
+
+Commands:
+    get <key>                   print the effective value of a key
+    plugin add <name> <command> [--] [args]...
+                                add a plugin entry
+    plugin remove <name>        remove a plugin entry
+    protocol add <name> <command> [--] [args]...
+                                add a protocol entry
+    protocol remove <name>      remove a protocol entry
 
 Options:
     --global                    access the home configuration file
     --local                     access the current project configuration file
+    --list                      print every effective configuration entry
+    --show-origin               print where each listed entry was defined
+    --description <text>        set the description of an added entry
     --append <key>=<value>...   add a value to a key storing a list
     --set <key>=<value>...      write the value at the key entry
     --unset <key>...            delete the key's entry
@@ -11,6 +11,9 @@ Options:
     --queue <dir>       set the destination directory to place fetched codebase
     --verbose           display the command being executed
     --force             fallback to default protocol if missing given protocol
+    --locked            fail if the lock file is out of date
+    --offline           fail if any package must be fetched
+    --jobs <n>          set the number of concurrent downloads
 
 Use 'orbit help download' to read more about the command.
 "#;
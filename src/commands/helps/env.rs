@@ -5,7 +5,8 @@ Usage:
     orbit env [options]
 
 Options:
-    <key>...     Lookup environment variable to include its value
+    <key>...              Lookup environment variable to include its value
+    --export <format>     Print the variables as statements (sh, fish, powershell, dotenv)
 
 Use 'orbit help env' to read more about the command.
 "#;
@@ -0,0 +1,11 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Look inside an ip archive.
+
+Usage:
+    orbit inspect [options] <file>
+
+Options:
+    <file>              the .ip archive to inspect
+
+Use 'orbit help inspect' to read more about the command.
+"#;
@@ -14,6 +14,8 @@ Options:
     --list              view available protocols and exit
     --verbose           display the command(s) being executed
     --force             install regardless of cache slot occupancy
+    --locked            fail if the lock file would be modified
+    --offline           fail if a dependency must be downloaded
 
 Use 'orbit help install' to read more about the command.
 "#;
@@ -20,6 +20,13 @@ Commands:
     env             print orbit environment information
     config          modify configuration values
     remove          uninstall an ip from the catalog
+    vendor          manage ip registries
+    update          refresh the lockfile within manifest constraints
+    outdated        list dependencies with newer versions
+    verify          audit the installations recorded in the lockfile
+    inspect         look inside an ip archive
+    unpack          extract an ip archive into a directory
+    cache           manage the installed and downloaded ip
 
 Options:
     --version       print version information and exit
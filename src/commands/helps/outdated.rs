@@ -0,0 +1,12 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"List dependencies with newer versions.
+
+Usage:
+    orbit outdated [options]
+
+Options:
+    --all               include dependencies that are up to date
+    --json              print the report as json data
+
+Use 'orbit help outdated' to read more about the command.
+"#;
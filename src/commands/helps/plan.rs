@@ -8,6 +8,7 @@ Options:
     --top <unit>            override auto-detected toplevel entity
     --bench <tb>            override auto-detected toplevel testbench
     --plugin <name>        collect filesets defined for a plugin
+    --profile <name>        select a profile for unset options
     --build-dir <dir>       set the output build directory
     --fileset <key=glob>... set an additional fileset
     --clean                 remove all files from the build directory
@@ -15,6 +16,8 @@ Options:
     --lock-only             create the lockfile and exit
     --all                   include all found HDL files
     --force                 skip reading from the lock file
+    --locked                fail if the lock file would be modified
+    --offline               fail if a dependency must be downloaded
 
 Use 'orbit help plan' to read more about the command.
 "#;
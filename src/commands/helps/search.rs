@@ -10,6 +10,7 @@ Args:
 Options:
     --install, -i       filter ip installed to cache
     --download, -d      filter ip downloaded to downloads
+    --available, -a     filter ip available from vendors
     --keyword <term>... special word to filter out packages
     --limit <num>       maximum number of results to return
     --match             only return results with each filter passed
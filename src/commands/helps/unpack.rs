@@ -0,0 +1,12 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Extract an ip archive into a directory.
+
+Usage:
+    orbit unpack [options] <file> <dir>
+
+Options:
+    <file>              the .ip archive to extract
+    <dir>               the directory to place the files
+
+Use 'orbit help unpack' to read more about the command.
+"#;
@@ -0,0 +1,14 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Refresh the lockfile within manifest constraints.
+
+Usage:
+    orbit update [options] [<ip>...]
+
+Args:
+    <ip>...             names of the dependencies to update
+
+Options:
+    --dry-run           display the changes without writing the lockfile
+
+Use 'orbit help update' to read more about the command.
+"#;
@@ -0,0 +1,13 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Manage ip registries.
+
+Usage:
+    orbit vendor <command> [options]
+
+Commands:
+    add <path>          register the directory at path as a vendor
+    refresh [<name>]    pull the latest changes for a vendor
+    list                display all known vendors
+
+Use 'orbit help vendor' to read more about the command.
+"#;
@@ -0,0 +1,11 @@
+// This help page was automatically generated from the mangen.py tool.
+pub const HELP: &str = r#"Audit the installations recorded in the lockfile.
+
+Usage:
+    orbit verify [options]
+
+Options:
+    --repair            reinstall failing ip from the downloads
+
+Use 'orbit help verify' to read more about the command.
+"#;
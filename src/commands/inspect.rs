@@ -0,0 +1,129 @@
+use crate::commands::helps::inspect;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::iparchive::{ArchiveUnit, IpArchive};
+use crate::core::lang::LangMode;
+use crate::core::signature::Verdict;
+use crate::util::anyerror::Fault;
+use crate::OrbitResult;
+use clif::arg::Positional;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use colored::Colorize;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq)]
+pub struct Inspect {
+    file: PathBuf,
+}
+
+impl FromCli for Inspect {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(inspect::HELP).ref_usage(2..4))?;
+        let command = Ok(Inspect {
+            file: cli.require_positional(Positional::new("file"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Inspect {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // read the archive without repairing it so the file is left untouched
+        let archive = IpArchive::open(&self.file)?;
+        let verdict = archive.judge(&self.file, &c.get_trust()?);
+        self.run(&archive, verdict)
+    }
+}
+
+impl Inspect {
+    fn run(&self, archive: &IpArchive, verdict: Verdict) -> Result<(), Fault> {
+        let files = archive.list_files()?;
+
+        println!("{:<12}{}", "Ip:", archive.get_man().get_ip().into_ip_spec());
+        println!("{:<12}{}", "Format:", archive.get_version());
+        println!("{:<12}{}", "Signature:", Self::format_verdict(&verdict));
+        println!(
+            "{:<12}{} ({} bytes)",
+            "Files:",
+            files.len(),
+            files.iter().map(|(_, size)| size).sum::<u64>()
+        );
+
+        println!("\n{}", "Manifest:".bold());
+        println!("{}", archive.get_man().to_string().trim_end());
+
+        println!("\n{}", "Lockfile:".bold());
+        match archive.get_lock().is_empty() {
+            true => println!("(none)"),
+            false => println!("{}", archive.get_lock().to_string().trim_end()),
+        }
+
+        println!("\n{}", "Units:".bold());
+        let units = match archive.get_units() {
+            Some(u) => u.clone(),
+            // archives without a unit list are unpacked to find them
+            None => Self::collect_units(archive)?,
+        };
+        println!("{}", Self::format_units_table(&units));
+
+        println!("\n{}", "Files:".bold());
+        for (name, size) in &files {
+            println!("{:>10}  {}", size, name);
+        }
+        Ok(())
+    }
+
+    fn format_verdict(verdict: &Verdict) -> String {
+        match verdict {
+            Verdict::Trusted => "signed by a trusted key".green().to_string(),
+            Verdict::Unsigned => "unsigned".yellow().to_string(),
+            Verdict::Invalid => "invalid".red().to_string(),
+            Verdict::Untrusted(key) => format!("signed by untrusted key {}", key)
+                .yellow()
+                .to_string(),
+        }
+    }
+
+    /// Finds the primary design units by unpacking the archive into a
+    /// temporary directory.
+    fn collect_units(archive: &IpArchive) -> Result<Vec<ArchiveUnit>, Fault> {
+        let dir = tempfile::tempdir()?;
+        let root = dir.path().to_path_buf();
+        IpArchive::extract(archive.get_archive(), &root)?;
+        let units = Ip::collect_units(
+            true,
+            &root,
+            &LangMode::default(),
+            false,
+            Ip::load(root.clone(), true)?.into_public_list(),
+        )?;
+        Ok(units.values().map(|u| ArchiveUnit::from(u)).collect())
+    }
+
+    /// Creates a string to display the primary design units within the archive.
+    fn format_units_table(units: &Vec<ArchiveUnit>) -> String {
+        let header = format!(
+            "\
+{:<36}{:<14}{:<12}
+{:->36}{3:->14}{3:->12}\n",
+            "Identifier", "Type", "Language", " "
+        );
+        let mut units: Vec<&ArchiveUnit> = units.iter().collect();
+        units.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+
+        let mut body = String::new();
+        for unit in units {
+            body.push_str(&format!(
+                "{:<36}{:<14}{:<12}\n",
+                unit.get_name(),
+                unit.get_kind(),
+                unit.get_language(),
+            ));
+        }
+        header + body.trim_end()
+    }
+}
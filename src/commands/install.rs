@@ -29,9 +29,11 @@ use crate::core::ip::Ip;
 use crate::core::ip::PartialIpSpec;
 use crate::core::iparchive::IpArchive;
 use crate::core::lockfile::LockEntry;
+use crate::core::lockfile::LockError;
 use crate::core::manifest::IP_MANIFEST_FILE;
 use crate::core::manifest::ORBIT_SUM_FILE;
 use crate::core::protocol::Protocol;
+use crate::core::signature;
 use crate::core::source::Source;
 use crate::core::variable::VariableTable;
 use crate::core::version;
@@ -62,6 +64,8 @@ pub struct Install {
     force: bool,
     verbose: bool,
     all: bool,
+    locked: bool,
+    offline: bool,
 }
 
 impl FromCli for Install {
@@ -73,6 +77,8 @@ impl FromCli for Install {
             verbose: cli.check_flag(Flag::new("verbose"))?,
             all: cli.check_flag(Flag::new("all"))?,
             list: cli.check_flag(Flag::new("list"))?,
+            locked: cli.check_flag(Flag::new("locked"))?,
+            offline: cli.check_flag(Flag::new("offline"))?,
             // Options
             path: cli.check_option(Optional::new("path"))?,
             url: cli.check_option(Optional::new("url"))?,
@@ -122,13 +128,23 @@ impl Command<Context> for Install {
             }
             return Ok(());
         }
+        // command-line flags enable the modes regardless of the configuration
+        let locked = self.locked == true || c.is_locked() == true;
+        let offline = self.offline == true || c.is_offline() == true;
+
         // gather the catalog (all manifests)
         let mut catalog = Catalog::new()
             .installations(c.get_cache_path())?
-            .downloads(c.get_downloads_path())?;
+            .layers(c.get_cache_layers())?
+            .downloads(c.get_downloads_path(), &c.get_trust()?)?;
 
         // check if trying to download from the internet
         let target = if self.url.is_some() {
+            if offline == true {
+                return Err(AnyError(format!(
+                    "unable to install from a url while offline"
+                )))?;
+            }
             Self::download_target_from_url(&self, c, &self.url.as_ref().unwrap())?;
             None
         // check if trying to download from local filesystem
@@ -154,7 +170,7 @@ impl Command<Context> for Install {
                 Some(entry) => match search_path.exists() {
                     true => {
                         let ip = Ip::load(search_dir.to_path_buf(), true)?;
-                        if ip.get_man().get_ip().get_name() == entry.get_name()
+                        if entry.get_name().refers_to(ip.get_man().get_ip().get_name())
                             && (entry.get_version().is_latest()
                                 || version::is_compatible(
                                     entry.get_version().as_specific().unwrap(),
@@ -191,12 +207,12 @@ impl Command<Context> for Install {
         };
 
         // update the downloads
-        catalog = catalog.downloads(c.get_downloads_path())?;
+        catalog = catalog.downloads(c.get_downloads_path(), &c.get_trust()?)?;
 
         // use the catalog (if no path is provided)
         let target = if self.path.is_none() {
             if let Some(spec) = &self.ip {
-                if let Some(lvl) = catalog.inner().get(spec.get_name()) {
+                if let Some(lvl) = catalog.lookup(spec.get_name())? {
                     if let Some(slot) = lvl.get(true, spec.get_version()) {
                         if let Some(bytes) = slot.get_mapping().as_bytes() {
                             // println!("{} {}", "using archive", slot.get_man().get_ip().into_ip_spec());
@@ -303,10 +319,11 @@ impl Command<Context> for Install {
             && catalog.is_downloaded_slot(&download_slot.as_ref().unwrap())
             && self.force == true
         {
-            std::fs::remove_file(
-                &c.get_downloads_path()
-                    .join(&download_slot.as_ref().unwrap().as_ref()),
-            )?;
+            let archive = c
+                .get_downloads_path()
+                .join(&download_slot.as_ref().unwrap().as_ref());
+            std::fs::remove_file(&archive)?;
+            signature::remove_detached(&archive)?;
         }
 
         // @MARK: check for when there are multiple uuids that could potentially be for this ip
@@ -329,9 +346,13 @@ impl Command<Context> for Install {
                 &le,
                 &catalog,
                 &c.get_config().get_protocols(),
+                &c.get_config().get_network(),
+                c.get_signer()?.as_ref(),
+                offline,
+                c.get_download_jobs(),
             )?;
             // recollect the queued items to update the catalog
-            catalog = catalog.downloads(c.get_downloads_path())?;
+            catalog = catalog.downloads(c.get_downloads_path(), &c.get_trust()?)?;
 
             plan::install_missing_deps(&lf, &le, &catalog)?;
             // recollect the installations and queued items to update the catalog
@@ -341,9 +362,13 @@ impl Command<Context> for Install {
         // @MARK: may be an issue and should error if trying to install with an out-of-date lockfile
         // generate lock file if it is missing or out of date
         if target.lock_exists() == false || target.can_use_lock() == false {
+            // a frozen lockfile must already agree with the manifest
+            if locked == true {
+                return Err(LockError::OutOfDate)?;
+            }
             // build entire ip graph and resolve with dynamic symbol transformation
             let ip_graph = algo::compute_final_ip_graph(&target, &catalog, &c.get_lang_mode())?;
-            Plan::write_lockfile(&target, &ip_graph, true)?;
+            Plan::write_lockfile(&target, &ip_graph, true, locked)?;
         }
 
         // move the IP to the downloads folder if not already there
@@ -354,6 +379,9 @@ impl Command<Context> for Install {
                 &target.get_root(),
                 c.get_downloads_path(),
                 &target.get_man().get_ip().into_ip_spec(),
+                None,
+                None,
+                c.get_signer()?.as_ref(),
             )?;
         }
 
@@ -404,6 +432,8 @@ impl Install {
             None,
             c.get_downloads_path(),
             &protocols,
+            &c.get_config().get_network(),
+            c.get_signer()?.as_ref(),
             self.verbose,
             self.force,
         )?;
@@ -33,11 +33,24 @@ DESCRIPTION
     will follow the previously defined arguments.
     
     The subprocess will spawn from the current working ip's build directory.
+    
+    If a profile was previously used during the planning phase, or a profile is 
+    provided with '--profile', then its arguments are passed before any arguments
+    from the command-line and its environment variables are set. A plugin from a
+    profile provided with '--profile' is used instead of the planned plugin.
+    
+    Any 'pre-build' hooks defined in the current working ip's manifest and then
+    in the plugin run from the build directory before the subprocess, and any 
+    'post-build' hooks run after it succeeds. Hooks receive the same environment
+    variables as the subprocess. A failing hook stops the building phase.
 
 OPTIONS
     --plugin <name>
         Plugin to execute
 
+    --profile <name>
+        Profile to select the plugin, arguments, and environment
+
     --command <cmd>
         Command to execute
 
@@ -53,6 +66,12 @@ OPTIONS
     --verbose
         Display the command being executed
 
+    --locked
+        Fail if the lock file is out of date
+
+    --offline
+        Fail if a dependency is not installed
+
     args
         Arguments to pass to the plugin or command
 
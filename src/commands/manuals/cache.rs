@@ -0,0 +1,59 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    cache - manage the installed and downloaded ip
+
+SYNOPSIS
+    orbit cache <command> [options]
+
+DESCRIPTION
+    This command will manage the slots installed in the cache ('$ORBIT_CACHE') and
+    the archives stored in the downloads ('$ORBIT_DOWNLOADS'). These directories 
+    grow with every new version of an ip that is used, so older entries can be
+    removed once no project depends on them.
+    
+    The 'list' command displays every slot in the cache along with its size and
+    when it was last used. Use '--downloads' to display the archives in the 
+    downloads instead.
+    
+    Only the writable cache is managed; read-only cache layers are never listed or
+    modified.
+    
+    The 'gc' command removes the slots and archives that are no longer needed. An
+    entry is kept if it is referenced by any lockfile found within the project 
+    directories given by '--root'. The current ip is always included as a project 
+    root when the command is run from within one. Using '--older-than' restricts 
+    the removal to entries that have not been used for the given number of days. 
+    When both criteria are provided, an entry must meet both to be removed. 
+    
+    A slot is considered used each time it is part of a plan. For slots that have
+    never been planned and for archives, the time they were last modified is used
+    instead. To view what would be removed without deleting anything, use
+    '--dry-run'.
+
+OPTIONS
+    <command>
+        Cache operation: list, gc
+
+    list
+        Display all slots in the cache
+
+    --downloads
+        Display the archives in the downloads instead
+
+    gc
+        Remove unreferenced or unused slots and archives
+
+    --root <path>...
+        Keep entries referenced by lockfiles under the path
+
+    --older-than <days>
+        Only remove entries unused for this many days
+
+    --dry-run
+        Report the entries to remove without deleting them
+
+EXAMPLES
+    orbit cache list
+    orbit cache gc --root ~/projects --dry-run
+    orbit cache gc --older-than 90
+"#;
@@ -3,7 +3,7 @@ pub const MANUAL: &str = r#"NAME
     config - modify configuration values
 
 SYNOPSIS
-    orbit config [options]
+    orbit config [options] [<command>]
 
 DESCRIPTION
     This command will alter configuration entries in Orbit's settings file named
@@ -16,14 +16,65 @@ DESCRIPTION
     The command modifies the document in three independent stages. The first stage
     modifies the settings by iterating through all defined '--append' values. Then, 
     it will insert all '--set' values. Lastly, it will remove all '--unset' entries.
+    
+    Use '--list' to print every effective entry after combining all configuration
+    files, where the first file in the precedence order to define an entry
+    provides its value. Entries that combine across files, such as 'include' and
+    'vendor', are listed once for every file that defines them. Add '--show-origin'
+    to also print the file (or environment variable) that provided each entry. 
+    Using '--global' or '--local' with '--list' only lists the entries of that file.
+    
+    Any entry in the '[general]', '[http]', '[signing]', and '[vhdl-format]' tables
+    can be overridden with an environment variable named 'ORBIT_<TABLE>_<KEY>', 
+    where dashes are replaced by underscores (for example, 'ORBIT_GENERAL_BUILD_DIR'
+    overrides 'general.build-dir'). These variables take precedence over every
+    configuration file.
+    
+    The 'get' command prints the effective value of an entry. A string is printed
+    without quotes and any other value is printed as TOML. When the key refers to
+    a table, every entry within the table is printed along with its key. 
+    
+    The 'plugin' and 'protocol' commands add and remove entries of the '[[plugin]]'
+    and '[[protocol]]' arrays. The 'add' command appends a new entry named '<name>'
+    that runs '<command>' with any arguments after '--', and it errors if an entry
+    with the same name already exists in the file. The 'remove' command deletes the
+    entry named '<name>'. The rest of the file's formatting and comments are
+    preserved.
 
 OPTIONS
+    <command>
+        Configuration operation: get, plugin, protocol
+
     --global
         Access the home configuration file
 
     --local
         Access the current project's configuration file
 
+    --list
+        Print every effective configuration entry
+
+    --show-origin
+        Print where each listed entry was defined
+
+    get <key>
+        Print the effective value of the key
+
+    plugin add <name> <command> [--] [args]...
+        Add a plugin entry
+
+    plugin remove <name>
+        Remove a plugin entry
+
+    protocol add <name> <command> [--] [args]...
+        Add a protocol entry
+
+    protocol remove <name>
+        Remove a protocol entry
+
+    --description <text>
+        Set the description of an added entry
+
     --append <key=value>...
         Add a value to the key storing a list
 
@@ -36,4 +87,8 @@ OPTIONS
 EXAMPLES
     orbit config --append include="~/.orbit/profiles/ks-tech"
     orbit config --unset env.VIVADO_PATH --global
+    orbit config --list --show-origin
+    orbit config get general.build-dir
+    orbit config plugin add ghdl python --description "Simulate with GHDL" -- ./ghdl.py
+    orbit config protocol remove kstp --local
 "#;
@@ -33,6 +33,13 @@ DESCRIPTION
     
     This action may automatically run during an install if the package is missing
     from the downloads. See 'orbit help install' for more details.
+    
+    Multiple packages are fetched at the same time, up to the limit set by '--jobs'
+    or the "general.download-jobs" configuration field. A package that fails to
+    download does not stop the remaining packages. When using the default protocol,
+    the archive is streamed to a partial file in the downloads directory. Transient
+    network failures are retried with an increasing delay, and an interrupted
+    transfer resumes from the partial file on the next attempt.
 
 OPTIONS
     --list
@@ -53,6 +60,15 @@ OPTIONS
     --force
         Download selected packages regardless of status
 
+    --locked
+        Fail if the lock file is out of date
+
+    --offline
+        Fail if any package must be fetched from the internet
+
+    --jobs <n>
+        Set the maximum number of packages to fetch at the same time
+
 EXAMPLES
     orbit download --missing --list
     orbit download --all --force
@@ -11,13 +11,27 @@ DESCRIPTION
     By default, this command prins information as a shell script. If one or more
     variable names are given as arguments as '<key>', then it will print the value
     of each provided variables on its own line.
+    
+    Use '--export' to print the variables as statements that set them for the
+    chosen '<format>': 'sh', 'fish', 'powershell', or 'dotenv'. This includes the
+    variables from the configuration, the current working ip, and the build
+    directory's '.env' file, which makes the environment available to programs run
+    outside of 'orbit' when the output is evaluated by a shell. Values are quoted
+    to be read literally and variables without a value are skipped. If one or more 
+    '<key>' are given, only those variables are exported.
 
 OPTIONS
     <key>...
         Include this variable's value specifically in the environment information
 
+    --export <format>
+        Print the variables as statements to set them in the format
+
 EXAMPLES
     orbit env
     orbit env ORBIT_HOME
     orbit env ORBIT_CACHE ORBIT_DOWNLOADS
+    eval "$(orbit env --export sh)"
+    orbit env --export powershell | Invoke-Expression
+    orbit env --export dotenv > .env.local
 "#;
@@ -0,0 +1,24 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    inspect - look inside an ip archive
+
+SYNOPSIS
+    orbit inspect [options] <file>
+
+DESCRIPTION
+    This command prints the contents of a '.ip' archive without installing it, so
+    third-party ip can be audited before it is placed in the cache. It displays the
+    archive's ip, format version, signature status, the embedded manifest and
+    lockfile, the primary design units, and a listing of every file with its size.
+    
+    The signature status is determined from the trusted keys listed in the 
+    '[signing]' section of the configuration, regardless of the signing policy. The 
+    archive file is only read and never modified.
+
+OPTIONS
+    <file>
+        The path to the .ip archive to inspect
+
+EXAMPLES
+    orbit inspect ~/.orbit/downloads/gates-1.0.0-8a2f91c4.ip
+"#;
@@ -45,6 +45,12 @@ OPTIONS
     --all
         Install all dependencies (including development)
 
+    --locked
+        Fail if the lock file would be modified
+
+    --offline
+        Fail if a dependency must be fetched from the internet
+
 EXAMPLES
     orbit install
     orbit install lcd_driver:2.0
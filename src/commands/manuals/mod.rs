@@ -1,13 +1,16 @@
 pub mod build;
+pub mod cache;
 pub mod config;
 pub mod download;
 pub mod env;
 pub mod get;
 pub mod init;
+pub mod inspect;
 pub mod install;
 pub mod launch;
 pub mod new;
 pub mod orbit;
+pub mod outdated;
 pub mod plan;
 pub mod probe;
 pub mod read;
@@ -15,3 +18,7 @@ pub mod remove;
 pub mod search;
 pub mod show;
 pub mod tree;
+pub mod unpack;
+pub mod update;
+pub mod vendor;
+pub mod verify;
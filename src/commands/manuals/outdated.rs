@@ -0,0 +1,35 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    outdated - list dependencies with newer versions
+
+SYNOPSIS
+    orbit outdated [options]
+
+DESCRIPTION
+    This command will report the dependencies recorded in the current ip's lockfile
+    that have newer versions known to the catalog. The catalog is searched across 
+    the cache, downloads, and vendors.
+    
+    For each dependency, the locked version is compared against two versions. The
+    compatible version is the newest version that satisfies the version requirements
+    of every ip that depends on it. The latest version is the newest version known 
+    overall, which may require changes to a manifest before it can be used.
+    
+    By default, only dependencies with a newer compatible or latest version are 
+    displayed. To display every dependency, use '--all'. To print the report as json
+    data, use '--json'.
+    
+    A lockfile is required to exist in the current ip. Compatible versions can be
+    written to the lockfile with 'orbit update'.
+
+OPTIONS
+    --all
+        Include dependencies that are up to date
+
+    --json
+        Print the report as json data
+
+EXAMPLES
+    orbit outdated
+    orbit outdated --all --json
+"#;
@@ -48,9 +48,33 @@ DESCRIPTION
     dependencies if necessary. To only download an ip, see the 'download' command.
     To only install an ip, see the 'install' command.
     
+    A profile selected with '--profile' fills in the top level, testbench, 
+    plugin, and filesets that are not set on the command-line. Filesets from
+    the command-line replace the profile's filesets of the same name. Profiles
+    are defined under '[profile.<name>]' in a configuration file or the manifest.
+    The selected profile and its environment variables are stored in the '.env'
+    file to be recalled during the build phase.
+    
+    Dependencies are written into the blueprint under the library declared in
+    their own manifest by default. The '[libraries]' section of the current
+    working ip's manifest can instead derive each dependency's library from its
+    name, place every dependency into a single library, or map a specific
+    dependency to a chosen library. A warning is shown when different ips share
+    the same library.
+    
+    Any 'pre-plan' hooks defined in the current working ip's manifest and then in
+    the selected plugin run from the ip's root directory before planning. Any
+    'post-plan' hooks run after the blueprint is created. A failing hook stops
+    the planning phase.
+    
     If an installed dependency's computed checksum does not match the checksum
     stored in the lockfile, it assumes the installation to be corrupt and will 
     re-install the dependency to the cache.
+    
+    For reproducible builds, use '--locked' to exit with an error instead of
+    modifying the lockfile, and use '--offline' to exit with an error instead of
+    fetching a missing dependency from the internet. Both modes can also be enabled
+    with the 'locked' and 'offline' fields in the '[general]' configuration table.
 
 OPTIONS
     --top <unit>
@@ -62,6 +86,9 @@ OPTIONS
     --plugin <name>
         A plugin to refer to gather its declared filesets
 
+    --profile <name>
+        A profile to fill in the unset options
+
     --build-dir <dir>
         The relative directory to place the blueprint.tsv file
 
@@ -83,9 +110,16 @@ OPTIONS
     --all
         Include all locally found HDL files
 
+    --locked
+        Fail if the lock file would be modified
+
+    --offline
+        Fail if a dependency must be fetched from the internet
+
 EXAMPLES
     orbit plan --bench my_tb
     orbit plan --top and_gate --fileset PIN-PLAN="*.board"
     orbit plan --plugin vivado --clean --bench ram_tb
     orbit plan --lock-only
+    orbit plan --profile sim
 "#;
@@ -7,10 +7,10 @@ SYNOPSIS
 
 DESCRIPTION
     This command will display a list of all the known ip in the catalog. The catalog
-    consists of 3 levels: cache, downloads, and channels.
+    consists of 3 levels: cache, downloads, and vendors.
     
     Any ip at the cache level are considered installed. Any ip at the downloads
-    level are considered downloaded. Any ip at the channels level is considered
+    level are considered downloaded. Any ip tracked by a vendor is considered
     available. An ip does not exist in the catalog if it is not found at any one
     of the three defined levels.
     
@@ -29,6 +29,9 @@ OPTIONS
     --download, -d
         Filter ip downloaded to the downloads
 
+    --available, -a
+        Filter ip available from vendors
+
     --keyword <term>...
         Include ip that contain this keyword
 
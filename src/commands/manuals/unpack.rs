@@ -0,0 +1,25 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    unpack - extract an ip archive into a directory
+
+SYNOPSIS
+    orbit unpack [options] <file> <dir>
+
+DESCRIPTION
+    This command extracts the project stored within a '.ip' archive into the
+    directory '<dir>', without installing it. This allows the source code of 
+    third-party ip to be reviewed before it is placed in the cache.
+    
+    The directory is created if it does not exist. An existing directory must be 
+    empty. The archive file is only read and never modified.
+
+OPTIONS
+    <file>
+        The path to the .ip archive to extract
+
+    <dir>
+        The directory to place the project's files
+
+EXAMPLES
+    orbit unpack ~/.orbit/downloads/gates-1.0.0-8a2f91c4.ip ./gates
+"#;
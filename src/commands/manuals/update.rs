@@ -0,0 +1,37 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    update - refresh the lockfile within manifest constraints
+
+SYNOPSIS
+    orbit update [options] [<ip>...]
+
+DESCRIPTION
+    This command will resolve the dependencies of the current ip again and rewrite
+    its lockfile. Each dependency is moved to the highest version found among the
+    installations and downloads that still satisfies the version written in the
+    manifest. A manifest dependency may be a partial version, such as "1" or "1.2",
+    to allow newer versions within those constraints.
+    
+    When one or more '<ip>' are provided, only those dependencies are re-resolved.
+    Every other dependency keeps the version already recorded in the lockfile as 
+    long as it still satisfies its manifest requirement.
+    
+    The changes between the old and new lockfile are displayed as a list of ip 
+    specifications. To view the changes without modifying the lockfile, use 
+    '--dry-run'.
+    
+    Any selected ip that only exists in the downloads is installed to the cache in 
+    order to record its checksum in the lockfile.
+
+OPTIONS
+    <ip>...
+        The names of the dependencies to update
+
+    --dry-run
+        Display the changes without writing the lockfile
+
+EXAMPLES
+    orbit update
+    orbit update gates mux
+    orbit update --dry-run
+"#;
@@ -0,0 +1,46 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    vendor - manage ip registries
+
+SYNOPSIS
+    orbit vendor <command> [options]
+
+DESCRIPTION
+    This command will manage the vendors known to Orbit. A vendor is a directory
+    with an 'index.toml' file at its root that tracks a collection of ip by storing
+    copies of their manifests. Every file matching 'Orbit-*.toml' within a vendor's
+    directory is considered a tracked manifest.
+    
+    Vendors are read from the paths listed in the 'vendor' entry of the 
+    configuration files and from any directories within '$ORBIT_HOME/vendor' that
+    contain an 'index.toml' file. Any ip tracked by a vendor is available in the
+    catalog, even if it has not been downloaded or installed.
+    
+    The 'add' command verifies the directory is a vendor and appends its path to
+    the global configuration file.
+    
+    The 'refresh' command updates vendors that are git repositories by pulling the
+    latest changes from their remote. When '<name>' is omitted, every known vendor
+    is refreshed.
+    
+    The 'list' command displays the known vendors along with the number of ip each 
+    vendor tracks.
+
+OPTIONS
+    <command>
+        Vendor operation: add, refresh, list
+
+    add <path>
+        Register the directory at path as a vendor
+
+    refresh [<name>]
+        Pull the latest changes for a vendor
+
+    list
+        Display all known vendors
+
+EXAMPLES
+    orbit vendor add ~/.orbit/vendor/ks-tech
+    orbit vendor refresh ks-tech
+    orbit vendor list
+"#;
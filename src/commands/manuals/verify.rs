@@ -0,0 +1,28 @@
+// This manual page was automatically generated from the mangen.py tool.
+pub const MANUAL: &str = r#"NAME
+    verify - audit the installations recorded in the lockfile
+
+SYNOPSIS
+    orbit verify [options]
+
+DESCRIPTION
+    This command will audit the integrity of every dependency recorded in the 
+    current ip's lockfile. For each entry, it checks that the entry's slot exists 
+    in the cache, recomputes the checksum of the installed files and compares it 
+    against the checksum stored in the lockfile, and detects a missing or tampered
+    '.orbit-checksum' file within the installation.
+    
+    Any issue found is reported and the command exits with an error. To reinstall
+    the affected ip from the downloads, use '--repair'. An ip that is missing from 
+    the downloads cannot be repaired; try running 'orbit download' first.
+    
+    A lockfile is required to exist in the current ip.
+
+OPTIONS
+    --repair
+        Reinstall ip that fail verification from the downloads
+
+EXAMPLES
+    orbit verify
+    orbit verify --repair
+"#;
@@ -3,21 +3,28 @@ pub mod orbit;
 
 // commands
 mod build;
+mod cache;
 mod config;
 mod download;
 mod env;
 mod get;
 mod help;
 mod init;
+mod inspect;
 mod install;
 mod launch;
 mod new;
+mod outdated;
 mod plan;
 mod read;
 mod remove;
 mod search;
 mod show;
 mod tree;
+mod unpack;
+mod update;
+mod vendor;
+mod verify;
 
 // informational content for help about commands
 mod helps;
@@ -54,6 +54,7 @@ impl Orbit {
                 .downloads(environment::ORBIT_DOWNLOADS)?
                 .current_ip_dir(environment::ORBIT_IP_PATH)? // must come before .settings() call
                 .settings(config::CONFIG_FILE)?
+                .cache_layers(environment::ORBIT_CACHE_PATH)? // must come after .settings() call
                 .build_dir(environment::ORBIT_BUILD_DIR)?;
             // pass the context to the given command
             c.exec(&context)
@@ -96,21 +97,28 @@ impl FromCli for Orbit {
 }
 
 use crate::commands::build::Build;
+use crate::commands::cache::Cache;
 use crate::commands::config::Config;
 use crate::commands::download::Download;
 use crate::commands::env::Env;
 use crate::commands::get::Get;
 use crate::commands::help::Help;
 use crate::commands::init::Init;
+use crate::commands::inspect::Inspect;
 use crate::commands::install::Install;
 use crate::commands::launch::Launch;
 use crate::commands::new::New;
+use crate::commands::outdated::Outdated;
 use crate::commands::plan::Plan;
 use crate::commands::read::Read;
 use crate::commands::remove::Remove;
 use crate::commands::search::Search;
 use crate::commands::show::Show;
 use crate::commands::tree::Tree;
+use crate::commands::unpack::Unpack;
+use crate::commands::update::Update;
+use crate::commands::vendor::Vendor;
+use crate::commands::verify::Verify;
 
 #[derive(Debug, PartialEq)]
 enum OrbitSubcommand {
@@ -130,6 +138,13 @@ enum OrbitSubcommand {
     Uninstall(Remove),
     Read(Read),
     Download(Download),
+    Vendor(Vendor),
+    Update(Update),
+    Outdated(Outdated),
+    Verify(Verify),
+    Inspect(Inspect),
+    Unpack(Unpack),
+    Cache(Cache),
 }
 
 impl FromCli for OrbitSubcommand {
@@ -137,7 +152,8 @@ impl FromCli for OrbitSubcommand {
         match cli
             .match_command(&[
                 "help", "new", "search", "plan", "p", "build", "launch", "download", "install",
-                "get", "init", "tree", "show", "b", "env", "config", "remove", "read",
+                "get", "init", "tree", "show", "b", "env", "config", "remove", "read", "vendor",
+                "update", "outdated", "verify", "inspect", "unpack", "cache",
             ])?
             .as_ref()
         {
@@ -157,6 +173,13 @@ impl FromCli for OrbitSubcommand {
             "config" => Ok(OrbitSubcommand::Config(Config::from_cli(cli)?)),
             "remove" => Ok(OrbitSubcommand::Uninstall(Remove::from_cli(cli)?)),
             "read" => Ok(OrbitSubcommand::Read(Read::from_cli(cli)?)),
+            "vendor" => Ok(OrbitSubcommand::Vendor(Vendor::from_cli(cli)?)),
+            "update" => Ok(OrbitSubcommand::Update(Update::from_cli(cli)?)),
+            "outdated" => Ok(OrbitSubcommand::Outdated(Outdated::from_cli(cli)?)),
+            "verify" => Ok(OrbitSubcommand::Verify(Verify::from_cli(cli)?)),
+            "inspect" => Ok(OrbitSubcommand::Inspect(Inspect::from_cli(cli)?)),
+            "unpack" => Ok(OrbitSubcommand::Unpack(Unpack::from_cli(cli)?)),
+            "cache" => Ok(OrbitSubcommand::Cache(Cache::from_cli(cli)?)),
             _ => panic!("an unimplemented command was passed through!"),
         }
     }
@@ -192,6 +215,13 @@ impl Command<Context> for OrbitSubcommand {
             OrbitSubcommand::Uninstall(c) => c.exec(context),
             OrbitSubcommand::Read(c) => c.exec(context),
             OrbitSubcommand::Download(c) => c.exec(context),
+            OrbitSubcommand::Vendor(c) => c.exec(context),
+            OrbitSubcommand::Update(c) => c.exec(context),
+            OrbitSubcommand::Outdated(c) => c.exec(context),
+            OrbitSubcommand::Verify(c) => c.exec(context),
+            OrbitSubcommand::Inspect(c) => c.exec(context),
+            OrbitSubcommand::Unpack(c) => c.exec(context),
+            OrbitSubcommand::Cache(c) => c.exec(context),
         }
     }
 }
@@ -0,0 +1,216 @@
+use crate::commands::helps::outdated;
+use crate::core::algo;
+use crate::core::catalog::Catalog;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::lockfile::LockFile;
+use crate::core::pkgid::PkgPart;
+use crate::core::version::{self, AnyVersion, Version};
+use crate::util::anyerror::{AnyError, Fault};
+use crate::OrbitResult;
+use clif::arg::Flag;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use serde_derive::Serialize;
+
+#[derive(Debug, PartialEq)]
+pub struct Outdated {
+    all: bool,
+    json: bool,
+}
+
+impl FromCli for Outdated {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(outdated::HELP).ref_usage(2..4))?;
+        let command = Ok(Outdated {
+            all: cli.check_flag(Flag::new("all"))?,
+            json: cli.check_flag(Flag::new("json"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Outdated {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+
+        // create the ip manifest
+        let target = Ip::load(c.get_ip_path().unwrap().clone(), true)?;
+
+        // verify a lockfile exists
+        if target.get_lock().is_empty() == true {
+            return Err(AnyError(format!(
+                "missing lockfile; try running `orbit plan --lock-only` to create one"
+            )))?;
+        }
+
+        // gather the catalog
+        let catalog = Catalog::new()
+            .installations(c.get_cache_path())?
+            .layers(c.get_cache_layers())?
+            .downloads(c.get_downloads_path(), &c.get_trust()?)?
+            .available(&c.get_vendors()?)?;
+
+        let records: Vec<Record> = Self::collect(target.get_lock(), &catalog)?
+            .into_iter()
+            .filter(|r| self.all == true || r.is_outdated() == true)
+            .collect();
+
+        match self.json {
+            true => println!("{}", serde_json::to_string_pretty(&records)?),
+            false => println!("{}", Self::fmt_table(&records)),
+        }
+        Ok(())
+    }
+}
+
+impl Outdated {
+    /// Compares every dependency in the `lock` against the versions known to
+    /// the `catalog`.
+    ///
+    /// The newest compatible version must satisfy the version requirements of
+    /// each ip that depends on the entry.
+    fn collect(lock: &LockFile, catalog: &Catalog) -> Result<Vec<Record>, Fault> {
+        let graph = algo::graph_ip_from_lock(lock)?;
+
+        let mut records = Vec::new();
+        for node in graph.get_map().values() {
+            let entry = node.as_ref();
+            // skip the current working ip
+            if entry.get_sum().is_none() == true {
+                continue;
+            }
+            // gather the version requirements from every dependent ip
+            let reqs: Vec<&AnyVersion> = graph
+                .get_graph()
+                .successors(node.index())
+                .filter_map(|i| graph.get_node_by_index(i))
+                .flat_map(|upper| {
+                    upper
+                        .as_ref()
+                        .get_deps()
+                        .iter()
+                        .filter(|d| d.get_name().refers_to(entry.get_name()))
+                        .map(|d| d.get_version())
+                })
+                .collect();
+            // versions are ordered from highest to lowest
+            let versions = catalog
+                .get_possible_versions(entry.get_name())
+                .unwrap_or(Vec::new());
+            let compatible = versions
+                .iter()
+                .map(|v| v.get_version())
+                .find(|&v| {
+                    reqs.iter().all(|r| match r {
+                        AnyVersion::Specific(pv) => version::is_compatible(pv, v),
+                        AnyVersion::Latest => true,
+                    })
+                })
+                .cloned();
+            let latest = versions.first().map(|v| v.get_version().clone());
+
+            records.push(Record {
+                name: entry.get_name().clone(),
+                locked: entry.get_version().clone(),
+                compatible,
+                latest,
+            });
+        }
+        // sort by name and then version
+        records.sort_by(|x, y| match x.name.cmp(&y.name) {
+            std::cmp::Ordering::Equal => x.locked.cmp(&y.locked),
+            ord => ord,
+        });
+        Ok(records)
+    }
+
+    fn fmt_table(records: &Vec<Record>) -> String {
+        let header = format!(
+            "\
+{:<28}{:<12}{:<12}{}
+{4:->28}{4:->12}{4:->12}{4:->12}\n",
+            "Ip", "Locked", "Compatible", "Latest", " "
+        );
+        let mut body = String::new();
+        for r in records {
+            body.push_str(&format!(
+                "{:<28}{:<12}{:<12}{}\n",
+                r.name.to_string(),
+                r.locked.to_string(),
+                Record::fmt_version(&r.compatible),
+                Record::fmt_version(&r.latest),
+            ));
+        }
+        header + &body
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Record {
+    name: PkgPart,
+    locked: Version,
+    compatible: Option<Version>,
+    latest: Option<Version>,
+}
+
+impl Record {
+    /// Checks if a newer version exists beyond the locked version.
+    fn is_outdated(&self) -> bool {
+        self.compatible.as_ref().map_or(false, |v| v > &self.locked)
+            || self.latest.as_ref().map_or(false, |v| v > &self.locked)
+    }
+
+    fn fmt_version(v: &Option<Version>) -> String {
+        match v {
+            Some(v) => v.to_string(),
+            None => String::from("-"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    fn record(name: &str, locked: &str, compatible: &str, latest: &str) -> Record {
+        Record {
+            name: PkgPart::from_str(name).unwrap(),
+            locked: Version::from_str(locked).unwrap(),
+            compatible: Some(Version::from_str(compatible).unwrap()),
+            latest: Some(Version::from_str(latest).unwrap()),
+        }
+    }
+
+    #[test]
+    fn is_outdated() {
+        assert_eq!(
+            record("gates", "1.0.0", "1.0.0", "1.0.0").is_outdated(),
+            false
+        );
+        assert_eq!(
+            record("gates", "1.0.0", "1.0.0", "2.0.0").is_outdated(),
+            true
+        );
+        assert_eq!(
+            record("gates", "1.0.0", "1.4.0", "2.0.0").is_outdated(),
+            true
+        );
+    }
+
+    #[test]
+    fn fmt_table() {
+        let t = Outdated::fmt_table(&vec![record("gates", "1.0.0", "1.4.0", "2.0.0")]);
+        let table = "\
+Ip                          Locked      Compatible  Latest
+--------------------------- ----------- ----------- ----------- 
+gates                       1.0.0       1.4.0       2.0.0
+";
+        assert_eq!(t, table);
+    }
+}
@@ -5,6 +5,9 @@ use clif::cmd::{Command, FromCli};
 use crate::commands::download::Download;
 use crate::core::context::Context;
 use crate::core::fileset::Fileset;
+use crate::core::fileset::Scope;
+use crate::core::hook;
+use crate::core::hook::Stage;
 use crate::core::iparchive::IpArchive;
 use crate::core::lang::parser::ParseError;
 use crate::core::lang::vhdl::subunit::SubUnit;
@@ -16,6 +19,8 @@ use crate::core::lang::vhdl::token::Identifier;
 use crate::core::lang::LangMode;
 use crate::core::plugin::Plugin;
 use crate::core::plugin::PluginError;
+use crate::core::profile;
+use crate::core::profile::Profile;
 use crate::core::variable;
 use crate::core::variable::VariableTable;
 use crate::core::version::AnyVersion;
@@ -35,6 +40,7 @@ use std::fs::File;
 use std::hash::Hash;
 use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use crate::commands::helps::plan;
 use crate::commands::install::Install;
@@ -42,18 +48,24 @@ use crate::core::algo;
 use crate::core::algo::IpFileNode;
 use crate::core::algo::IpNode;
 use crate::core::catalog::Catalog;
+use crate::core::config::Network;
 use crate::core::ip::Ip;
 use crate::core::ip::IpSpec;
 use crate::core::lockfile::LockEntry;
+use crate::core::lockfile::LockError;
 use crate::core::lockfile::LockFile;
+use crate::core::manifest::LibraryPolicy;
+use crate::core::signature::Signer;
+use crate::core::source::Source;
 use crate::util::graphmap::Node;
 
 pub const BLUEPRINT_FILE: &str = "blueprint.tsv";
 pub const BLUEPRINT_DELIMITER: &str = "\t";
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Plan {
     plugin: Option<String>,
+    profile: Option<String>,
     bench: Option<Identifier>,
     top: Option<Identifier>,
     clean: bool,
@@ -63,6 +75,8 @@ pub struct Plan {
     filesets: Option<Vec<Fileset>>,
     only_lock: bool,
     force: bool,
+    locked: bool,
+    offline: bool,
 }
 
 impl FromCli for Plan {
@@ -72,6 +86,8 @@ impl FromCli for Plan {
             // flags
             force: cli.check_flag(Flag::new("force"))?,
             only_lock: cli.check_flag(Flag::new("lock-only"))?,
+            locked: cli.check_flag(Flag::new("locked"))?,
+            offline: cli.check_flag(Flag::new("offline"))?,
             all: cli.check_flag(Flag::new("all"))?,
             clean: cli.check_flag(Flag::new("clean"))?,
             list: cli.check_flag(Flag::new("list"))?,
@@ -79,6 +95,7 @@ impl FromCli for Plan {
             top: cli.check_option(Optional::new("top").value("unit"))?,
             bench: cli.check_option(Optional::new("bench").value("tb"))?,
             plugin: cli.check_option(Optional::new("plugin").value("name"))?,
+            profile: cli.check_option(Optional::new("profile").value("name"))?,
             build_dir: cli.check_option(Optional::new("build-dir").value("dir"))?,
             filesets: cli.check_option_all(Optional::new("fileset").value("key=glob"))?,
         });
@@ -127,16 +144,45 @@ impl Command<Context> for Plan {
         // create the ip manifest
         let target = Ip::load(c.get_ip_path().unwrap().clone(), true)?;
 
+        // fill in any options not set on the command-line from the selected profile
+        let profile = match &self.profile {
+            Some(name) => Some(profile::find(name, c.get_config(), target.get_man())?),
+            None => None,
+        };
+        let plan = match profile {
+            Some(p) => self.apply_profile(p)?,
+            None => self.clone(),
+        };
+        // locate the plugin if the profile selected one
+        let plugin = match (plugin, &plan.plugin) {
+            (Some(p), _) => Some(p),
+            (None, Some(alias)) => match c.get_config().get_plugins().get(alias.as_str()) {
+                Some(&p) => Some(p),
+                None => return Err(PluginError::Missing(alias.to_string()))?,
+            },
+            (None, None) => None,
+        };
+
+        // command-line flags enable the modes regardless of the configuration
+        let locked = self.locked == true || c.is_locked() == true;
+        let offline = self.offline == true || c.is_offline() == true;
+
+        // a frozen lockfile must already agree with the manifest
+        if locked == true && target.can_use_lock() == false {
+            return Err(LockError::OutOfDate)?;
+        }
+
         // gather the catalog
         let mut catalog = Catalog::new()
             .installations(c.get_cache_path())?
-            .downloads(c.get_downloads_path())?;
+            .layers(c.get_cache_layers())?
+            .downloads(c.get_downloads_path(), &c.get_trust()?)?;
 
         // @todo: recreate the ip graph from the lockfile, then read each installation
         // see Install::install_from_lock_file
 
         // this code is only ran if the lock file matches the manifest and we aren't force to recompute
-        if target.can_use_lock() == true && self.force == false {
+        if target.can_use_lock() == true && plan.force == false {
             let le: LockEntry = LockEntry::from((&target, true));
             let lf = target.get_lock();
 
@@ -145,9 +191,19 @@ impl Command<Context> for Plan {
                 .from_config(c.get_config())?;
             let vtable = VariableTable::new().load_environment(&env)?;
 
-            download_missing_deps(vtable, &lf, &le, &catalog, &c.get_config().get_protocols())?;
+            download_missing_deps(
+                vtable,
+                &lf,
+                &le,
+                &catalog,
+                &c.get_config().get_protocols(),
+                &c.get_config().get_network(),
+                c.get_signer()?.as_ref(),
+                offline,
+                c.get_download_jobs(),
+            )?;
             // recollect the downloaded items to update the catalog for installations
-            catalog = catalog.downloads(c.get_downloads_path())?;
+            catalog = catalog.downloads(c.get_downloads_path(), &c.get_trust()?)?;
 
             install_missing_deps(&lf, &le, &catalog)?;
             // recollect the installations to update the catalog for dependency graphing
@@ -156,12 +212,60 @@ impl Command<Context> for Plan {
 
         // determine the build directory (command-line arg overrides configuration setting)
         let default_build_dir = c.get_build_dir();
-        let b_dir = match &self.build_dir {
+        let b_dir = match &plan.build_dir {
             Some(dir) => dir,
             None => &default_build_dir,
         };
 
-        self.run(target, b_dir, plugin, catalog, &c.get_lang_mode())
+        // set the environment for the hooks to run with
+        let env = Environment::new()
+            // read config.toml for setting any env variables
+            .from_config(c.get_config())?
+            // read ip manifest for env variables
+            .from_ip(&target)?
+            .add(EnvVar::new().key(environment::ORBIT_BUILD_DIR).value(b_dir));
+        let mut env = env;
+        if let Some(p) = profile {
+            p.get_env().into_iter().for_each(|e| {
+                env.insert(e);
+            });
+        }
+        let vtable = VariableTable::new().load_environment(&env)?;
+        env.initialize();
+
+        hook::run_hooks(Stage::PrePlan, &target, plugin, &vtable, false, ".")?;
+
+        plan.run(&target, b_dir, plugin, catalog, &c.get_lang_mode(), locked)?;
+
+        // no blueprint is created when only updating the lockfile
+        if plan.only_lock == true {
+            return Ok(());
+        }
+
+        // record the profile in the build directory's .env file
+        if let (Some(name), Some(p)) = (&self.profile, profile) {
+            let build_path = target.get_root().join(b_dir);
+            let mut envs = Environment::new().from_env_file(&build_path)?;
+            envs.insert(EnvVar::new().key(environment::ORBIT_PROFILE).value(name));
+            p.get_env().into_iter().for_each(|e| {
+                envs.insert(e);
+            });
+            environment::save_environment(&envs, &build_path)?;
+        }
+
+        // load the variables written to the .env file during planning
+        let envs = Environment::new()
+            .add(
+                EnvVar::new()
+                    .key(environment::ORBIT_BLUEPRINT)
+                    .value(BLUEPRINT_FILE),
+            )
+            .from_env_file(&target.get_root().join(b_dir))?;
+        let vtable = vtable.load_environment(&envs)?;
+        envs.initialize();
+
+        hook::run_hooks(Stage::PostPlan, &target, plugin, &vtable, false, ".")?;
+        Ok(())
     }
 }
 
@@ -171,9 +275,13 @@ pub fn download_missing_deps(
     le: &LockEntry,
     catalog: &Catalog,
     protocols: &ProtocolMap,
+    network: &Network,
+    signer: Option<&Signer>,
+    offline: bool,
+    jobs: usize,
 ) -> Result<(), Fault> {
-    let mut vtable = vtable;
-    // fetch all non-downloaded packages
+    let mut downloads: Vec<(IpSpec, Source)> = Vec::new();
+    // collect all non-downloaded packages
     for entry in lf.inner() {
         // skip the current project's IP entry or any IP already in the downloads/
         if entry.matches_target(le) == true
@@ -186,7 +294,7 @@ pub fn download_missing_deps(
 
         let mut require_download = false;
 
-        match catalog.inner().get(entry.get_name()) {
+        match catalog.lookup(entry.get_name())? {
             Some(status) => {
                 match status.get_install(&ver) {
                     Some(dep) => {
@@ -218,20 +326,14 @@ pub fn download_missing_deps(
         }
         // check if the slot is not already filled before trying to download
         if require_download == true {
-            match entry.get_source() {
-                Some(src) => {
-                    // fetch from the internet
-                    Download::download(
-                        &mut vtable,
-                        &entry.to_ip_spec(),
-                        src,
-                        None,
-                        catalog.get_downloads_path(),
-                        &protocols,
-                        false,
-                        true,
-                    )?;
-                }
+            if offline == true {
+                return Err(AnyError(format!(
+                    "unable to fetch IP {} from the internet while offline",
+                    entry.to_ip_spec()
+                )))?;
+            }
+            match entry.get_pinned_source() {
+                Some(src) => downloads.push((entry.to_ip_spec(), src)),
                 None => {
                     return Err(AnyError(format!(
                         "unable to fetch IP {} from the internet due to missing source",
@@ -241,6 +343,21 @@ pub fn download_missing_deps(
             }
         }
     }
+    // fetch from the internet
+    if downloads.is_empty() == false {
+        Download::download_all(
+            &downloads,
+            &protocols,
+            network,
+            signer,
+            vtable,
+            false,
+            None,
+            catalog.get_downloads_path(),
+            true,
+            jobs,
+        )?;
+    }
     Ok(())
 }
 
@@ -255,7 +372,7 @@ pub fn install_missing_deps(lf: &LockFile, le: &LockEntry, catalog: &Catalog) ->
         let ver = AnyVersion::Specific(entry.get_version().to_partial_version());
 
         // try to use the lock file to fill in missing pieces
-        match catalog.inner().get(entry.get_name()) {
+        match catalog.lookup(entry.get_name())? {
             Some(status) => {
                 // find this IP to read its dependencies
                 match status.get_install(&ver) {
@@ -304,7 +421,7 @@ pub fn install_missing_deps(lf: &LockFile, le: &LockEntry, catalog: &Catalog) ->
     Ok(())
 }
 
-fn install_ip_from_downloads(dep: &Ip, catalog: &Catalog, force: bool) -> Result<(), Fault> {
+pub fn install_ip_from_downloads(dep: &Ip, catalog: &Catalog, force: bool) -> Result<(), Fault> {
     // perform extra work if the Ip is virtual (from downloads)
     if let Some(bytes) = dep.get_mapping().as_bytes() {
         // place the dependency into a temporary directory
@@ -345,6 +462,163 @@ use crate::core::lang::node::HdlNode;
 use crate::core::lang::node::SubUnitNode;
 
 impl Plan {
+    /// Creates a copy of the plan with any options not set on the command-line
+    /// taken from the `profile`.
+    ///
+    /// Filesets from the command-line replace the profile's filesets of the same name.
+    fn apply_profile(&self, profile: &Profile) -> Result<Self, Fault> {
+        let mut plan = self.clone();
+        if plan.top.is_none() == true {
+            plan.top = profile.get_top()?;
+        }
+        if plan.bench.is_none() == true {
+            plan.bench = profile.get_bench()?;
+        }
+        if plan.plugin.is_none() == true {
+            plan.plugin = profile.get_plugin().cloned();
+        }
+        let mut fsets = plan.filesets.take().unwrap_or(Vec::new());
+        for fset in profile.get_filesets()? {
+            if fsets.iter().any(|f| f.get_name() == fset.get_name()) == false {
+                fsets.push(fset);
+            }
+        }
+        plan.filesets = match fsets.is_empty() {
+            true => None,
+            false => Some(fsets),
+        };
+        Ok(plan)
+    }
+
+    /// Assigns the HDL library for each dependency in the `ip_graph` according to
+    /// the `target` ip's library policy.
+    ///
+    /// Warns when multiple ips are assigned the same library, other than the
+    /// default "work" library or the library chosen by the single policy.
+    fn assign_libraries<'a>(
+        ip_graph: &'a GraphMap<IpSpec, IpNode<'a>, ()>,
+        target: &Ip,
+    ) -> HashMap<&'a PathBuf, Identifier> {
+        let libs = target.get_man().get_libraries();
+        let mut assigned = HashMap::new();
+        // track which ips are placed into each library
+        let mut users: HashMap<Identifier, Vec<String>> = HashMap::new();
+        users.insert(
+            target.get_man().get_hdl_library(),
+            vec![target.get_man().get_ip().get_name().to_string()],
+        );
+        ip_graph.get_map().iter().for_each(|(_, node)| {
+            let ip = node.as_ref().as_ip();
+            if ip == target {
+                return;
+            }
+            let lib = libs.assign(ip.get_man());
+            let name = ip.get_man().get_ip().get_name().to_string();
+            let entry = users.entry(lib.clone()).or_default();
+            if entry.contains(&name) == false {
+                entry.push(name);
+            }
+            assigned.insert(ip.get_root(), lib);
+        });
+        // placing every dependency into one library is intentional
+        let single = match libs.get_policy() {
+            LibraryPolicy::Single => Some(libs.get_single_library()),
+            _ => None,
+        };
+        // ips without a declared library share "work" by default
+        let working = Identifier::new_working();
+        let mut collisions: Vec<(String, Vec<String>)> = users
+            .into_iter()
+            .filter(|(lib, names)| {
+                names.len() > 1 && lib != &working && Some(lib) != single.as_ref()
+            })
+            .map(|(lib, mut names)| {
+                names.sort();
+                (lib.to_string(), names)
+            })
+            .collect();
+        collisions.sort();
+        for (lib, names) in collisions {
+            println!(
+                "{} library '{}' is shared by ips: {}",
+                "warning:".yellow(),
+                lib,
+                names.join(", ")
+            );
+        }
+        assigned
+    }
+
+    /// Collects the libraries that the library policy moved every ip out of,
+    /// mapped to the name of an ip that was moved.
+    ///
+    /// The "work" library is never collected since it always refers to the
+    /// library of the file using it.
+    fn moved_libraries<'a>(
+        ip_graph: &'a GraphMap<IpSpec, IpNode<'a>, ()>,
+        target: &Ip,
+        assigned: &HashMap<&'a PathBuf, Identifier>,
+    ) -> HashMap<Identifier, String> {
+        let mut remaining: HashSet<&Identifier> = assigned.values().collect();
+        let target_lib = target.get_man().get_hdl_library();
+        remaining.insert(&target_lib);
+        let working = Identifier::new_working();
+        ip_graph
+            .get_map()
+            .iter()
+            .map(|(_, node)| node.as_ref())
+            .filter(|node| node.get_library() != &working)
+            .filter(|node| remaining.contains(node.get_library()) == false)
+            .map(|node| {
+                (
+                    node.get_library().clone(),
+                    node.as_ip().get_man().get_ip().get_name().to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// Errors if any of the `files` refers by name to a library in `moved`,
+    /// since the sources would reference a library that no longer exists.
+    fn check_moved_libraries(
+        files: &Vec<IpFileNode>,
+        moved: &HashMap<Identifier, String>,
+    ) -> Result<(), Fault> {
+        if moved.is_empty() == true {
+            return Ok(());
+        }
+        for source_file in files {
+            if fileset::is_vhdl(&source_file.get_file()) == false {
+                continue;
+            }
+            let contents = fs::read_to_string(&source_file.get_file())?;
+            // files that fail to parse are reported when building the graph
+            let symbols = match VHDLParser::read(&contents) {
+                Ok(s) => s.into_symbols(),
+                Err(_) => continue,
+            };
+            for sym in &symbols {
+                let mut refs: Vec<&CompoundIdentifier> = sym.get_refs().iter().collect();
+                if let Some(arch) = sym.as_architecture() {
+                    refs.extend(arch.edges().iter());
+                }
+                if let Some(lib) = refs
+                    .into_iter()
+                    .filter_map(|r| r.get_prefix())
+                    .find(|p| moved.contains_key(p))
+                {
+                    return Err(AnyError(format!(
+                        "file {} refers to library '{}', but the library policy places ip {} into a different library\n\nKeep the ip's library by adding it to the `map` of the [libraries] table",
+                        source_file.get_file(),
+                        lib,
+                        moved.get(lib).unwrap()
+                    )))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Builds a graph of design units. Used for planning.
     fn build_full_graph<'a>(
         files: &'a Vec<IpFileNode>,
@@ -507,10 +781,12 @@ impl Plan {
 
     /// Writes the lockfile according to the constructed `ip_graph`. Only writes if the lockfile is
     /// out of date or `force` is `true`.
+    /// Errors if `locked` is `true` and the lockfile would be modified.
     pub fn write_lockfile(
         target: &Ip,
         ip_graph: &GraphMap<IpSpec, IpNode, ()>,
         force: bool,
+        locked: bool,
     ) -> Result<(), Fault> {
         // only modify the lockfile if it is out-of-date
         if target.can_use_lock() == false || force == true {
@@ -521,6 +797,9 @@ impl Plan {
                 .map(|p| p.1.as_ref().as_original_ip())
                 .collect();
             let lock = LockFile::from_build_list(&mut build_list, target);
+            if locked == true && target.get_lock() != &lock {
+                return Err(LockError::RequiresUpdate)?;
+            }
             lock.save_to_disk(target.get_root())?;
 
             if target.get_lock() != &lock {
@@ -853,11 +1132,12 @@ impl Plan {
     /// Performs the backend logic for creating a blueprint file (planning a design).
     fn run(
         &self,
-        target: Ip,
+        target: &Ip,
         build_dir: &str,
         plug: Option<&Plugin>,
         catalog: Catalog,
         mode: &LangMode,
+        locked: bool,
     ) -> Result<(), Fault> {
         // create the build path to know where to begin storing files
         let mut build_path = target.get_root().clone();
@@ -869,7 +1149,7 @@ impl Plan {
         }
 
         // build entire ip graph and resolve with dynamic symbol transformation
-        let ip_graph = match algo::compute_final_ip_graph(&target, &catalog, mode) {
+        let ip_graph = match algo::compute_final_ip_graph(target, &catalog, mode) {
             Ok(g) => g,
             Err(e) => {
                 // generate a single blueprint
@@ -906,11 +1186,36 @@ impl Plan {
 
         // only write lockfile and exit if flag is raised
         if self.only_lock == true {
-            Self::write_lockfile(&target, &ip_graph, self.force)?;
+            Self::write_lockfile(target, &ip_graph, self.force, locked)?;
             return Ok(());
         }
 
-        let files = algo::build_ip_file_list(&ip_graph, &target);
+        // remember when each installed dependency was last used for `orbit cache gc`
+        let now = SystemTime::now();
+        ip_graph
+            .get_map()
+            .iter()
+            .map(|(_, ip)| ip.as_ref().as_ip())
+            .filter(|ip| ip.get_root().starts_with(catalog.get_cache_path()) == true)
+            .for_each(|ip| {
+                // failing to record the time should never stop a plan
+                let _ = Ip::write_last_used_to_metadata(ip.get_root(), now);
+            });
+
+        // assign the library for each dependency according to the working ip's policy
+        let libraries = Self::assign_libraries(&ip_graph, target);
+
+        // place each file into its assigned library so references resolve against it
+        let mut files = algo::build_ip_file_list(&ip_graph, target);
+        files.iter_mut().for_each(|f| {
+            if let Some(lib) = libraries.get(f.get_ip().get_root()).cloned() {
+                f.set_library(lib);
+            }
+        });
+        Self::check_moved_libraries(
+            &files,
+            &Self::moved_libraries(&ip_graph, target, &libraries),
+        )?;
 
         let global_graph = Self::build_full_graph(&files)?;
 
@@ -918,7 +1223,7 @@ impl Plan {
 
         // restrict graph to units only found within the current IP
         let local_graph: GraphMap<&CompoundIdentifier, &HdlNode, &()> =
-            Self::compute_local_graph(&global_graph, &working_lib, &target);
+            Self::compute_local_graph(&global_graph, &working_lib, target);
 
         let (top, bench) = match self.detect_bench(&global_graph, &local_graph, &working_lib) {
             Ok(r) => r,
@@ -975,7 +1280,7 @@ impl Plan {
         }
 
         // [!] write the lock file
-        Self::write_lockfile(&target, &ip_graph, true)?;
+        Self::write_lockfile(target, &ip_graph, true, locked)?;
 
         // compute minimal topological ordering
         let min_order = match self.all {
@@ -1077,30 +1382,75 @@ impl Plan {
                 }
             }
 
+            // gather the public files of each dependency only when a plugin's fileset reaches them
+            let needs_deps = plug
+                .and_then(|p| p.get_filesets())
+                .map(|m| m.values().any(|r| r.get_scope() != &Scope::Local))
+                .unwrap_or(false);
+            let dep_files: Vec<(&Ip, String, Vec<String>)> = match needs_deps {
+                true => ip_graph
+                    .get_map()
+                    .iter()
+                    .map(|(_, node)| node.as_ref())
+                    .filter(|node| node.as_ip() != target)
+                    .map(|node| {
+                        let ip = node.as_ip();
+                        let pub_list = ip.into_public_list();
+                        let files = filesystem::gather_current_files(&ip.get_root(), false)
+                            .into_iter()
+                            .filter(|f| pub_list.is_included(f.as_ref()))
+                            .collect();
+                        let lib = libraries.get(ip.get_root()).unwrap_or(node.get_library());
+                        (ip, lib.to_string(), files)
+                    })
+                    .collect(),
+                false => Vec::new(),
+            };
+
             // collect data for the given plugin
             if plug.is_some() == true && plug.unwrap().get_filesets().is_some() == true {
-                for (name, pattern) in plug.unwrap().get_filesets().unwrap() {
+                for (name, rule) in plug.unwrap().get_filesets().unwrap() {
                     let proper_key = Fileset::standardize_name(name);
-                    // check if appeared in cli arguments
-                    let (f_name, f_patt) = match cli_fset_map.contains_key(&proper_key) {
-                        // override with fileset provided by command-line if conflicting names
-                        true => {
-                            // pull from map to ensure it is not double-counted when just writing command-line filesets
-                            let entry = cli_fset_map.remove(&proper_key);
-                            (name, entry.unwrap().get_pattern())
+                    // override with fileset provided by command-line if conflicting names
+                    if let Some(entry) = cli_fset_map.remove(&proper_key) {
+                        // perform variable substitution
+                        let fset = Fileset::new().name(name).pattern(&variable::substitute(
+                            entry.get_pattern().to_string(),
+                            &vtable,
+                        )?)?;
+                        // match files
+                        fset.collect_files(&current_files)
+                            .into_iter()
+                            .for_each(|f| {
+                                blueprint_data += &fset.to_blueprint_string(&f);
+                            });
+                        continue;
+                    }
+                    // skip filesets conditioned on a disabled language
+                    if rule.is_enabled(mode) == false {
+                        continue;
+                    }
+                    let fset = Fileset::new().name(name);
+                    // match files within the current working ip
+                    let name = target.get_man().get_ip().get_name().to_string();
+                    if rule.accepts_ip(&name, true) == true {
+                        rule.collect_files(&current_files, &vtable)?
+                            .into_iter()
+                            .for_each(|f| {
+                                blueprint_data += &fset.to_blueprint_string(&f);
+                            });
+                    }
+                    // match files within the dependencies
+                    for (ip, lib, files) in &dep_files {
+                        let name = ip.get_man().get_ip().get_name().to_string();
+                        if rule.accepts_ip(&name, false) == true {
+                            rule.collect_files(files, &vtable)?
+                                .into_iter()
+                                .for_each(|f| {
+                                    blueprint_data += &fset.to_blueprint_string_in(&f, lib);
+                                });
                         }
-                        false => (name, pattern.inner()),
-                    };
-                    // perform variable substitution
-                    let fset = Fileset::new()
-                        .name(f_name)
-                        .pattern(&variable::substitute(f_patt.to_string(), &vtable))?;
-                    // match files
-                    fset.collect_files(&current_files)
-                        .into_iter()
-                        .for_each(|f| {
-                            blueprint_data += &fset.to_blueprint_string(&f);
-                        });
+                    }
                 }
             }
 
@@ -1112,7 +1462,7 @@ impl Plan {
                     .pattern(&variable::substitute(
                         fset.get_pattern().to_string(),
                         &vtable,
-                    ))?;
+                    )?)?;
                 // match files
                 fset.collect_files(&current_files)
                     .into_iter()
@@ -1250,6 +1600,85 @@ impl std::fmt::Display for PlanError {
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn check_moved_libraries() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        fs::write(
+            root.join("Orbit.toml"),
+            "[ip]\nname = \"gates\"\nversion = \"1.0.0\"\nlibrary = \"gates\"\n",
+        )
+        .unwrap();
+        let file = root.join("top.vhd");
+        fs::write(
+            &file,
+            "library gates;\nuse gates.gates_pkg.all;\n\nentity top is end entity;\n\narchitecture rtl of top is begin u0: entity work.and_gate; end architecture;\n",
+        )
+        .unwrap();
+        let ip = Ip::load(root, true).unwrap();
+        let files = vec![IpFileNode::new(
+            file.display().to_string(),
+            &ip,
+            Identifier::from_str("gates").unwrap(),
+        )];
+
+        let mut moved = HashMap::new();
+        assert_eq!(Plan::check_moved_libraries(&files, &moved).is_ok(), true);
+        // only references to a moved library are rejected
+        moved.insert(
+            Identifier::from_str("other").unwrap(),
+            String::from("other"),
+        );
+        assert_eq!(Plan::check_moved_libraries(&files, &moved).is_ok(), true);
+        moved.insert(
+            Identifier::from_str("gates").unwrap(),
+            String::from("gates"),
+        );
+        assert_eq!(Plan::check_moved_libraries(&files, &moved).is_err(), true);
+    }
+
+    #[test]
+    fn apply_profile() {
+        let plan = Plan {
+            plugin: None,
+            profile: Some(String::from("sim")),
+            bench: None,
+            top: Some(Identifier::from_str("adder").unwrap()),
+            clean: false,
+            list: false,
+            all: false,
+            build_dir: None,
+            filesets: Some(vec![Fileset::from_str("waves=*.vcd").unwrap()]),
+            only_lock: false,
+            force: false,
+            locked: false,
+            offline: false,
+        };
+        let prof: Profile = toml::from_str(
+            r#"
+top = "mult"
+bench = "adder_tb"
+plugin = "ghdl"
+fileset.waves = "*.gtkw"
+fileset.py-model = "*.py"
+"#,
+        )
+        .unwrap();
+        let plan = plan.apply_profile(&prof).unwrap();
+        // command-line options take precedence
+        assert_eq!(plan.top, Some(Identifier::from_str("adder").unwrap()));
+        assert_eq!(plan.bench, Some(Identifier::from_str("adder_tb").unwrap()));
+        assert_eq!(plan.plugin, Some(String::from("ghdl")));
+        assert_eq!(
+            plan.filesets,
+            Some(vec![
+                Fileset::from_str("waves=*.vcd").unwrap(),
+                Fileset::from_str("py-model=*.py").unwrap(),
+            ])
+        );
+    }
 
     #[test]
     fn remove_multi_occur() {
@@ -93,10 +93,12 @@ impl Command<Context> for Read {
         // checking external IP
         if let Some(tg) = &self.ip {
             // gather the catalog (all manifests)
-            let catalog = Catalog::new().installations(c.get_cache_path())?;
+            let catalog = Catalog::new()
+                .installations(c.get_cache_path())?
+                .layers(c.get_cache_layers())?;
 
             // access the requested ip
-            match catalog.inner().get(&tg.get_name()) {
+            match catalog.lookup(&tg.get_name())? {
                 Some(lvl) => {
                     let inst = match lvl.get_install(tg.get_version()) {
                         Some(i) => i,
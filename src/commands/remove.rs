@@ -2,6 +2,7 @@ use super::helps::remove;
 use crate::core::catalog::{CacheSlot, Catalog};
 use crate::core::context::Context;
 use crate::core::ip::{Ip, PartialIpSpec};
+use crate::core::signature;
 use crate::core::version::AnyVersion;
 use crate::util::anyerror::AnyError;
 use crate::OrbitResult;
@@ -45,10 +46,10 @@ impl Command<Context> for Remove {
         // collect the catalog from dev and installations
         let catalog = Catalog::new()
             .installations(c.get_cache_path())?
-            .downloads(c.get_downloads_path())?;
+            .downloads(c.get_downloads_path(), &c.get_trust()?)?;
 
         // check for ip in development or installation
-        let status = match catalog.inner().get(&self.ip.get_name()) {
+        let status = match catalog.lookup(&self.ip.get_name())? {
             Some(st) => st,
             None => return Err(AnyError(format!("ip '{}' does not exist", self.ip)))?,
         };
@@ -112,16 +113,16 @@ impl Command<Context> for Remove {
             };
             let ip_spec = target.get_man().get_ip().into_ip_spec();
             // delete the project from the cache (default behavior)
-            fs::remove_file(
-                c.get_downloads_path().join(
-                    &target
-                        .get_lock()
-                        .get_self_entry(&ip_spec.get_name())
-                        .unwrap()
-                        .to_download_slot_key()
-                        .as_ref(),
-                ),
-            )?;
+            let archive = c.get_downloads_path().join(
+                &target
+                    .get_lock()
+                    .get_self_entry(&ip_spec.get_name())
+                    .unwrap()
+                    .to_download_slot_key()
+                    .as_ref(),
+            );
+            fs::remove_file(&archive)?;
+            signature::remove_detached(&archive)?;
             println!("info: Removed ip {} from downloads", ip_spec);
         }
 
@@ -19,6 +19,7 @@ pub struct Search {
     ip: Option<PkgPart>,
     cached: bool,
     downloaded: bool,
+    available: bool,
     keywords: Vec<String>,
     limit: Option<usize>,
     hard_match: bool,
@@ -30,6 +31,7 @@ impl FromCli for Search {
         let command = Ok(Search {
             downloaded: cli.check_flag(Flag::new("download").switch('d'))?,
             cached: cli.check_flag(Flag::new("install").switch('i'))?,
+            available: cli.check_flag(Flag::new("available").switch('a'))?,
             hard_match: cli.check_flag(Flag::new("match"))?,
             limit: cli.check_option(Optional::new("limit").value("num"))?,
             keywords: cli
@@ -48,11 +50,13 @@ impl Command<Context> for Search {
         let mut catalog = Catalog::new();
 
         // collect installed IP
-        catalog = catalog.installations(c.get_cache_path())?;
+        catalog = catalog
+            .installations(c.get_cache_path())?
+            .layers(c.get_cache_layers())?;
         // collect downloaded IP
-        catalog = catalog.downloads(c.get_downloads_path())?;
+        catalog = catalog.downloads(c.get_downloads_path(), &c.get_trust()?)?;
         // collect available IP
-        // @todo
+        catalog = catalog.available(&c.get_vendors()?)?;
 
         self.run(&catalog)
     }
@@ -67,7 +71,10 @@ impl Search {
             .into_iter()
             // filter by name if user entered a pkgid to search
             .filter(|(key, iplvl)| {
-                if let Some(prj) = iplvl.get(true, &AnyVersion::Latest) {
+                if let Some(prj) = iplvl
+                    .get(true, &AnyVersion::Latest)
+                    .or(iplvl.get_available(&AnyVersion::Latest))
+                {
                     match self.hard_match {
                         true => {
                             let name_match = match &self.ip {
@@ -127,7 +134,13 @@ impl Search {
 
         println!(
             "{}",
-            Self::fmt_table(tree, self.limit, self.cached, self.downloaded)
+            Self::fmt_table(
+                tree,
+                self.limit,
+                self.cached,
+                self.downloaded,
+                self.available
+            )
         );
         Ok(())
     }
@@ -137,6 +150,7 @@ impl Search {
         limit: Option<usize>,
         cached: bool,
         downloaded: bool,
+        available: bool,
     ) -> String {
         let header = format!(
             "\
@@ -147,7 +161,7 @@ impl Search {
         let mut body = String::new();
         let mut index = 0;
 
-        let default = !(cached || downloaded);
+        let default = !(cached || downloaded || available);
 
         // note: There is definitely a nicer way to handle all of this logic... but this works for now.
 
@@ -155,7 +169,10 @@ impl Search {
             // use this variable to determine if a level higher in the catalog has a higher version not displayed right now
             let mut is_update_available = false;
             // return the highest version (return installation when they are equal in downloads and cache)
-            let ip = {
+            let ip = if available == true && (cached || downloaded) == false {
+                // only display the version known from the vendors
+                status.get_available(&AnyVersion::Latest)
+            } else {
                 let dld = status.get_download(&AnyVersion::Latest);
                 let ins = status.get_install(&AnyVersion::Latest);
                 if dld.is_some() && ins.is_some() {
@@ -169,12 +186,23 @@ impl Search {
                     } else {
                         dld
                     }
+                } else if dld.is_none() && ins.is_none() {
+                    // the vendors are the only place the ip is known
+                    status.get_available(&AnyVersion::Latest)
                 } else if dld.is_none() {
                     ins
                 } else {
                     dld
                 }
             };
+            // an update is possible if a vendor has a higher version than the one displayed
+            if let Some(avl) = status.get_available(&AnyVersion::Latest) {
+                if let Some(shown) = ip {
+                    is_update_available = is_update_available
+                        || avl.get_man().get_ip().get_version()
+                            > shown.get_man().get_ip().get_version();
+                }
+            }
             // IP should NOT be empty but skip if it is
             let ip = match ip {
                 Some(r) => r,
@@ -194,6 +222,7 @@ impl Search {
                 || match ip.get_mapping() {
                     Mapping::Physical => cached == true,
                     Mapping::Virtual(_) => downloaded == true,
+                    Mapping::Imaginary => available == true,
                 };
             if cleared == false {
                 continue;
@@ -212,8 +241,7 @@ impl Search {
                 match ip.get_mapping() {
                     Mapping::Physical => "Installed",
                     Mapping::Virtual(_) => "Downloaded",
-                    // Mapping::Imaginary => "Available",
-                    // _ => ""
+                    Mapping::Imaginary => "Available",
                 },
             ));
         }
@@ -227,7 +255,7 @@ mod test {
 
     #[test]
     fn fmt_table() {
-        let t = Search::fmt_table(BTreeMap::new(), None, false, false);
+        let t = Search::fmt_table(BTreeMap::new(), None, false, false, false);
         let table = "\
 Package                     Latest    Status   
 --------------------------- --------- ---------- 
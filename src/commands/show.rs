@@ -40,7 +40,8 @@ impl Command<Context> for Show {
         // collect all manifests available (load catalog)
         let catalog = Catalog::new()
             .installations(c.get_cache_path())?
-            .downloads(c.get_downloads_path())?;
+            .layers(c.get_cache_layers())?
+            .downloads(c.get_downloads_path(), &c.get_trust()?)?;
 
         let dev_ip: Option<Result<Ip, Fault>> = {
             match Context::find_ip_path(&current_dir().unwrap()) {
@@ -53,7 +54,7 @@ impl Command<Context> for Show {
         // try to auto-determine the ip (check if in a working ip)
         let ip: &Ip = if let Some(spec) = &self.ip {
             // find the path to the provided ip by searching through the catalog
-            if let Some(lvl) = catalog.inner().get(spec.get_name()) {
+            if let Some(lvl) = catalog.lookup(spec.get_name())? {
                 // return the highest available version
                 if let Some(slot) = lvl.get_install(spec.get_version()) {
                     slot
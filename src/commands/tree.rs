@@ -69,7 +69,9 @@ impl Command<Context> for Tree {
         let ip = Ip::load(c.get_ip_path().unwrap().clone(), true)?;
 
         // gather the catalog
-        let catalog = Catalog::new().installations(c.get_cache_path())?;
+        let catalog = Catalog::new()
+            .installations(c.get_cache_path())?
+            .layers(c.get_cache_layers())?;
 
         self.run(ip, catalog, c.get_lang_mode())
     }
@@ -0,0 +1,59 @@
+use crate::commands::helps::unpack;
+use crate::core::context::Context;
+use crate::core::iparchive::IpArchive;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::OrbitResult;
+use clif::arg::Positional;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq)]
+pub struct Unpack {
+    file: PathBuf,
+    dir: PathBuf,
+}
+
+impl FromCli for Unpack {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(unpack::HELP).ref_usage(2..4))?;
+        let command = Ok(Unpack {
+            file: cli.require_positional(Positional::new("file"))?,
+            dir: cli.require_positional(Positional::new("dir"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Unpack {
+    type Status = OrbitResult;
+
+    fn exec(&self, _: &Context) -> Self::Status {
+        self.run()
+    }
+}
+
+impl Unpack {
+    fn run(&self) -> Result<(), Fault> {
+        // never place files over existing contents
+        if self.dir.exists() == true
+            && (self.dir.is_dir() == false || fs::read_dir(&self.dir)?.next().is_some() == true)
+        {
+            return Err(AnyError(format!(
+                "directory {} already exists and is not empty",
+                self.dir.display()
+            )))?;
+        }
+        // read the archive without repairing it so the file is left untouched
+        let archive = IpArchive::open(&self.file)?;
+        IpArchive::extract(archive.get_archive(), &self.dir)?;
+        println!(
+            "info: Unpacked ip {} to {}",
+            archive.get_man().get_ip().into_ip_spec(),
+            self.dir.display()
+        );
+        Ok(())
+    }
+}
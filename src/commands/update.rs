@@ -0,0 +1,316 @@
+use crate::commands::helps::update;
+use crate::commands::plan;
+use crate::core::catalog::{Catalog, IpLevel};
+use crate::core::context::Context;
+use crate::core::ip::{Ip, IpSpec};
+use crate::core::lockfile::LockFile;
+use crate::core::pkgid::PkgPart;
+use crate::core::version::AnyVersion;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::OrbitResult;
+use clif::arg::{Flag, Positional};
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq)]
+pub struct Update {
+    names: Vec<PkgPart>,
+    dry_run: bool,
+}
+
+impl FromCli for Update {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(update::HELP).ref_usage(2..4))?;
+        let command = Ok(Update {
+            // flags
+            dry_run: cli.check_flag(Flag::new("dry-run"))?,
+            // positionals
+            names: {
+                let mut names = Vec::new();
+                while let Some(n) = cli.check_positional(Positional::new("ip"))? {
+                    names.push(n);
+                }
+                names
+            },
+        });
+        command
+    }
+}
+
+impl Command<Context> for Update {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+
+        // create the ip manifest
+        let target = Ip::load(c.get_ip_path().unwrap().clone(), true)?;
+        let lock = target.get_lock();
+
+        // verify each requested ip is a dependency of the current project
+        for name in &self.names {
+            if lock.inner().iter().any(|e| name.refers_to(e.get_name())) == false
+                && target
+                    .get_man()
+                    .get_deps_list(true)
+                    .iter()
+                    .any(|(n, _)| name.refers_to(n) || n.refers_to(name))
+                    == false
+            {
+                return Err(AnyError(format!(
+                    "ip '{}' is not a dependency of the current project",
+                    name
+                )))?;
+            }
+        }
+
+        // gather the catalog
+        let catalog = Catalog::new()
+            .installations(c.get_cache_path())?
+            .layers(c.get_cache_layers())?
+            .downloads(c.get_downloads_path(), &c.get_trust()?)?;
+
+        let build_list = self.resolve(&target, &catalog)?;
+        let changes = Change::diff(&Self::into_specs(lock), &Self::into_specs_from(&build_list));
+
+        for change in &changes {
+            println!("info: {}", change);
+        }
+
+        if self.dry_run == true {
+            if changes.is_empty() == true {
+                println!("info: Lockfile is already up to date");
+            }
+            return Ok(());
+        }
+
+        // install any selected ip that currently only exist as downloads
+        let mut requires_install = false;
+        for ip in build_list
+            .iter()
+            .filter(|ip| ip.get_mapping().as_bytes().is_some())
+        {
+            plan::install_ip_from_downloads(ip, &catalog, false)?;
+            requires_install = true;
+        }
+
+        // recollect the installations to compute the checksums of the newly installed ip
+        let catalog = match requires_install {
+            true => Catalog::new()
+                .installations(c.get_cache_path())?
+                .layers(c.get_cache_layers())?
+                .downloads(c.get_downloads_path(), &c.get_trust()?)?,
+            false => catalog,
+        };
+        let mut build_list = self.resolve(&target, &catalog)?;
+
+        let next = LockFile::from_build_list(&mut build_list, &target);
+        next.save_to_disk(target.get_root())?;
+
+        if lock != &next {
+            println!("info: Lockfile updated");
+        } else {
+            println!("info: Lockfile experienced no changes");
+        }
+        Ok(())
+    }
+}
+
+impl Update {
+    /// Checks if the dependency `name` is allowed to move away from its locked version.
+    fn is_unlocked(&self, name: &PkgPart) -> bool {
+        self.names.is_empty() == true
+            || self
+                .names
+                .iter()
+                .any(|n| n.refers_to(name) || name.refers_to(n))
+    }
+
+    /// Selects the highest version across the installations and downloads that
+    /// satisfies `version`.
+    ///
+    /// Installations are preferred when both levels share the same version.
+    fn select<'a>(status: &'a IpLevel, version: &AnyVersion) -> Option<&'a Ip> {
+        match (status.get_install(version), status.get_download(version)) {
+            (Some(i), Some(d)) => {
+                if d.get_man().get_ip().get_version() > i.get_man().get_ip().get_version() {
+                    Some(d)
+                } else {
+                    Some(i)
+                }
+            }
+            (Some(i), None) => Some(i),
+            (None, Some(d)) => Some(d),
+            (None, None) => None,
+        }
+    }
+
+    /// Re-resolves the dependencies of `target` within the constraints of each
+    /// manifest.
+    ///
+    /// Dependencies not requested for an update keep the version recorded in
+    /// the lockfile as long as it still satisfies the manifest.
+    fn resolve<'a>(&self, target: &'a Ip, catalog: &'a Catalog) -> Result<Vec<&'a Ip>, Fault> {
+        let lock = target.get_lock();
+
+        let mut build_list: Vec<&Ip> = vec![target];
+        let mut processing: Vec<(&Ip, bool)> = vec![(target, true)];
+
+        while let Some((ip, is_root)) = processing.pop() {
+            for (name, version) in ip.get_man().get_deps_list(is_root) {
+                let status = match catalog.lookup(name)? {
+                    Some(st) => st,
+                    None => {
+                        return Err(AnyError(format!(
+                            "ip '{}' is not installed or downloaded",
+                            name
+                        )))?
+                    }
+                };
+                let requirement = AnyVersion::from(version);
+                // try the locked version before moving to the highest compatible version
+                let pinned = match self.is_unlocked(name) {
+                    true => None,
+                    false => lock
+                        .get_highest(name, &requirement)
+                        .and_then(|e| Self::select(status, &AnyVersion::from(e.get_version()))),
+                };
+                let dep = match pinned.or(Self::select(status, &requirement)) {
+                    Some(d) => d,
+                    None => {
+                        return Err(AnyError(format!(
+                            "no installed or downloaded version of ip '{}' satisfies version {}",
+                            name, version
+                        )))?
+                    }
+                };
+                // add the ip only once to the build list
+                if build_list.iter().any(|&b| {
+                    b.get_man().get_ip().into_ip_spec() == dep.get_man().get_ip().into_ip_spec()
+                }) == false
+                {
+                    build_list.push(dep);
+                    processing.push((dep, false));
+                }
+            }
+        }
+        Ok(build_list)
+    }
+
+    /// Collects the specifications of every non-root entry in the `lock`.
+    fn into_specs(lock: &LockFile) -> Vec<IpSpec> {
+        lock.inner()
+            .iter()
+            .filter(|e| e.get_sum().is_some())
+            .map(|e| e.to_ip_spec())
+            .collect()
+    }
+
+    /// Collects the specifications of every dependency in the `build_list`.
+    ///
+    /// Assumes the first element is the root ip.
+    fn into_specs_from(build_list: &Vec<&Ip>) -> Vec<IpSpec> {
+        build_list
+            .iter()
+            .skip(1)
+            .map(|ip| ip.get_man().get_ip().into_ip_spec())
+            .collect()
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Change {
+    Update(IpSpec, IpSpec),
+    Add(IpSpec),
+    Remove(IpSpec),
+}
+
+impl Change {
+    /// Computes the changes required to go from the `old` list of ip to the `new`
+    /// list of ip.
+    ///
+    /// The changes are sorted by the ip name.
+    fn diff(old: &Vec<IpSpec>, new: &Vec<IpSpec>) -> Vec<Change> {
+        let mut removed: Vec<&IpSpec> = old.iter().filter(|o| new.contains(o) == false).collect();
+        let mut added: Vec<&IpSpec> = new.iter().filter(|n| old.contains(n) == false).collect();
+
+        let mut changes = Vec::new();
+        // pair together versions of the same ip
+        removed.retain(
+            |&o| match added.iter().position(|n| n.get_name() == o.get_name()) {
+                Some(i) => {
+                    changes.push(Change::Update(o.clone(), added.remove(i).clone()));
+                    false
+                }
+                None => true,
+            },
+        );
+        changes.extend(removed.into_iter().map(|o| Change::Remove(o.clone())));
+        changes.extend(added.into_iter().map(|n| Change::Add(n.clone())));
+
+        changes.sort_by(|x, y| x.get_name().cmp(y.get_name()));
+        changes
+    }
+
+    fn get_name(&self) -> &PkgPart {
+        match self {
+            Self::Update(o, _) => o.get_name(),
+            Self::Add(n) => n.get_name(),
+            Self::Remove(o) => o.get_name(),
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Update(o, n) => write!(f, "Updating {} -> {}", o, n),
+            Self::Add(n) => write!(f, "Adding {}", n),
+            Self::Remove(o) => write!(f, "Removing {}", o),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn diff() {
+        let old = vec![
+            IpSpec::from_str("gates:1.0.0").unwrap(),
+            IpSpec::from_str("mux:2.0.0").unwrap(),
+            IpSpec::from_str("alu:0.3.0").unwrap(),
+        ];
+        let new = vec![
+            IpSpec::from_str("gates:1.2.0").unwrap(),
+            IpSpec::from_str("mux:2.0.0").unwrap(),
+            IpSpec::from_str("adder:0.1.0").unwrap(),
+        ];
+        assert_eq!(
+            Change::diff(&old, &new),
+            vec![
+                Change::Add(IpSpec::from_str("adder:0.1.0").unwrap()),
+                Change::Remove(IpSpec::from_str("alu:0.3.0").unwrap()),
+                Change::Update(
+                    IpSpec::from_str("gates:1.0.0").unwrap(),
+                    IpSpec::from_str("gates:1.2.0").unwrap()
+                ),
+            ]
+        );
+        assert_eq!(Change::diff(&old, &old), Vec::new());
+    }
+
+    #[test]
+    fn fmt_change() {
+        let c = Change::Update(
+            IpSpec::from_str("gates:1.0.0").unwrap(),
+            IpSpec::from_str("gates:1.2.0").unwrap(),
+        );
+        assert_eq!(c.to_string(), "Updating gates:1.0.0 -> gates:1.2.0");
+    }
+}
@@ -0,0 +1,269 @@
+use crate::commands::helps::vendor;
+use crate::core::config::ConfigDocument;
+use crate::core::context::Context;
+use crate::core::manifest::FromFile;
+use crate::core::pkgid::PkgPart;
+use crate::core::vendor::Vendor as Registry;
+use crate::util::anyerror::AnyError;
+use crate::util::anyerror::Fault;
+use crate::util::filesystem;
+use crate::OrbitResult;
+use clif::arg::Positional;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq)]
+pub struct Vendor {
+    command: Option<VendorSubcommand>,
+}
+
+impl FromCli for Vendor {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(vendor::HELP).ref_usage(2..4))?;
+        let command = Ok(Vendor {
+            command: cli.check_command(Positional::new("command"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Vendor {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        match &self.command {
+            Some(cmd) => cmd.exec(c),
+            // if no command is given then print default help
+            None => Ok(println!("{}", vendor::HELP)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum VendorSubcommand {
+    Add(Add),
+    Refresh(Refresh),
+    List(List),
+}
+
+impl FromCli for VendorSubcommand {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        match cli.match_command(&["add", "refresh", "list"])?.as_ref() {
+            "add" => Ok(VendorSubcommand::Add(Add::from_cli(cli)?)),
+            "refresh" => Ok(VendorSubcommand::Refresh(Refresh::from_cli(cli)?)),
+            "list" => Ok(VendorSubcommand::List(List::from_cli(cli)?)),
+            _ => panic!("an unimplemented command was passed through!"),
+        }
+    }
+}
+
+impl Command<Context> for VendorSubcommand {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        match self {
+            VendorSubcommand::Add(cmd) => cmd.exec(c),
+            VendorSubcommand::Refresh(cmd) => cmd.exec(c),
+            VendorSubcommand::List(cmd) => cmd.exec(c),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Add {
+    path: PathBuf,
+}
+
+impl FromCli for Add {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(vendor::HELP).ref_usage(2..4))?;
+        let command = Ok(Add {
+            path: cli.require_positional(Positional::new("path"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Add {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        let root = filesystem::full_normal(&self.path);
+        // verify the directory is a vendor
+        let target = Registry::load(&root)?;
+        // verify the vendor is not already known
+        if let Some(v) = c
+            .get_vendors()?
+            .into_iter()
+            .find(|v| v.get_name() == target.get_name())
+        {
+            return Err(AnyError(format!(
+                "vendor '{}' already exists at {:?}",
+                v.get_name(),
+                v.get_root()
+            )))?;
+        }
+        // store the vendor's path in the global configuration
+        let file = c.get_all_configs().get_global().0.clone();
+        let mut cfg = ConfigDocument::from_file(&file)?;
+        cfg.append_vendor(&filesystem::into_std_str(root));
+        cfg.write(&file)?;
+
+        println!(
+            "info: added vendor '{}' tracking {} ip",
+            target.get_name(),
+            Self::count_ip(&target)?
+        );
+        Ok(())
+    }
+}
+
+impl Add {
+    /// Counts the number of unique ip names tracked by the `vendor`.
+    fn count_ip(vendor: &Registry) -> Result<usize, Fault> {
+        let names: HashSet<PkgPart> = vendor
+            .read_ip()?
+            .into_iter()
+            .map(|ip| ip.get_man().get_ip().get_name().clone())
+            .collect();
+        Ok(names.len())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Refresh {
+    name: Option<PkgPart>,
+}
+
+impl FromCli for Refresh {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(vendor::HELP).ref_usage(2..4))?;
+        let command = Ok(Refresh {
+            name: cli.check_positional(Positional::new("name"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Refresh {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        let vendors: Vec<Registry> = c
+            .get_vendors()?
+            .into_iter()
+            .filter(|v| match &self.name {
+                Some(n) => v.get_name() == n,
+                None => true,
+            })
+            .collect();
+
+        if let Some(n) = &self.name {
+            if vendors.is_empty() == true {
+                return Err(AnyError(format!("vendor '{}' does not exist", n)))?;
+            }
+        }
+
+        for v in &vendors {
+            Self::refresh(v)?;
+        }
+        Ok(())
+    }
+}
+
+impl Refresh {
+    /// Pulls the latest changes for the `vendor` if it is a git repository.
+    fn refresh(vendor: &Registry) -> Result<(), Fault> {
+        if vendor.get_root().join(".git").exists() == false {
+            println!(
+                "info: skipping vendor '{}' (not a git repository)",
+                vendor.get_name()
+            );
+            return Ok(());
+        }
+        println!("info: refreshing vendor '{}' ...", vendor.get_name());
+        let mut proc = filesystem::invoke(
+            &filesystem::into_std_str(vendor.get_root().clone()),
+            &String::from("git"),
+            &vec![String::from("pull")],
+            Context::enable_windows_bat_file_match(),
+        )?;
+        let exit_code = proc.wait()?;
+        match exit_code.code() {
+            Some(0) => Ok(()),
+            Some(num) => Err(AnyError(format!(
+                "failed to refresh vendor '{}': exited with error code {}",
+                vendor.get_name(),
+                num
+            )))?,
+            None => Err(AnyError(format!(
+                "failed to refresh vendor '{}': terminated by signal",
+                vendor.get_name()
+            )))?,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct List {}
+
+impl FromCli for List {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(vendor::HELP).ref_usage(2..4))?;
+        let command = Ok(List {});
+        command
+    }
+}
+
+impl Command<Context> for List {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        let vendors = c.get_vendors()?;
+        let mut rows = Vec::with_capacity(vendors.len());
+        for v in &vendors {
+            rows.push((v, Add::count_ip(v)?));
+        }
+        println!("{}", Self::fmt_table(rows));
+        Ok(())
+    }
+}
+
+impl List {
+    fn fmt_table(vendors: Vec<(&Registry, usize)>) -> String {
+        let header = format!(
+            "\
+{:<28}{:<6}{}
+{3:->28}{3:->6}{3:->40}\n",
+            "Vendor", "IP", "Summary", " "
+        );
+        let mut body = String::new();
+        for (v, count) in vendors {
+            body.push_str(&format!(
+                "{:<28}{:<6}{}\n",
+                v.get_name().to_string(),
+                count,
+                v.get_summary().unwrap_or(&String::new()),
+            ));
+        }
+        header + &body
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fmt_table() {
+        let t = List::fmt_table(Vec::new());
+        let table = "\
+Vendor                      IP    Summary
+--------------------------- ----- --------------------------------------- 
+";
+        assert_eq!(t, table);
+    }
+}
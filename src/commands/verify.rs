@@ -0,0 +1,221 @@
+use crate::commands::helps::verify;
+use crate::commands::plan;
+use crate::core::catalog::Catalog;
+use crate::core::context::Context;
+use crate::core::ip::Ip;
+use crate::core::lockfile::LockEntry;
+use crate::core::manifest::ORBIT_SUM_FILE;
+use crate::core::version::AnyVersion;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::sha256::Sha256Hash;
+use crate::OrbitResult;
+use clif::arg::Flag;
+use clif::cmd::{Command, FromCli};
+use clif::Cli;
+use clif::Error as CliError;
+use colored::Colorize;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq)]
+pub struct Verify {
+    repair: bool,
+}
+
+impl FromCli for Verify {
+    fn from_cli<'c>(cli: &'c mut Cli) -> Result<Self, CliError> {
+        cli.check_help(clif::Help::new().quick_text(verify::HELP).ref_usage(2..4))?;
+        let command = Ok(Verify {
+            repair: cli.check_flag(Flag::new("repair"))?,
+        });
+        command
+    }
+}
+
+impl Command<Context> for Verify {
+    type Status = OrbitResult;
+
+    fn exec(&self, c: &Context) -> Self::Status {
+        // check that user is in an IP directory
+        c.goto_ip_path()?;
+
+        // create the ip manifest
+        let target = Ip::load(c.get_ip_path().unwrap().clone(), true)?;
+
+        // verify a lockfile exists
+        if target.get_lock().is_empty() == true {
+            return Err(AnyError(format!(
+                "missing lockfile; try running `orbit plan --lock-only` to create one"
+            )))?;
+        }
+
+        // gather the catalog
+        let catalog = Catalog::new()
+            .installations(c.get_cache_path())?
+            .layers(c.get_cache_layers())?
+            .downloads(c.get_downloads_path(), &c.get_trust()?)?;
+
+        let mut total = 0;
+        let mut failures = 0;
+        for entry in target.get_lock().inner() {
+            // skip the current working ip
+            let sum = match entry.get_sum() {
+                Some(s) => s,
+                None => continue,
+            };
+            total += 1;
+
+            // a slot missing from every layer is reported against the writable cache
+            let key = entry.to_cache_slot_key();
+            let slot = catalog
+                .find_cached_slot(&key)
+                .unwrap_or(catalog.get_cache_path().join(key.to_string()));
+            let issue = match Self::check(&slot, sum) {
+                Some(i) => i,
+                None => continue,
+            };
+            println!(
+                "{}: ip {} {}",
+                "warning".yellow().bold(),
+                entry.to_ip_spec(),
+                issue
+            );
+            if self.repair == false {
+                failures += 1;
+                continue;
+            }
+            // reinstall the ip from its download and check the result once more
+            if let Err(e) = Self::repair(entry, &catalog) {
+                println!(
+                    "{}: failed to repair ip {}: {}",
+                    "warning".yellow().bold(),
+                    entry.to_ip_spec(),
+                    e
+                );
+                failures += 1;
+            // repairs are always installed to the writable cache
+            } else if let Some(issue) =
+                Self::check(&catalog.get_cache_path().join(key.to_string()), sum)
+            {
+                println!(
+                    "{}: failed to repair ip {}: reinstalled ip {}",
+                    "warning".yellow().bold(),
+                    entry.to_ip_spec(),
+                    issue
+                );
+                failures += 1;
+            } else {
+                println!("info: Repaired ip {}", entry.to_ip_spec());
+            }
+        }
+
+        match failures {
+            0 => {
+                println!("info: Verified {} ip", total);
+                Ok(())
+            }
+            _ => Err(AnyError(format!(
+                "{} of {} ip failed verification{}",
+                failures,
+                total,
+                match self.repair {
+                    true => "",
+                    false => "; use `--repair` to reinstall them from the downloads",
+                }
+            )))?,
+        }
+    }
+}
+
+impl Verify {
+    /// Audits the installation at the cache `slot` against the checksum `sum`
+    /// recorded in the lockfile.
+    ///
+    /// Returns `None` if the installation is intact.
+    fn check(slot: &PathBuf, sum: &Sha256Hash) -> Option<Issue> {
+        if slot.is_dir() == false {
+            return Some(Issue::MissingSlot);
+        }
+        let computed = Ip::compute_checksum(slot);
+        if &computed != sum {
+            return Some(Issue::Modified(computed));
+        }
+        match Ip::read_checksum_proof(slot) {
+            Some(proof) => match proof == computed {
+                true => None,
+                false => Some(Issue::TamperedProof(proof)),
+            },
+            None => Some(Issue::MissingProof),
+        }
+    }
+
+    /// Reinstalls the ip for the lock `entry` from the downloads.
+    fn repair(entry: &LockEntry, catalog: &Catalog) -> Result<(), Fault> {
+        let dep = catalog
+            .inner()
+            .get(entry.get_name())
+            .and_then(|st| st.get_download(&AnyVersion::from(entry.get_version())));
+        match dep {
+            Some(d) => plan::install_ip_from_downloads(d, catalog, true),
+            None => Err(AnyError(format!(
+                "missing download; try running `orbit download` first"
+            )))?,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Issue {
+    MissingSlot,
+    Modified(Sha256Hash),
+    MissingProof,
+    TamperedProof(Sha256Hash),
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MissingSlot => write!(f, "is missing from the cache"),
+            Self::Modified(sum) => {
+                write!(f, "does not match the lockfile checksum (computed {})", sum)
+            }
+            Self::MissingProof => write!(f, "is missing its {} file", ORBIT_SUM_FILE),
+            Self::TamperedProof(sum) => {
+                write!(f, "has a tampered {} file (found {})", ORBIT_SUM_FILE, sum)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::util::filesystem;
+
+    #[test]
+    fn check_slot() {
+        let cache = tempfile::tempdir().unwrap();
+        let slot = cache.path().join("project1-0.1.0-0123456789");
+        assert_eq!(
+            Verify::check(&slot, &Sha256Hash::new()),
+            Some(Issue::MissingSlot)
+        );
+
+        filesystem::copy(&PathBuf::from("./tests/env/project1"), &slot, true, None).unwrap();
+        let sum = Ip::compute_checksum(&slot);
+        assert_eq!(Verify::check(&slot, &sum), Some(Issue::MissingProof));
+        assert_eq!(
+            Verify::check(&slot, &Sha256Hash::new()),
+            Some(Issue::Modified(sum.clone()))
+        );
+
+        std::fs::write(slot.join(ORBIT_SUM_FILE), sum.to_string()).unwrap();
+        assert_eq!(Verify::check(&slot, &sum), None);
+
+        std::fs::write(slot.join(ORBIT_SUM_FILE), Sha256Hash::new().to_string()).unwrap();
+        assert_eq!(
+            Verify::check(&slot, &sum),
+            Some(Issue::TamperedProof(Sha256Hash::new()))
+        );
+    }
+}
@@ -14,6 +14,7 @@ use crate::core::catalog::CacheSlot;
 use crate::core::catalog::Catalog;
 use crate::core::ip::Ip;
 use crate::core::ip::IpSpec;
+use crate::core::ip::PartialIpSpec;
 use crate::core::lockfile::{LockEntry, LockFile};
 use crate::core::manifest;
 use crate::core::version::AnyVersion;
@@ -34,7 +35,7 @@ pub fn graph_ip_from_lock(lock: &LockFile) -> Result<GraphMap<IpSpec, &LockEntry
         for dep in upper.get_deps() {
             // determine the most compatible entry for this dependency
             let lower = lock
-                .get_highest(&dep.get_name(), &AnyVersion::from(dep.get_version()))
+                .get_highest(&dep.get_name(), dep.get_version())
                 .unwrap();
             graph.add_edge_by_key(&lower.to_ip_spec(), &upper.to_ip_spec(), ());
         }
@@ -74,10 +75,21 @@ fn graph_ip<'a>(
         let reqs = ip.get_man().get_deps_list(is_root);
         // read dependencies
         for (pkgid, version) in reqs {
-            match catalog.inner().get(pkgid) {
+            // prefer the version pinned by the root's lockfile while it satisfies the requirement
+            let target = match root
+                .get_lock()
+                .get_highest(pkgid, &AnyVersion::from(version))
+            {
+                Some(entry) => AnyVersion::from(entry.get_version()),
+                None => AnyVersion::from(version),
+            };
+            match catalog
+                .lookup(pkgid)
+                .map_err(|e| CodeFault(None, Box::new(e)))?
+            {
                 Some(status) => {
                     // find this IP to read its dependencies
-                    match status.get_install(&AnyVersion::from(version)) {
+                    match status.get_install(&target) {
                         Some(dep) => {
                             // check if node is already in graph ????
                             let s = if let Some(existing_node) =
@@ -136,7 +148,7 @@ fn graph_ip<'a>(
                                 None,
                                 Box::new(AnyError(format!(
                                     "IP {} is not installed",
-                                    IpSpec::from((pkgid.clone(), version.clone()))
+                                    PartialIpSpec::from((pkgid.clone(), version.clone()))
                                 ))),
                             ))?
                         }
@@ -149,7 +161,7 @@ fn graph_ip<'a>(
                         None,
                         Box::new(AnyError(format!(
                             "unknown IP {}",
-                            IpSpec::from((pkgid.clone(), version.clone()))
+                            PartialIpSpec::from((pkgid.clone(), version.clone()))
                         ))),
                     ))?
                 }
@@ -309,7 +321,7 @@ impl<'a> IpNode<'a> {
         &self.original
     }
 
-    fn get_library(&self) -> &Identifier {
+    pub fn get_library(&self) -> &Identifier {
         &self.library
     }
 
@@ -452,4 +464,9 @@ impl<'a> IpFileNode<'a> {
     pub fn get_library(&self) -> &Identifier {
         &self.library
     }
+
+    /// Places the file into the library `lib`.
+    pub fn set_library(&mut self, lib: Identifier) {
+        self.library = lib;
+    }
 }
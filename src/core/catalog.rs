@@ -15,6 +15,8 @@ use super::{
 
 use crate::core::ip::Ip;
 use crate::core::iparchive::IpArchive;
+use crate::core::signature::Trust;
+use crate::core::vendor::Vendor;
 use std::cmp::PartialOrd;
 use std::hash::Hash;
 
@@ -72,6 +74,7 @@ impl<'a> Hash for VersionItem<'a> {
 pub struct Catalog<'a> {
     inner: HashMap<PkgPart, IpLevel>,
     cache: Option<&'a PathBuf>,
+    layers: Vec<&'a PathBuf>,
     downloads: Option<&'a PathBuf>,
 }
 
@@ -231,13 +234,25 @@ impl<'a> Catalog<'a> {
         Self {
             inner: HashMap::new(),
             cache: None,
+            layers: Vec::new(),
             downloads: None,
         }
     }
 
-    /// Uses the cache slot name to check if the directory exists.
+    /// Uses the cache slot name to check if the directory exists in the cache
+    /// or any of its read-only layers.
     pub fn is_cached_slot(&self, slot: &CacheSlot) -> bool {
-        self.get_cache_path().join(slot.to_string()).is_dir()
+        self.find_cached_slot(slot).is_some()
+    }
+
+    /// Returns the path to the directory for the cache slot, searching the
+    /// writable cache first and then each read-only layer in order.
+    pub fn find_cached_slot(&self, slot: &CacheSlot) -> Option<PathBuf> {
+        self.cache
+            .iter()
+            .chain(self.layers.iter())
+            .map(|root| root.join(slot.to_string()))
+            .find(|dir| dir.is_dir() == true)
     }
 
     /// Uses the download slot name to check if the file exists.
@@ -253,6 +268,10 @@ impl<'a> Catalog<'a> {
             while let Some(d) = rd.next() {
                 if let Ok(p) = d {
                     let file_name = p.file_name().into_string().unwrap();
+                    // skip detached signatures and other files kept beside the archives
+                    if p.path().extension().map_or(true, |e| e != ARCHIVE_EXT) == true {
+                        continue;
+                    }
                     // collect all possible UUIDs
                     if file_name.starts_with(&pat) == true {
                         ids.push(file_name.rsplit_once('-').unwrap().1.to_string());
@@ -277,16 +296,62 @@ impl<'a> Catalog<'a> {
         self.detect(path, &IpLevel::add_install, IpState::Installation)
     }
 
-    /// Searches the `path` for IP downloaded.
-    pub fn downloads(mut self, path: &'a PathBuf) -> Result<Self, Fault> {
+    /// Searches each read-only cache layer in `paths` for IP installed.
+    ///
+    /// Layers are searched after the writable cache, so an installation found
+    /// by [Catalog::installations] takes precedence over the same version in a layer.
+    pub fn layers(mut self, paths: &'a Vec<PathBuf>) -> Result<Self, Fault> {
+        for path in paths {
+            self.layers.push(path);
+            self = self.detect(path, &IpLevel::add_install, IpState::Installation)?;
+        }
+        Ok(self)
+    }
+
+    /// Searches the `path` for IP downloaded, checking each archive's signature
+    /// against the `trust` policy.
+    pub fn downloads(mut self, path: &'a PathBuf, trust: &Trust) -> Result<Self, Fault> {
         self.downloads = Some(&path);
-        self.detect(path, &IpLevel::add_download, IpState::Downloaded)
+        let found = IpArchive::detect_all(path, trust)?;
+        Ok(self.insert(found, &IpLevel::add_download))
+    }
+
+    /// Searches each vendor in `vendors` for IP available.
+    pub fn available(mut self, vendors: &Vec<Vendor>) -> Result<Self, Fault> {
+        for v in vendors {
+            self = self.detect(v.get_root(), &IpLevel::add_available, IpState::Available)?;
+        }
+        Ok(self)
     }
 
     pub fn inner(&self) -> &HashMap<PkgPart, IpLevel> {
         &self.inner
     }
 
+    /// Finds the ip named `name` in the catalog.
+    ///
+    /// A name without its full vendor and library qualification is accepted as
+    /// long as it only refers to a single ip in the catalog.
+    pub fn lookup(&self, name: &PkgPart) -> Result<Option<&IpLevel>, CatalogError> {
+        if let Some(lvl) = self.inner.get(name) {
+            return Ok(Some(lvl));
+        }
+        let mut found: Vec<(&PkgPart, &IpLevel)> = self
+            .inner
+            .iter()
+            .filter(|(key, _)| name.refers_to(key) == true)
+            .collect();
+        match found.len() {
+            0 => Ok(None),
+            1 => Ok(Some(found.pop().unwrap().1)),
+            _ => {
+                let mut names: Vec<PkgPart> = found.into_iter().map(|(k, _)| k.clone()).collect();
+                names.sort();
+                Err(CatalogError::AmbiguousName(name.clone(), names))
+            }
+        }
+    }
+
     pub fn inner_mut(&mut self) -> &mut HashMap<PkgPart, IpLevel> {
         &mut self.inner
     }
@@ -311,6 +376,13 @@ impl<'a> Catalog<'a> {
                 IpState::Downloaded,
             ));
         }
+        // read from vendors
+        for ip in kaban.get_availability() {
+            set.insert(VersionItem::new(
+                ip.get_man().get_ip().get_version(),
+                IpState::Available,
+            ));
+        }
         let mut arr: Vec<VersionItem> = set.into_iter().collect();
         arr.sort();
         arr.reverse();
@@ -325,20 +397,23 @@ impl<'a> Catalog<'a> {
     ///
     /// This function is generic enough to be used to catch ip at all 3 levels: dev, install, and available.
     fn detect(
-        mut self,
+        self,
         path: &PathBuf,
         add: &dyn Fn(&mut IpLevel, Ip) -> (),
         lvl: IpState,
     ) -> Result<Self, Fault> {
-        match lvl {
+        let found = match lvl {
             IpState::Installation => Ip::detect_all(path, false),
-            IpState::Available => todo!("only detect for available"),
-            IpState::Downloaded => IpArchive::detect_all(path),
+            IpState::Available => Ip::detect_available(path),
             _ => panic!("Unknown catalog state to find"),
-        }?
-        .into_iter()
-        .for_each(
-            |ip| match self.inner.get_mut(&ip.get_man().get_ip().get_name()) {
+        }?;
+        Ok(self.insert(found, add))
+    }
+
+    /// Adds each ip in `found` to its level in the catalog.
+    fn insert(mut self, found: Vec<Ip>, add: &dyn Fn(&mut IpLevel, Ip) -> ()) -> Self {
+        found.into_iter().for_each(|ip| {
+            match self.inner.get_mut(&ip.get_man().get_ip().get_name()) {
                 Some(lvl) => add(lvl, ip),
                 None => {
                     let pkgid = ip.get_man().get_ip().get_name().clone();
@@ -347,15 +422,20 @@ impl<'a> Catalog<'a> {
                     self.inner.insert(pkgid, lvl);
                     ()
                 }
-            },
-        );
-        Ok(self)
+            }
+        });
+        self
     }
 
     pub fn get_cache_path(&self) -> &PathBuf {
         self.cache.as_ref().unwrap()
     }
 
+    /// References the read-only cache layers in the order they are searched.
+    pub fn get_cache_layers(&self) -> &Vec<&'a PathBuf> {
+        &self.layers
+    }
+
     pub fn get_downloads_path(&self) -> &PathBuf {
         self.downloads.as_ref().unwrap()
     }
@@ -410,6 +490,58 @@ impl CacheEntry {
 mod test {
     use super::*;
 
+    #[test]
+    fn lookup_qualified_names() {
+        let mut catalog = Catalog::new();
+        for name in ["ks-tech.rary.fifo", "acme.ips.fifo", "acme.ips.uart"] {
+            catalog
+                .inner_mut()
+                .insert(PkgPart::from_str(name).unwrap(), IpLevel::new());
+        }
+        let find = |n: &str| catalog.lookup(&PkgPart::from_str(n).unwrap());
+
+        assert_eq!(find("acme.ips.fifo").unwrap().is_some(), true);
+        // unqualified names work when unambiguous
+        assert_eq!(find("uart").unwrap().is_some(), true);
+        assert_eq!(find("rary.fifo").unwrap().is_some(), true);
+        assert_eq!(find("spi").unwrap().is_none(), true);
+        // two vendors' ip no longer collide
+        assert_eq!(
+            find("fifo").unwrap_err().to_string(),
+            "ip name 'fifo' is ambiguous; it could be any of: acme.ips.fifo, ks-tech.rary.fifo\n\nTry qualifying the name with its vendor and library"
+        );
+    }
+
+    #[test]
+    fn find_slot_in_layers() {
+        let user = tempfile::tempdir().unwrap().into_path();
+        let system = tempfile::tempdir().unwrap().into_path();
+        let layers = vec![system.clone()];
+
+        let gates = CacheSlot::try_from_str("gates-1.0.0-0123456789").unwrap();
+        let lab = CacheSlot::try_from_str("lab1-0.1.0-9876543210").unwrap();
+        std::fs::create_dir(system.join(gates.to_string())).unwrap();
+
+        let catalog = Catalog::new()
+            .installations(&user)
+            .unwrap()
+            .layers(&layers)
+            .unwrap();
+        // lookups fall through to the read-only layer
+        assert_eq!(
+            catalog.find_cached_slot(&gates),
+            Some(system.join(gates.to_string()))
+        );
+        assert_eq!(catalog.is_cached_slot(&lab), false);
+
+        // the writable cache takes precedence
+        std::fs::create_dir(user.join(gates.to_string())).unwrap();
+        assert_eq!(
+            catalog.find_cached_slot(&gates),
+            Some(user.join(gates.to_string()))
+        );
+    }
+
     #[test]
     fn disp_set() {
         let ce = CacheEntry::from(&Uuid::nil());
@@ -510,6 +642,7 @@ impl AsRef<str> for DownloadSlot {
 pub enum CatalogError {
     SuggestInstall(PkgId, AnyVersion),
     NoVersionForIp(PkgId, AnyVersion),
+    AmbiguousName(PkgPart, Vec<PkgPart>),
 }
 
 impl std::error::Error for CatalogError {}
@@ -519,6 +652,7 @@ impl std::fmt::Display for CatalogError {
         match self {
             Self::SuggestInstall(target, version) => write!(f, "ip '{}' is not installed but is available\n\nTry installing the ip: `orbit install --ip {} -v {}`", target, target, version),
             Self::NoVersionForIp(pkgid, version) => write!(f, "ip '{}' has no version '{}'", pkgid, version),
+            Self::AmbiguousName(name, found) => write!(f, "ip name '{}' is ambiguous; it could be any of: {}\n\nTry qualifying the name with its vendor and library", name, found.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(", ")),
         }
     }
 }
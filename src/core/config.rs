@@ -1,14 +1,19 @@
 use crate::core::lang::vhdl::format::VhdlFormat;
 use crate::core::manifest::FromFile;
+use crate::core::manifest::Manifest;
 use crate::core::plugin::{Plugin, Plugins};
+use crate::core::profile::{Profile, Profiles};
 use crate::core::protocol::Protocol;
 use crate::core::protocol::Protocols;
+use crate::core::signature::{self, SignaturePolicy, Signer, Trust};
 use crate::util::anyerror::AnyError;
 use crate::util::filesystem;
 use crate::util::filesystem::Standardize;
+use colored::Colorize;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::error::Error;
+use std::ffi::OsString;
 use std::path::PathBuf;
 use std::str::FromStr;
 
@@ -33,8 +38,10 @@ impl FromStr for ConfigDocument {
 }
 
 const INCLUDE_KEY: &str = "include";
+const VENDOR_KEY: &str = "vendor";
 use crate::util::anyerror::Fault;
 use toml_edit::Array;
+use toml_edit::ArrayOfTables;
 use toml_edit::Formatted;
 use toml_edit::Item;
 use toml_edit::Table;
@@ -68,6 +75,13 @@ impl ConfigDocument {
         Self::append_list(&mut self.document, INCLUDE_KEY, item);
     }
 
+    /// Adds a new value to the `vendor` entry.
+    ///
+    /// Automatically creates the new key if it does not exist.
+    pub fn append_vendor(&mut self, item: &str) -> () {
+        Self::append_list(&mut self.document, VENDOR_KEY, item);
+    }
+
     /// Sets a value for the given entry in the toml document.
     ///
     /// Creates parent table and/or key if does not exist.
@@ -119,6 +133,100 @@ impl ConfigDocument {
         }
     }
 
+    /// Finds the position of the table identified by `name` in the array of
+    /// tables at `key`.
+    fn find_named_table(&self, key: &str, name: &str) -> Option<usize> {
+        let is_named = |t: Option<&Value>| t.and_then(|v| v.as_str()) == Some(name);
+        match self.document.get(key) {
+            Some(Item::ArrayOfTables(arr)) => arr
+                .iter()
+                .position(|t| is_named(t.get("name").and_then(|i| i.as_value()))),
+            Some(Item::Value(Value::Array(arr))) => arr
+                .iter()
+                .position(|t| is_named(t.as_inline_table().and_then(|t| t.get("name")))),
+            _ => None,
+        }
+    }
+
+    /// Adds the `entry` to the array of tables at `key`, such as a new `[[plugin]]`.
+    ///
+    /// Errors if the entry is invalid or if a table with the same `name` already
+    /// exists in the array.
+    pub fn add_named_table(&mut self, key: &str, entry: Table) -> Result<(), Fault> {
+        let name = entry
+            .get("name")
+            .and_then(|i| i.as_str())
+            .unwrap_or_default()
+            .to_string();
+        if self.find_named_table(key, &name).is_some() == true {
+            return Err(AnyError(format!(
+                "{} '{}' already exists in configuration",
+                key, name
+            )))?;
+        }
+        // verify the entry on its own before modifying the document
+        let mut probe = Document::new();
+        let mut arr = ArrayOfTables::new();
+        arr.push(entry.clone());
+        probe.insert(key, Item::ArrayOfTables(arr));
+        if let Err(e) = Config::from_str(&probe.to_string()) {
+            return Err(AnyError(format!(
+                "invalid {} '{}': {}",
+                key,
+                name,
+                e.message()
+            )))?;
+        }
+        // create the array if it does not exist
+        if self.document.contains_key(key) == false {
+            self.document
+                .insert(key, Item::ArrayOfTables(ArrayOfTables::new()));
+        }
+        match self.document.get_mut(key).unwrap() {
+            Item::ArrayOfTables(arr) => arr.push(entry),
+            // keep the style of an array written with inline tables
+            Item::Value(Value::Array(arr)) => arr.push(entry.into_inline_table()),
+            _ => {
+                return Err(AnyError(format!(
+                    "key '{}' in configuration is not an array of tables",
+                    key
+                )))?
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the table identified by `name` from the array of tables at `key`.
+    ///
+    /// Errors if the table does not exist.
+    pub fn remove_named_table(&mut self, key: &str, name: &str) -> Result<(), Fault> {
+        let i = match self.find_named_table(key, name) {
+            Some(i) => i,
+            None => {
+                return Err(AnyError(format!(
+                    "{} '{}' does not exist in configuration",
+                    key, name
+                )))?
+            }
+        };
+        let is_empty = match self.document.get_mut(key).unwrap() {
+            Item::ArrayOfTables(arr) => {
+                arr.remove(i);
+                arr.is_empty()
+            }
+            Item::Value(Value::Array(arr)) => {
+                arr.remove(i);
+                arr.is_empty()
+            }
+            _ => false,
+        };
+        // remove the key entirely once the array is empty
+        if is_empty == true {
+            self.document.remove(key);
+        }
+        Ok(())
+    }
+
     /// Writes the `document` to the `path`.
     ///
     /// Uses CONFIG_FILE as the filename to save to.
@@ -137,12 +245,7 @@ impl FromFile for ConfigDocument {
         match Self::from_str(&contents) {
             Ok(r) => Ok(r),
             // enter a blank lock file if failed (do not exit)
-            Err(e) => {
-                return Err(AnyError(format!(
-                    "Failed to parse configuration file at path {:?}: {}",
-                    path, e
-                )))?
-            }
+            Err(e) => return Err(ConfigError::from_toml(path, &contents, &e))?,
         }
     }
 }
@@ -152,6 +255,22 @@ pub enum Locality {
     Global,
     Local,
     Other,
+    Ip,
+    Env,
+}
+
+impl Locality {
+    /// Returns the rank of the configuration level, where lower ranks take
+    /// precedence over higher ranks.
+    fn precedence(&self) -> usize {
+        match self {
+            Self::Env => 0,
+            Self::Local => 1,
+            Self::Global => 2,
+            Self::Other => 3,
+            Self::Ip => 4,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -179,12 +298,16 @@ impl Configs {
                 // load the entry file
                 let cfg = match Config::from_file(&path) {
                     Ok(r) => r,
-                    Err(e) => {
-                        return Err(AnyError(format!(
-                            "Failed to load configuration file at path {:?}: {}",
-                            path, e
-                        )))?
-                    }
+                    Err(e) => match e.downcast::<ConfigError>() {
+                        // the error already points to the file
+                        Ok(e) => return Err(e)?,
+                        Err(e) => {
+                            return Err(AnyError(format!(
+                                "Failed to load configuration file at path {:?}: {}",
+                                path, e
+                            )))?
+                        }
+                    },
                 };
                 set.insert(path.clone());
                 configs.push((path.clone(), cfg, local.clone()));
@@ -204,6 +327,71 @@ impl Configs {
         Ok(Self { inner: configs })
     }
 
+    /// Loads the plugins defined in the working ip's manifest at `file`.
+    ///
+    /// These plugins have the lowest precedence, so a plugin defined in any
+    /// configuration file replaces a manifest's plugin of the same name.
+    pub fn load_manifest(self, file: PathBuf) -> Result<Self, Box<dyn Error>> {
+        let mut configs = self.inner;
+        let path = PathBuf::standardize(&file);
+        let mut cfg = Config::new();
+        cfg.plugin = Some(Manifest::from_file(&path)?.into_plugins());
+        configs.push((path, cfg, Locality::Ip));
+        Ok(Self { inner: configs })
+    }
+
+    /// Loads the overrides set by environment variables named `ORBIT_<TABLE>_<KEY>`.
+    ///
+    /// These overrides have the highest precedence.
+    pub fn load_env(self) -> Result<Self, Box<dyn Error>> {
+        let mut configs = self.inner;
+        let mut cfg = Config::from_env_vars(std::env::vars_os())?;
+        // paths set by the environment are relative to the working directory
+        cfg.resolve_paths(&std::env::current_dir()?);
+        if cfg != Config::new() {
+            configs.push((PathBuf::new(), cfg, Locality::Env));
+        }
+        Ok(Self { inner: configs })
+    }
+
+    /// Collects every effective entry as its key, value, and origin in the
+    /// precedence order.
+    ///
+    /// Entries that are combined across configurations, such as `include`, are
+    /// listed once for every origin that defines them. Only the configurations
+    /// at the level `lvl` are listed when it is provided.
+    pub fn list(&self, lvl: Option<&Locality>) -> Vec<(String, toml::Value, String)> {
+        let mut ordered: Vec<&(PathBuf, Config, Locality)> = self
+            .inner
+            .iter()
+            .filter(|p| lvl.is_none() || lvl == Some(&p.2))
+            .collect();
+        ordered.sort_by_key(|p| p.2.precedence());
+
+        let mut claimed = HashSet::new();
+        let mut list = Vec::new();
+        for (path, cfg, lvl) in ordered {
+            let mut entries = Vec::new();
+            if let Ok(value) = toml::Value::try_from(cfg) {
+                flatten("", &value, &mut entries);
+            }
+            let mut claims = HashSet::new();
+            for (claim, key, value) in entries {
+                if COMBINED_KEYS.contains(&key.as_str()) == false && claimed.contains(&claim) {
+                    continue;
+                }
+                let origin = match lvl {
+                    Locality::Env => format!("env:{}", to_env_var(&key)),
+                    _ => format!("file:{}", filesystem::into_std_str(path.clone())),
+                };
+                list.push((key, value, origin));
+                claims.insert(claim);
+            }
+            claimed.extend(claims);
+        }
+        list
+    }
+
     pub fn get_plugins(&self) -> HashMap<&str, &Plugin> {
         // iterate through all linked configs
         let mut map = HashMap::new();
@@ -237,34 +425,101 @@ impl From<Configs> for Config {
     /// Transform the multi-layered configurations into a single level.
     ///
     /// This function processes configurations in the following order:
-    /// 1. LOCAL
-    /// 2. GLOBAL
-    /// 3. INCLUDES (first to last)
+    /// 1. ENV
+    /// 2. LOCAL
+    /// 3. GLOBAL
+    /// 4. INCLUDES (first to last)
+    /// 5. IP
     ///
     /// Once a value is set (not None), then it will not be overridden by any
     /// configuration file later in the processing order. The processing order is
     /// the precedence order.
     fn from(value: Configs) -> Self {
         let mut single = Config::new();
-        let mut value = value;
-        // process local file
-        let local = value.inner.iter().position(|p| p.2 == Locality::Local);
-        if let Some(i) = local {
-            single.append(value.inner.remove(i).1);
-        }
-        // process global file
-        let global = value.inner.iter().position(|p| p.2 == Locality::Global);
-        if let Some(i) = global {
-            single.append(value.inner.remove(i).1);
-        }
-        // process includes in the order they were read
-        value.inner.into_iter().for_each(|p| {
+        let mut inner = value.inner;
+        // the sort is stable so includes remain in the order they were read
+        inner.sort_by_key(|p| p.2.precedence());
+        inner.into_iter().for_each(|p| {
             single.append(p.1);
         });
         single
     }
 }
 
+/// The entries that are combined across configurations rather than set once.
+const COMBINED_KEYS: [&str; 5] = [
+    "include",
+    "vendor",
+    "cache-path",
+    "mirror",
+    "signing.trusted-keys",
+];
+
+/// The tables whose keys can be overridden by environment variables.
+///
+/// Top-level keys are excluded since names like `ORBIT_PLUGIN` and `ORBIT_ENV_*`
+/// are already set by orbit for the processes it runs.
+const ENV_TABLES: [&str; 4] = ["general", "http", "signing", "vhdl-format"];
+
+/// Maps an environment variable named `ORBIT_<TABLE>_<KEY>` to its table and key.
+fn from_env_var(var: &str) -> Option<(String, String)> {
+    ENV_TABLES.iter().find_map(|table| {
+        let prefix = format!("ORBIT_{}_", table.to_uppercase().replace('-', "_"));
+        match var.strip_prefix(&prefix) {
+            Some(key) if key.is_empty() == false => {
+                Some((table.to_string(), key.to_lowercase().replace('_', "-")))
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Maps a dotted `key` to the name of the environment variable that overrides it.
+fn to_env_var(key: &str) -> String {
+    format!("ORBIT_{}", key.to_uppercase().replace(['.', '-'], "_"))
+}
+
+/// Flattens the `value` into a list of dotted keys and their values.
+///
+/// Arrays of tables identified by a `name`, such as plugins and protocols, are
+/// flattened under their names. Each entry also stores the key it claims, which
+/// is the name of the table for these arrays since they are set as a whole.
+fn flatten(prefix: &str, value: &toml::Value, list: &mut Vec<(String, String, toml::Value)>) {
+    let join = |key: &str| match prefix.is_empty() {
+        true => key.to_string(),
+        false => format!("{}.{}", prefix, key),
+    };
+    match value {
+        toml::Value::Table(table) => {
+            table.iter().for_each(|(k, v)| flatten(&join(k), v, list));
+        }
+        toml::Value::Array(arr)
+            if arr.is_empty() == false
+                && arr
+                    .iter()
+                    .all(|v| v.get("name").and_then(|n| n.as_str()).is_some())
+                    == true =>
+        {
+            for item in arr {
+                let group = join(item["name"].as_str().unwrap());
+                let mut fields = Vec::new();
+                item.as_table()
+                    .unwrap()
+                    .iter()
+                    .filter(|(k, _)| k.as_str() != "name")
+                    .for_each(|(k, v)| flatten(k, v, &mut fields));
+                fields.into_iter().for_each(|(_, k, v)| {
+                    list.push((group.clone(), format!("{}.{}", group, k), v))
+                });
+            }
+        }
+        _ => list.push((prefix.to_string(), prefix.to_string(), value.clone())),
+    }
+}
+
+/// The number of dependencies downloaded at the same time when not configured.
+pub const DEFAULT_DOWNLOAD_JOBS: usize = 4;
+
 #[derive(PartialEq, Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct General {
@@ -272,6 +527,10 @@ pub struct General {
     build_dir: Option<String>,
     #[serde(rename = "language-mode")]
     lang_mode: Option<LangMode>,
+    locked: Option<bool>,
+    offline: Option<bool>,
+    #[serde(rename = "download-jobs")]
+    download_jobs: Option<usize>,
 }
 
 impl General {
@@ -279,6 +538,9 @@ impl General {
         Self {
             build_dir: None,
             lang_mode: None,
+            locked: None,
+            offline: None,
+            download_jobs: None,
         }
     }
 
@@ -297,6 +559,23 @@ impl General {
             .clone()
     }
 
+    /// Checks if the lockfile is forbidden from being modified.
+    pub fn is_locked(&self) -> bool {
+        self.locked.unwrap_or(false)
+    }
+
+    /// Checks if fetching ip from the internet is forbidden.
+    pub fn is_offline(&self) -> bool {
+        self.offline.unwrap_or(false)
+    }
+
+    /// Access the maximum number of dependencies to download at the same time.
+    ///
+    /// A value of 0 is treated as 1.
+    pub fn get_download_jobs(&self) -> usize {
+        self.download_jobs.unwrap_or(DEFAULT_DOWNLOAD_JOBS).max(1)
+    }
+
     /// Merges any populated data from `rhs` into attributes that do not already
     /// have data defined in `self`.
     pub fn merge(&mut self, rhs: Option<Self>) {
@@ -309,6 +588,192 @@ impl General {
             if self.lang_mode.is_some() == false {
                 self.lang_mode = rhs.lang_mode
             }
+            // no locked mode defined so give it the value from `rhs`
+            if self.locked.is_some() == false {
+                self.locked = rhs.locked
+            }
+            // no offline mode defined so give it the value from `rhs`
+            if self.offline.is_some() == false {
+                self.offline = rhs.offline
+            }
+            // no download jobs defined so give it the value from `rhs`
+            if self.download_jobs.is_some() == false {
+                self.download_jobs = rhs.download_jobs
+            }
+        }
+    }
+}
+
+/// A replacement location for urls that begin with a particular prefix.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Mirror {
+    prefix: String,
+    url: String,
+}
+
+impl Mirror {
+    pub fn get_prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Http {
+    proxy: Option<String>,
+    #[serde(rename = "no-proxy")]
+    no_proxy: Option<String>,
+    #[serde(rename = "ca-bundle")]
+    ca_bundle: Option<PathBuf>,
+}
+
+impl Http {
+    pub fn new() -> Self {
+        Self {
+            proxy: None,
+            no_proxy: None,
+            ca_bundle: None,
+        }
+    }
+
+    /// Access the proxy to send requests through.
+    pub fn get_proxy(&self) -> Option<&String> {
+        self.proxy.as_ref()
+    }
+
+    /// Access the comma-separated list of hosts that do not use the proxy.
+    pub fn get_no_proxy(&self) -> Option<&String> {
+        self.no_proxy.as_ref()
+    }
+
+    /// Access the file of certificate authorities to verify servers with.
+    pub fn get_ca_bundle(&self) -> Option<&PathBuf> {
+        self.ca_bundle.as_ref()
+    }
+
+    /// Merges any populated data from `rhs` into attributes that do not already
+    /// have data defined in `self`.
+    pub fn merge(&mut self, rhs: Option<Self>) {
+        if let Some(rhs) = rhs {
+            if self.proxy.is_some() == false {
+                self.proxy = rhs.proxy
+            }
+            if self.no_proxy.is_some() == false {
+                self.no_proxy = rhs.no_proxy
+            }
+            if self.ca_bundle.is_some() == false {
+                self.ca_bundle = rhs.ca_bundle
+            }
+        }
+    }
+}
+
+/// The settings for reaching the internet when fetching ip.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Network {
+    mirrors: Vec<Mirror>,
+    http: Http,
+}
+
+impl Network {
+    pub fn new() -> Self {
+        Self {
+            mirrors: Vec::new(),
+            http: Http::new(),
+        }
+    }
+
+    pub fn get_http(&self) -> &Http {
+        &self.http
+    }
+
+    /// Replaces the beginning of the `url` with the mirror with the longest
+    /// matching prefix.
+    ///
+    /// Returns `None` if no mirror matches the `url`.
+    pub fn rewrite(&self, url: &str) -> Option<String> {
+        let mut best: Option<&Mirror> = None;
+        for m in &self.mirrors {
+            if url.starts_with(m.get_prefix()) == true
+                && best.map_or(true, |b| m.get_prefix().len() > b.get_prefix().len()) == true
+            {
+                best = Some(m);
+            }
+        }
+        best.map(|m| format!("{}{}", m.get_url(), &url[m.get_prefix().len()..]))
+    }
+}
+
+/// The settings for signing archives and verifying their signatures.
+#[derive(PartialEq, Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Signing {
+    policy: Option<SignaturePolicy>,
+    #[serde(rename = "trusted-keys")]
+    trusted_keys: Option<Vec<String>>,
+    key: Option<PathBuf>,
+    detached: Option<bool>,
+}
+
+impl Signing {
+    pub fn new() -> Self {
+        Self {
+            policy: None,
+            trusted_keys: None,
+            key: None,
+            detached: None,
+        }
+    }
+
+    /// Collects the policy and the trusted public keys for verifying archives.
+    pub fn get_trust(&self) -> Result<Trust, Fault> {
+        let mut keys = Vec::new();
+        for k in self.trusted_keys.as_ref().unwrap_or(&Vec::new()) {
+            match signature::parse_verifying_key(k) {
+                Ok(v) => keys.push(v),
+                Err(e) => Err(AnyError(format!("invalid trusted key {}: {}", k, e)))?,
+            }
+        }
+        Ok(Trust::new(self.policy.unwrap_or_default(), keys))
+    }
+
+    /// Loads the secret key for signing archives written by orbit.
+    ///
+    /// Returns `None` if no key is configured.
+    pub fn get_signer(&self) -> Result<Option<Signer>, Fault> {
+        match &self.key {
+            Some(k) => Ok(Some(Signer::new(
+                signature::load_signing_key(k)?,
+                self.detached.unwrap_or(false),
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    /// Merges any populated data from `rhs` into attributes that do not already
+    /// have data defined in `self`.
+    ///
+    /// The trusted keys from both are combined.
+    pub fn merge(&mut self, rhs: Option<Self>) {
+        if let Some(rhs) = rhs {
+            if self.policy.is_some() == false {
+                self.policy = rhs.policy
+            }
+            match &mut self.trusted_keys {
+                Some(v) => v.append(&mut rhs.trusted_keys.unwrap_or(Vec::new())),
+                None => self.trusted_keys = rhs.trusted_keys,
+            }
+            if self.key.is_some() == false {
+                self.key = rhs.key
+            }
+            if self.detached.is_some() == false {
+                self.detached = rhs.detached
+            }
         }
     }
 }
@@ -319,23 +784,36 @@ pub const CONFIG_FILE: &str = "config.toml";
 #[serde(deny_unknown_fields)]
 pub struct Config {
     include: Option<Vec<PathBuf>>,
+    vendor: Option<Vec<PathBuf>>,
+    #[serde(rename = "cache-path")]
+    cache_path: Option<Vec<PathBuf>>,
     env: Option<HashMap<String, String>>,
     plugin: Option<Plugins>,
     protocol: Option<Protocols>,
     #[serde(rename = "vhdl-format")]
     vhdl_format: Option<VhdlFormat>,
     general: Option<General>,
+    mirror: Option<Vec<Mirror>>,
+    http: Option<Http>,
+    signing: Option<Signing>,
+    profile: Option<Profiles>,
 }
 
 impl Config {
     pub fn new() -> Self {
         Self {
             include: None,
+            vendor: None,
+            cache_path: None,
             env: None,
             plugin: None,
             protocol: None,
             vhdl_format: None,
             general: None,
+            mirror: None,
+            http: None,
+            signing: None,
+            profile: None,
         }
     }
 
@@ -359,6 +837,16 @@ impl Config {
             Some(v) => v.append(&mut rhs.include.unwrap_or(Vec::new())),
             None => self.include = rhs.include,
         }
+        // combine 'vendor' entry
+        match &mut self.vendor {
+            Some(v) => v.append(&mut rhs.vendor.unwrap_or(Vec::new())),
+            None => self.vendor = rhs.vendor,
+        }
+        // combine 'cache-path' entry
+        match &mut self.cache_path {
+            Some(v) => v.append(&mut rhs.cache_path.unwrap_or(Vec::new())),
+            None => self.cache_path = rhs.cache_path,
+        }
         // combine '[general]' table
         match &mut self.general {
             Some(v) => v.merge(rhs.general),
@@ -391,6 +879,36 @@ impl Config {
             Some(v) => v.append(&mut rhs.protocol.unwrap_or(Vec::new())),
             None => self.protocol = rhs.protocol,
         }
+        // combine '[[mirror]]' array
+        match &mut self.mirror {
+            Some(v) => v.append(&mut rhs.mirror.unwrap_or(Vec::new())),
+            None => self.mirror = rhs.mirror,
+        }
+        // combine '[http]' table
+        match &mut self.http {
+            Some(v) => v.merge(rhs.http),
+            None => self.http = rhs.http,
+        }
+        // combine '[signing]' table
+        match &mut self.signing {
+            Some(v) => v.merge(rhs.signing),
+            None => self.signing = rhs.signing,
+        }
+        // combine '[profile]' table
+        match &mut self.profile {
+            Some(v) => {
+                let temp = rhs.profile.unwrap_or(Profiles::new());
+                for (key, val) in temp {
+                    match v.get_mut(&key) {
+                        Some(p) => p.merge(val),
+                        None => {
+                            v.insert(key, val);
+                        }
+                    }
+                }
+            }
+            None => self.profile = rhs.profile,
+        }
     }
 
     pub fn get_includes(&self) -> Vec<&PathBuf> {
@@ -400,6 +918,22 @@ impl Config {
         }
     }
 
+    /// Returns the list of paths to vendor directories.
+    pub fn get_vendors(&self) -> Vec<&PathBuf> {
+        match &self.vendor {
+            Some(v) => v.iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Returns the list of paths to read-only cache layers.
+    pub fn get_cache_layers(&self) -> Vec<&PathBuf> {
+        match &self.cache_path {
+            Some(v) => v.iter().collect(),
+            None => Vec::new(),
+        }
+    }
+
     pub fn get_plugins(&self) -> HashMap<&str, &Plugin> {
         let mut map = HashMap::new();
 
@@ -415,6 +949,14 @@ impl Config {
         map
     }
 
+    /// Returns the map of profiles by their names.
+    pub fn get_profiles(&self) -> HashMap<&str, &Profile> {
+        match &self.profile {
+            Some(map) => map.iter().map(|(k, v)| (k.as_str(), v)).collect(),
+            None => HashMap::new(),
+        }
+    }
+
     pub fn get_env(&self) -> &Option<HashMap<String, String>> {
         &self.env
     }
@@ -441,6 +983,130 @@ impl Config {
     pub fn get_general(&self) -> Option<&General> {
         self.general.as_ref()
     }
+
+    /// Collects the mirrors and http settings for fetching ip.
+    ///
+    /// Mirrors defined earlier take precedence over later mirrors with an
+    /// identical prefix.
+    pub fn get_network(&self) -> Network {
+        Network {
+            mirrors: self.mirror.clone().unwrap_or(Vec::new()),
+            http: self.http.clone().unwrap_or(Http::new()),
+        }
+    }
+
+    /// Access the settings for signing and verifying archives.
+    pub fn get_signing(&self) -> Signing {
+        self.signing.clone().unwrap_or(Signing::new())
+    }
+
+    /// Collects the entries set by any environment variables named
+    /// `ORBIT_<TABLE>_<KEY>` from `vars`, such as `ORBIT_GENERAL_BUILD_DIR`.
+    ///
+    /// Values are read as TOML values when possible and as strings otherwise.
+    /// Keys that are not part of their table are ignored with a warning.
+    pub fn from_env_vars<I>(vars: I) -> Result<Self, ConfigError>
+    where
+        I: IntoIterator<Item = (OsString, OsString)>,
+    {
+        let mut doc = toml::Table::new();
+        for (var, value) in vars {
+            // other variables are never read, so they may hold any bytes
+            let (var, table, key) = match var.into_string() {
+                Ok(var) => match from_env_var(&var) {
+                    Some((table, key)) => (var, table, key),
+                    None => continue,
+                },
+                Err(_) => continue,
+            };
+            let value = match value.into_string() {
+                Ok(v) => v,
+                Err(_) => {
+                    return Err(ConfigError::Env(
+                        var,
+                        String::from("value is not valid unicode"),
+                    ))
+                }
+            };
+            let parsed = match toml::from_str::<toml::Table>(&format!("v = {}", value)) {
+                Ok(mut t) => t.remove("v").unwrap(),
+                Err(_) => toml::Value::String(value.clone()),
+            };
+            // verify each variable on its own to report the one at fault
+            let value = match Self::check_env_entry(&table, &key, parsed) {
+                Ok(v) => v,
+                Err(e) => {
+                    // a string field accepts the text as written, such as a number
+                    match Self::check_env_entry(&table, &key, toml::Value::String(value)) {
+                        Ok(v) => v,
+                        // a stray variable should not stop every command from running
+                        Err(_) if e.message().starts_with("unknown field") == true => {
+                            println!(
+                                "{}: ignoring environment variable {}: {} has no field \"{}\"",
+                                "warning".yellow().bold(),
+                                var,
+                                table,
+                                key
+                            );
+                            continue;
+                        }
+                        Err(_) => return Err(ConfigError::Env(var, e.message().to_string())),
+                    }
+                }
+            };
+            if let toml::Value::Table(t) = doc
+                .entry(table)
+                .or_insert(toml::Value::Table(toml::Table::new()))
+            {
+                t.insert(key, value);
+            }
+        }
+        toml::Value::Table(doc)
+            .try_into()
+            .map_err(|e| ConfigError::Env(String::from("ORBIT_*"), e.message().to_string()))
+    }
+
+    /// Verifies the `value` is accepted for the `key` within `table`.
+    fn check_env_entry(
+        table: &str,
+        key: &str,
+        value: toml::Value,
+    ) -> Result<toml::Value, toml::de::Error> {
+        let mut entry = toml::Table::new();
+        entry.insert(key.to_string(), value.clone());
+        let mut single = toml::Table::new();
+        single.insert(table.to_string(), toml::Value::Table(entry));
+        toml::Value::Table(single).try_into::<Config>()?;
+        Ok(value)
+    }
+
+    /// Resolves the relative paths within the configuration against `base`.
+    fn resolve_paths(&mut self, base: &PathBuf) {
+        // resolve vendor paths
+        if let Some(vendors) = &mut self.vendor {
+            vendors.iter_mut().for_each(|v| {
+                *v = filesystem::resolve_rel_path2(&base, v);
+            });
+        }
+        // resolve cache layer paths
+        if let Some(layers) = &mut self.cache_path {
+            layers.iter_mut().for_each(|l| {
+                *l = filesystem::resolve_rel_path2(&base, l);
+            });
+        }
+        // resolve the certificate bundle
+        if let Some(http) = &mut self.http {
+            if let Some(ca) = &mut http.ca_bundle {
+                *ca = filesystem::resolve_rel_path2(&base, ca);
+            }
+        }
+        // resolve the signing key
+        if let Some(signing) = &mut self.signing {
+            if let Some(key) = &mut signing.key {
+                *key = filesystem::resolve_rel_path2(&base, key);
+            }
+        }
+    }
 }
 
 impl FromStr for Config {
@@ -464,6 +1130,8 @@ impl FromFile for Config {
             Ok(mut r) => {
                 // set roots for plugins and protocols
                 let base = PathBuf::standardize(path).parent().unwrap().to_path_buf();
+                // resolve paths relative to this configuration file
+                r.resolve_paths(&base);
                 if let Some(protos) = &mut r.protocol {
                     protos.iter_mut().for_each(|p| {
                         p.set_root(base.clone());
@@ -476,8 +1144,57 @@ impl FromFile for Config {
                 }
                 Ok(r)
             }
-            // enter a blank lock file if failed (do not exit)
-            Err(e) => return Err(e)?,
+            Err(e) => return Err(ConfigError::from_toml(path, &contents, &e))?,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ConfigError {
+    Invalid(PathBuf, Option<(usize, usize)>, String),
+    Env(String, String),
+}
+
+impl ConfigError {
+    /// Creates an error pointing to the line and column in the file at `path`
+    /// where the `contents` failed to parse.
+    pub fn from_toml(path: &PathBuf, contents: &str, err: &toml::de::Error) -> Self {
+        let pos = err.span().map(|span| {
+            let before = &contents[..span.start.min(contents.len())];
+            let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+            (
+                before.matches('\n').count() + 1,
+                before[line_start..].chars().count() + 1,
+            )
+        });
+        Self::Invalid(path.clone(), pos, err.message().to_string())
+    }
+}
+
+impl Error for ConfigError {}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(path, Some((line, col)), msg) => write!(
+                f,
+                "Invalid configuration at {}:{}:{}: {}",
+                filesystem::into_std_str(path.clone()),
+                line,
+                col,
+                msg
+            ),
+            Self::Invalid(path, None, msg) => write!(
+                f,
+                "Invalid configuration at {}: {}",
+                filesystem::into_std_str(path.clone()),
+                msg
+            ),
+            Self::Env(var, msg) => write!(
+                f,
+                "Invalid configuration from environment variable {}: {}",
+                var, msg
+            ),
         }
     }
 }
@@ -486,6 +1203,25 @@ impl FromFile for Config {
 mod test {
     use super::*;
 
+    const C_2: &str = r#"
+[[mirror]]
+prefix = "https://github.com/"
+url = "https://mirror.lab/github/"
+
+[[mirror]]
+prefix = "https://github.com/ks-tech/"
+url = "https://git.lab/ks-tech/"
+
+[http]
+proxy = "http://proxy.lab:3128"
+"#;
+
+    const C_3: &str = r#"
+[http]
+proxy = "http://proxy.home:8080"
+no-proxy = "localhost,.lab"
+"#;
+
     const C_0: &str = r#"
 # This is a blank configuration file.
 "#;
@@ -497,6 +1233,11 @@ mod test {
 include = [
     "path/to/other/config.toml",
 ]
+
+# list of vendor directories
+vendor = [
+    "path/to/vendor",
+]
     
 [[plugin]]
 name = "quartus"
@@ -542,10 +1283,320 @@ tab-size = 3
         }
     }
 
+    #[test]
+    fn merge_general() {
+        let mut lhs = General::new();
+        lhs.locked = Some(false);
+        let mut rhs = General::new();
+        rhs.locked = Some(true);
+        rhs.offline = Some(true);
+        lhs.merge(Some(rhs));
+        assert_eq!(lhs.is_locked(), false);
+        assert_eq!(lhs.is_offline(), true);
+        assert_eq!(General::new().is_offline(), false);
+        assert_eq!(General::new().get_download_jobs(), DEFAULT_DOWNLOAD_JOBS);
+    }
+
+    #[test]
+    fn mirrors() {
+        let mut cfg = Config::from_str(C_2).unwrap();
+        cfg.append(Config::from_str(C_3).unwrap());
+
+        let net = cfg.get_network();
+        // the longest matching prefix is used
+        assert_eq!(
+            net.rewrite("https://github.com/ks-tech/gates.git"),
+            Some(String::from("https://git.lab/ks-tech/gates.git"))
+        );
+        assert_eq!(
+            net.rewrite("https://github.com/cdotrus/orbit.git"),
+            Some(String::from("https://mirror.lab/github/cdotrus/orbit.git"))
+        );
+        assert_eq!(net.rewrite("https://gitlab.com/a/b.git"), None);
+        // earlier configurations take precedence
+        assert_eq!(
+            net.get_http().get_proxy(),
+            Some(&String::from("http://proxy.lab:3128"))
+        );
+        assert_eq!(
+            net.get_http().get_no_proxy(),
+            Some(&String::from("localhost,.lab"))
+        );
+        assert_eq!(Config::new().get_network(), Network::new());
+    }
+
+    #[test]
+    fn signing() {
+        let public: String = ed25519_dalek::SigningKey::from_bytes(&[1; 32])
+            .verifying_key()
+            .as_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        let mut cfg = Config::from_str(&format!(
+            "[signing]\npolicy = \"require\"\ntrusted-keys = [\"{}\"]\n",
+            public
+        ))
+        .unwrap();
+        let trust = cfg.get_signing().get_trust().unwrap();
+        assert_eq!(trust.get_policy(), &SignaturePolicy::Require);
+        assert_eq!(cfg.get_signing().get_signer().unwrap().is_none(), true);
+        // trusted keys are combined across configurations
+        cfg.append(
+            Config::from_str("[signing]\npolicy = \"warn\"\ntrusted-keys = [\"00\"]\n").unwrap(),
+        );
+        assert_eq!(cfg.get_signing().get_trust().is_err(), true);
+        // unsigned archives are allowed by default
+        assert_eq!(
+            Config::new().get_signing().get_trust().unwrap(),
+            Trust::new(SignaturePolicy::Ignore, Vec::new())
+        );
+    }
+
+    #[test]
+    fn profiles() {
+        let mut cfg = Config::from_str("[profile.sim]\nplugin = \"ghdl\"\n").unwrap();
+        cfg.append(
+            Config::from_str(
+                "[profile.sim]\nplugin = \"modelsim\"\nargs = [\"-c\"]\n\n[profile.synth]\nplugin = \"vivado\"\n",
+            )
+            .unwrap(),
+        );
+        let profiles = cfg.get_profiles();
+        assert_eq!(profiles.len(), 2);
+        // fields missing from the higher precedence profile are filled in
+        let sim = profiles.get("sim").unwrap();
+        assert_eq!(sim.get_plugin(), Some(&String::from("ghdl")));
+        assert_eq!(sim.get_args(), vec!["-c"]);
+    }
+
+    #[test]
+    fn cache_layers() {
+        let mut cfg = Config::from_str("cache-path = [\"/opt/orbit/cache\"]\n").unwrap();
+        cfg.append(Config::from_str("cache-path = [\"/srv/orbit/cache\"]\n").unwrap());
+        assert_eq!(
+            cfg.get_cache_layers(),
+            vec![
+                &PathBuf::from("/opt/orbit/cache"),
+                &PathBuf::from("/srv/orbit/cache")
+            ]
+        );
+        assert_eq!(Config::new().get_cache_layers().is_empty(), true);
+    }
+
     #[test]
     fn linked_configs() {
         Configs::new()
             .load(PathBuf::from("./tests/data/config1.toml"), Locality::Global)
             .unwrap();
     }
+
+    /// Converts the pairs into environment variables.
+    fn os_vars(vars: Vec<(&str, &str)>) -> Vec<(OsString, OsString)> {
+        vars.into_iter()
+            .map(|(k, v)| (OsString::from(k), OsString::from(v)))
+            .collect()
+    }
+
+    #[test]
+    fn env_overrides() {
+        let vars = os_vars(vec![
+            ("ORBIT_GENERAL_BUILD_DIR", "out"),
+            ("ORBIT_GENERAL_LOCKED", "true"),
+            ("ORBIT_VHDL_FORMAT_TAB_SIZE", "3"),
+            ("ORBIT_HOME", "/home/orbit"),
+            ("ORBIT_ENV_SEED", "4"),
+        ]);
+        let mut cfg = Config::from_env_vars(vars).unwrap();
+        cfg.append(Config::from_str("[general]\nbuild-dir = \"target\"\n").unwrap());
+        assert_eq!(cfg.get_general().unwrap().get_build_dir(), "out");
+        assert_eq!(cfg.get_general().unwrap().is_locked(), true);
+        assert_eq!(cfg.get_env(), &None);
+        assert_eq!(
+            to_env_var("vhdl-format.tab-size"),
+            "ORBIT_VHDL_FORMAT_TAB_SIZE"
+        );
+
+        // report the variable at fault
+        assert_eq!(
+            Config::from_env_vars(os_vars(vec![("ORBIT_GENERAL_LOCKED", "4")]))
+                .unwrap_err()
+                .to_string()
+                .contains("ORBIT_GENERAL_LOCKED"),
+            true
+        );
+        // text that only looks like another type is kept for string fields
+        assert_eq!(
+            Config::from_env_vars(os_vars(vec![("ORBIT_GENERAL_BUILD_DIR", "123")]))
+                .unwrap()
+                .get_general()
+                .unwrap()
+                .get_build_dir(),
+            "123"
+        );
+        // unknown keys are ignored
+        assert_eq!(
+            Config::from_env_vars(os_vars(vec![
+                ("ORBIT_HTTP_PORT", "80"),
+                ("ORBIT_GENERAL_LOCKED", "true"),
+            ]))
+            .unwrap()
+            .get_general()
+            .unwrap()
+            .is_locked(),
+            true
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn env_not_unicode() {
+        use std::os::unix::ffi::OsStringExt;
+        let bad = OsString::from_vec(vec![0x66, 0x80]);
+        // unrelated variables are never read
+        let vars = vec![
+            (OsString::from("HOME"), bad.clone()),
+            (bad.clone(), OsString::from("1")),
+        ];
+        assert_eq!(Config::from_env_vars(vars).unwrap(), Config::new());
+        assert_eq!(
+            Config::from_env_vars(vec![(OsString::from("ORBIT_GENERAL_BUILD_DIR"), bad)]),
+            Err(ConfigError::Env(
+                String::from("ORBIT_GENERAL_BUILD_DIR"),
+                String::from("value is not valid unicode")
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_position() {
+        let contents = "[general]\nbuild-dir = \"build\"\nlocked = 4\n";
+        let err = Config::from_str(contents).unwrap_err();
+        let err = ConfigError::from_toml(&PathBuf::from("/home/config.toml"), contents, &err);
+        match &err {
+            ConfigError::Invalid(_, pos, _) => assert_eq!(pos, &Some((3, 10))),
+            _ => panic!("expected invalid error"),
+        }
+        assert_eq!(
+            err.to_string()
+                .starts_with("Invalid configuration at /home/config.toml:3:10: "),
+            true
+        );
+    }
+
+    #[test]
+    fn named_tables() {
+        let mut doc = ConfigDocument::from_str(C_1).unwrap();
+        let mut entry = Table::new();
+        entry.insert("name", toml_edit::value("ghdl"));
+        entry.insert("command", toml_edit::value("python"));
+        doc.add_named_table("plugin", entry.clone()).unwrap();
+        // names must be unique
+        assert_eq!(doc.add_named_table("plugin", entry.clone()).is_err(), true);
+        // entries must be valid
+        let mut bad = entry.clone();
+        bad.insert("name", toml_edit::value("vivado"));
+        bad.insert("color", toml_edit::value(true));
+        assert_eq!(doc.add_named_table("plugin", bad).is_err(), true);
+
+        let cfg = Config::from_str(&doc.document.to_string()).unwrap();
+        assert_eq!(cfg.get_plugins().len(), 2);
+        // comments are preserved
+        assert_eq!(
+            doc.document
+                .to_string()
+                .contains("# list of vendor directories"),
+            true
+        );
+
+        doc.remove_named_table("protocol", "kstp").unwrap();
+        assert_eq!(doc.document.contains_key("protocol"), false);
+        assert_eq!(doc.remove_named_table("protocol", "kstp").is_err(), true);
+        doc.remove_named_table("plugin", "quartus").unwrap();
+        let cfg = Config::from_str(&doc.document.to_string()).unwrap();
+        assert_eq!(cfg.get_plugins().contains_key("ghdl"), true);
+        assert_eq!(cfg.get_plugins().len(), 1);
+
+        // arrays written with inline tables keep their style
+        let mut doc =
+            ConfigDocument::from_str("plugin = [{ name = \"sim\", command = \"ghdl\" }]\n")
+                .unwrap();
+        doc.add_named_table("plugin", entry).unwrap();
+        assert_eq!(doc.document["plugin"].is_array(), true);
+        assert_eq!(doc.document["plugin"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn list_origins() {
+        // collects the values and file names that provide the `key`
+        fn find<'a>(
+            list: &'a [(String, toml::Value, String)],
+            key: &str,
+        ) -> Vec<(String, &'a str)> {
+            list.iter()
+                .filter(|(k, _, _)| k == key)
+                .map(|(_, v, o)| (v.to_string(), o.rsplit('/').next().unwrap()))
+                .collect()
+        }
+
+        let cfgs = Configs::new()
+            .load(PathBuf::from("./tests/data/config1.toml"), Locality::Global)
+            .unwrap();
+        let list = cfgs.list(None);
+        // the first configuration to set the key provides it
+        assert_eq!(
+            find(&list, "env.key"),
+            vec![(String::from("\"1\""), "config1.toml")]
+        );
+        assert_eq!(find(&list, "include").len(), 1);
+        assert_eq!(cfgs.list(Some(&Locality::Local)).is_empty(), true);
+
+        let cfgs = Configs::new()
+            .load(
+                PathBuf::from("./tests/data/ip-plugins/config.toml"),
+                Locality::Global,
+            )
+            .unwrap()
+            .load_manifest(PathBuf::from("./tests/data/ip-plugins/Orbit.toml"))
+            .unwrap();
+        let list = cfgs.list(None);
+        // plugins are provided as a whole
+        assert_eq!(
+            find(&list, "plugin.sim.command"),
+            vec![(String::from("\"ghdl\""), "config.toml")]
+        );
+        assert_eq!(find(&list, "plugin.sim.args"), vec![]);
+        assert_eq!(
+            find(&list, "plugin.synth.command"),
+            vec![(String::from("\"vivado\""), "Orbit.toml")]
+        );
+    }
+
+    #[test]
+    fn manifest_plugins() {
+        use crate::core::plugin::Process;
+
+        let cfgs = Configs::new()
+            .load(
+                PathBuf::from("./tests/data/ip-plugins/config.toml"),
+                Locality::Global,
+            )
+            .unwrap()
+            .load_manifest(PathBuf::from("./tests/data/ip-plugins/Orbit.toml"))
+            .unwrap();
+        // the user configuration takes precedence over the manifest
+        let plugs = cfgs.get_plugins();
+        assert_eq!(plugs.len(), 2);
+        assert_eq!(plugs.get("sim").unwrap().get_command(), "ghdl");
+        assert_eq!(plugs.get("synth").unwrap().get_command(), "vivado");
+
+        let cfg = Config::from(cfgs);
+        let plugs = cfg.get_plugins();
+        assert_eq!(plugs.len(), 2);
+        assert_eq!(plugs.get("sim").unwrap().get_command(), "ghdl");
+        assert_eq!(
+            plugs.get("synth").unwrap().get_root(),
+            &PathBuf::standardize(PathBuf::from("./tests/data/ip-plugins"))
+        );
+    }
 }
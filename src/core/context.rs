@@ -1,10 +1,15 @@
 use crate::core::config::General;
 use crate::core::config::{Config, Configs, Locality};
+use crate::core::manifest::IP_MANIFEST_FILE;
 use crate::core::plugin::Plugin;
+use crate::core::signature::{Signer, Trust};
+use crate::core::vendor;
+use crate::core::vendor::Vendor;
 use crate::util::anyerror::AnyError;
 use crate::util::anyerror::Fault;
 use crate::util::environment::ORBIT_WIN_LITERAL_CMD;
 use crate::util::filesystem::Standardize;
+use colored::Colorize;
 use std::collections::HashMap;
 use std::env;
 use std::fs;
@@ -27,6 +32,8 @@ pub struct Context {
     home_path: PathBuf,
     /// Directory holding installed immutable tags of git repositories.
     cache_path: PathBuf,
+    /// Read-only directories holding installations shared beneath the cache.
+    cache_layers: Vec<PathBuf>,
     /// Directory holding orbit IP downloaded
     download_path: PathBuf,
     /// The parent path to the current ip `Orbit.toml` manifest file.
@@ -51,6 +58,7 @@ impl Context {
         Context {
             home_path: home,
             cache_path: cache,
+            cache_layers: Vec::new(),
             download_path: downloads,
             ip_path: None,
             plugins: HashMap::new(),
@@ -101,6 +109,42 @@ impl Context {
         Ok(self)
     }
 
+    /// Sets the read-only cache layers searched after the cache. The paths listed
+    /// in `key` come first, followed by the `cache-path` entries of the configuration.
+    ///
+    /// Note: the `self.config` must already be determined before invocation.
+    pub fn cache_layers(mut self, key: &str) -> Result<Context, Fault> {
+        let mut layers: Vec<PathBuf> = match env::var_os(key) {
+            Some(s) => env::split_paths(&s)
+                .filter(|p| p.as_os_str().is_empty() == false)
+                .collect(),
+            None => Vec::new(),
+        };
+        layers.extend(self.config.get_cache_layers().into_iter().cloned());
+
+        for layer in layers {
+            // a shared cache may only be mounted on some machines
+            if layer.is_dir() == false {
+                println!(
+                    "{}: skipping cache layer {}: directory does not exist",
+                    "warning".yellow().bold(),
+                    layer.display()
+                );
+                continue;
+            }
+            let layer = PathBuf::standardize(&layer);
+            // the writable cache is never treated as a layer
+            if layer == PathBuf::standardize(&self.cache_path) || self.cache_layers.contains(&layer)
+            {
+                continue;
+            }
+            self.cache_layers.push(layer);
+        }
+        // set the environment variable
+        env::set_var(key, env::join_paths(&self.cache_layers)?);
+        Ok(self)
+    }
+
     /// Checks if the cache tag file is properly configured in the set cache directory.
     ///
     /// Returns an `Err` holding the path to the needed cache file if the path was
@@ -177,6 +221,11 @@ impl Context {
         &self.cache_path
     }
 
+    /// References the read-only cache layers in the order they are searched.
+    pub fn get_cache_layers(&self) -> &Vec<PathBuf> {
+        &self.cache_layers
+    }
+
     /// References the downloads directory
     pub fn get_downloads_path(&self) -> &PathBuf {
         &self.download_path
@@ -202,14 +251,22 @@ impl Context {
         // if in ip, also look along current directory for a /.orbit/config.toml file to load (local configuration)
         self.all_configs = if let Some(ip_dir) = self.get_ip_path() {
             let local_path = ip_dir.join(".orbit").join(name);
-            if local_path.exists() == true {
+            let cfg = if local_path.exists() == true {
                 cfg.load(local_path, Locality::Local)?
             } else {
                 cfg
+            };
+            // plugins defined by the ip have the lowest precedence
+            let man_path = ip_dir.join(IP_MANIFEST_FILE);
+            if man_path.exists() == true {
+                cfg.load_manifest(man_path)?
+            } else {
+                cfg
             }
         } else {
             cfg
-        };
+        }
+        .load_env()?;
 
         // @TODO: FIXME (clone?)
         // initialize and load the global configuration
@@ -217,14 +274,22 @@ impl Context {
         // if in ip, also look along current directory for a /.orbit/config.toml file to load (local configuration)
         self.config = if let Some(ip_dir) = self.get_ip_path() {
             let local_path = ip_dir.join(".orbit").join(name);
-            if local_path.exists() == true {
+            let cfg = if local_path.exists() == true {
                 cfg.load(local_path, Locality::Local)?
             } else {
                 cfg
+            };
+            // plugins defined by the ip have the lowest precedence
+            let man_path = ip_dir.join(IP_MANIFEST_FILE);
+            if man_path.exists() == true {
+                cfg.load_manifest(man_path)?
+            } else {
+                cfg
             }
         } else {
             cfg
         }
+        .load_env()?
         .into();
         // @todo: dynamically set from environment variables from configuration data
         Ok(self)
@@ -260,6 +325,49 @@ impl Context {
         }
     }
 
+    /// Checks if the lockfile is forbidden from being modified.
+    pub fn is_locked(&self) -> bool {
+        match self.config.get_general() {
+            Some(g) => g.is_locked(),
+            None => General::new().is_locked(),
+        }
+    }
+
+    /// Checks if fetching ip from the internet is forbidden.
+    pub fn is_offline(&self) -> bool {
+        match self.config.get_general() {
+            Some(g) => g.is_offline(),
+            None => General::new().is_offline(),
+        }
+    }
+
+    /// Access the maximum number of dependencies to download at the same time.
+    pub fn get_download_jobs(&self) -> usize {
+        match self.config.get_general() {
+            Some(g) => g.get_download_jobs(),
+            None => General::new().get_download_jobs(),
+        }
+    }
+
+    /// Collects the policy and trusted keys for verifying downloaded archives.
+    pub fn get_trust(&self) -> Result<Trust, Fault> {
+        self.config.get_signing().get_trust()
+    }
+
+    /// Loads the secret key for signing archives, if one is configured.
+    pub fn get_signer(&self) -> Result<Option<Signer>, Fault> {
+        self.config.get_signing().get_signer()
+    }
+
+    /// Loads every vendor known from the configuration and from the `vendor`
+    /// directory at the home path.
+    pub fn get_vendors(&self) -> Result<Vec<Vendor>, Fault> {
+        vendor::collect_vendors(
+            &self.home_path.join(vendor::VENDOR_DIR),
+            self.config.get_vendors(),
+        )
+    }
+
     /// Access the ip directory detected from the current working directory.
     pub fn get_ip_path(&self) -> Option<&path::PathBuf> {
         self.ip_path.as_ref()
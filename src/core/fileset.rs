@@ -1,3 +1,7 @@
+use crate::core::lang::LangMode;
+use crate::core::variable;
+use crate::core::variable::VariableTable;
+use crate::util::anyerror::Fault;
 use glob::{Pattern, PatternError};
 use serde_derive::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -25,21 +29,32 @@ impl Filesets {
     // }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct Fileset {
     name: String,
     pattern: Style,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Style(Pattern);
 
 impl Style {
     pub fn inner(&self) -> &Pattern {
         &self.0
     }
+
+    /// Checks if the `file` matches the pattern, ignoring case.
+    pub fn matches(&self, file: &str) -> bool {
+        self.0.matches_with(file, MATCH_OPTIONS)
+    }
 }
 
+const MATCH_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
 impl From<Pattern> for Style {
     fn from(value: Pattern) -> Self {
         Self(value)
@@ -59,6 +74,7 @@ impl FromStr for Style {
 }
 
 use serde::de::{self};
+use serde::ser::SerializeMap;
 use serde::Serializer;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -101,6 +117,239 @@ impl Serialize for Style {
     }
 }
 
+/// The ips a [Rule] collects files from.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub enum Scope {
+    /// Only the current working ip.
+    #[serde(rename = "local")]
+    Local,
+    /// Only the dependencies of the current working ip.
+    #[serde(rename = "deps")]
+    Deps,
+    /// The current working ip and its dependencies.
+    #[serde(rename = "all")]
+    All,
+}
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self::Local
+    }
+}
+
+/// A fileset definition that may have multiple include and exclude patterns
+/// and conditions on which ips it collects files from.
+///
+/// A rule can be written as a single glob-style pattern string or as a table.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Rule {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    scope: Scope,
+    ip: Vec<String>,
+    language: Option<LangMode>,
+}
+
+impl FromStr for Rule {
+    type Err = FilesetError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rule = Self {
+            include: vec![s.to_string()],
+            exclude: Vec::new(),
+            scope: Scope::default(),
+            ip: Vec::new(),
+            language: None,
+        };
+        rule.validate()?;
+        Ok(rule)
+    }
+}
+
+impl Rule {
+    /// Verifies there is at least one include pattern and every pattern is valid.
+    fn validate(&self) -> Result<(), FilesetError> {
+        if self.include.is_empty() == true {
+            return Err(FilesetError::EmptyPattern);
+        }
+        for p in self
+            .include
+            .iter()
+            .chain(self.exclude.iter())
+            .chain(self.ip.iter())
+        {
+            if p.is_empty() == true {
+                return Err(FilesetError::EmptyPattern);
+            }
+            if let Err(e) = Pattern::new(p) {
+                return Err(FilesetError::PatternError(p.to_string(), e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks if the rule is written as only a single include pattern.
+    fn is_simple(&self) -> bool {
+        self.include.len() == 1
+            && self.exclude.is_empty() == true
+            && self.scope == Scope::Local
+            && self.ip.is_empty() == true
+            && self.language.is_none() == true
+    }
+
+    pub fn get_scope(&self) -> &Scope {
+        &self.scope
+    }
+
+    /// Checks if the rule is enabled for the language `mode`.
+    ///
+    /// A rule without a language condition is always enabled.
+    pub fn is_enabled(&self, mode: &LangMode) -> bool {
+        match &self.language {
+            Some(LangMode::Vhdl) => mode.supports_vhdl(),
+            Some(LangMode::Verilog) => mode.supports_verilog(),
+            Some(LangMode::Mixed) => mode == &LangMode::Mixed,
+            None => true,
+        }
+    }
+
+    /// Checks if the rule collects files from the ip called `name`, where
+    /// `is_local` is true if it is the current working ip.
+    pub fn accepts_ip(&self, name: &str, is_local: bool) -> bool {
+        let in_scope = match self.scope {
+            Scope::Local => is_local == true,
+            Scope::Deps => is_local == false,
+            Scope::All => true,
+        };
+        in_scope == true
+            && (self.ip.is_empty() == true
+                || self.ip.iter().any(|p| match Pattern::new(p) {
+                    Ok(p) => p.matches(name),
+                    Err(_) => false,
+                }))
+    }
+
+    /// Returns the files matching any include pattern and no exclude pattern.
+    ///
+    /// Variable substitution is performed on each pattern with `vtable`.
+    pub fn collect_files<'a>(
+        &self,
+        files: &'a [String],
+        vtable: &VariableTable,
+    ) -> Result<Vec<&'a String>, Fault> {
+        let compile = |list: &Vec<String>| -> Result<Vec<Style>, Fault> {
+            let mut styles = Vec::new();
+            for p in list {
+                styles.push(Style::from_str(&variable::substitute(
+                    p.to_string(),
+                    vtable,
+                )?)?);
+            }
+            Ok(styles)
+        };
+        let include = compile(&self.include)?;
+        let exclude = compile(&self.exclude)?;
+        Ok(files
+            .iter()
+            .filter(|f| {
+                include.iter().any(|p| p.matches(f)) == true
+                    && exclude.iter().any(|p| p.matches(f)) == false
+            })
+            .collect())
+    }
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.include.join(", "))?;
+        if self.exclude.is_empty() == false {
+            write!(f, " (exclude: {})", self.exclude.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'de> Deserialize<'de> for Rule {
+    fn deserialize<D>(deserializer: D) -> Result<Rule, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct RuleTable {
+            include: Vec<String>,
+            #[serde(default)]
+            exclude: Vec<String>,
+            #[serde(default)]
+            scope: Scope,
+            #[serde(default)]
+            ip: Vec<String>,
+            language: Option<LangMode>,
+        }
+
+        struct LayerVisitor;
+
+        impl<'de> de::Visitor<'de> for LayerVisitor {
+            type Value = Rule;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a glob-style pattern or a table")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Rule::from_str(v).map_err(de::Error::custom)
+            }
+
+            fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+            where
+                M: de::MapAccess<'de>,
+            {
+                let t: RuleTable =
+                    Deserialize::deserialize(de::value::MapAccessDeserializer::new(map))?;
+                let rule = Rule {
+                    include: t.include,
+                    exclude: t.exclude,
+                    scope: t.scope,
+                    ip: t.ip,
+                    language: t.language,
+                };
+                rule.validate().map_err(de::Error::custom)?;
+                Ok(rule)
+            }
+        }
+
+        deserializer.deserialize_any(LayerVisitor)
+    }
+}
+
+impl Serialize for Rule {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.is_simple() == true {
+            serializer.serialize_str(&self.include[0])
+        } else {
+            let mut map = serializer.serialize_map(None)?;
+            map.serialize_entry("include", &self.include)?;
+            if self.exclude.is_empty() == false {
+                map.serialize_entry("exclude", &self.exclude)?;
+            }
+            map.serialize_entry("scope", &self.scope)?;
+            if self.ip.is_empty() == false {
+                map.serialize_entry("ip", &self.ip)?;
+            }
+            if let Some(lang) = &self.language {
+                map.serialize_entry("language", lang)?;
+            }
+            map.end()
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum FilesetError {
     MissingSeparator(char),
@@ -226,7 +475,13 @@ impl Fileset {
     ///
     /// The format goes FILESET_NAME`\t`LIBRARY_NAME`\t`FILE_PATH
     pub fn to_blueprint_string(&self, file: &str) -> String {
-        format!("{}\t{}\t{}\n", self.name, "work", file)
+        self.to_blueprint_string_in(file, "work")
+    }
+
+    /// Creates format for blueprint.tsv file for a custom fileset with a file
+    /// belonging to the HDL `library`.
+    pub fn to_blueprint_string_in(&self, file: &str, library: &str) -> String {
+        format!("{}\t{}\t{}\n", self.name, library, file)
     }
 }
 
@@ -296,6 +551,98 @@ mod test {
         );
     }
 
+    #[test]
+    fn rule_from_toml() {
+        #[derive(Deserialize, Serialize)]
+        struct Map {
+            fileset: HashMap<String, Rule>,
+        }
+
+        let map: Map = toml::from_str(
+            r#"
+fileset.constraints = "*.xdc"
+fileset.ip-cores = { include = ["*.xci", "*.xcix"], exclude = ["*/sim/*"], scope = "all", ip = ["ks-tech.*"], language = "vhdl" }
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            map.fileset.get("constraints").unwrap(),
+            &Rule::from_str("*.xdc").unwrap()
+        );
+        let rule = map.fileset.get("ip-cores").unwrap();
+        assert_eq!(rule.include, vec!["*.xci", "*.xcix"]);
+        assert_eq!(rule.exclude, vec!["*/sim/*"]);
+        assert_eq!(rule.get_scope(), &Scope::All);
+        assert_eq!(rule.language, Some(LangMode::Vhdl));
+        // simple rules serialize back into a single pattern
+        let text = toml::to_string(&map).unwrap();
+        assert_eq!(text.contains("constraints = \"*.xdc\""), true);
+        let map2: Map = toml::from_str(&text).unwrap();
+        assert_eq!(map2.fileset.get("ip-cores"), Some(rule));
+
+        // reject missing and invalid patterns
+        assert_eq!(
+            toml::from_str::<Map>("fileset.a = { include = [] }").is_err(),
+            true
+        );
+        assert_eq!(
+            toml::from_str::<Map>("fileset.a = { include = [\"[\"] }").is_err(),
+            true
+        );
+        assert_eq!(
+            toml::from_str::<Map>("fileset.a = { include = [\"*\"], scope = \"world\" }").is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn rule_collect_files() {
+        let rule: Rule = toml::from_str(
+            r#"
+include = ["*.xci", "{{ orbit.bench }}.py"]
+exclude = ["*/sim/*"]
+"#,
+        )
+        .unwrap();
+        let mut vtable = VariableTable::new();
+        vtable.add("orbit.bench", "adder_tb");
+        let files = vec![
+            String::from("/ip/cores/fifo.xci"),
+            String::from("/ip/cores/sim/fifo.xci"),
+            String::from("/ip/adder_tb.py"),
+            String::from("/ip/mult_tb.py"),
+            String::from("/ip/top.vhd"),
+        ];
+        assert_eq!(
+            rule.collect_files(&files, &vtable).unwrap(),
+            vec![&files[0], &files[2]]
+        );
+    }
+
+    #[test]
+    fn rule_conditions() {
+        let rule = Rule::from_str("*.xdc").unwrap();
+        assert_eq!(rule.accepts_ip("gates", true), true);
+        assert_eq!(rule.accepts_ip("gates", false), false);
+        assert_eq!(rule.is_enabled(&LangMode::Verilog), true);
+
+        let rule: Rule = toml::from_str(
+            r#"
+include = ["*.xdc"]
+scope = "deps"
+ip = ["ks-tech.*", "uart"]
+language = "verilog"
+"#,
+        )
+        .unwrap();
+        assert_eq!(rule.accepts_ip("ks-tech.rary.fifo", false), true);
+        assert_eq!(rule.accepts_ip("uart", false), true);
+        assert_eq!(rule.accepts_ip("uart", true), false);
+        assert_eq!(rule.accepts_ip("gates", false), false);
+        assert_eq!(rule.is_enabled(&LangMode::Vhdl), false);
+        assert_eq!(rule.is_enabled(&LangMode::Mixed), true);
+    }
+
     #[test]
     fn detect_vhdl_files() {
         let s = "filename.vhd";
@@ -0,0 +1,297 @@
+//! A hook is a user-defined command attached to a stage of the planning and
+//! building workflow, such as a code generator that runs before planning or a
+//! report collector that runs after a build.
+
+use crate::core::ip::Ip;
+use crate::core::plugin::Plugin;
+use crate::core::plugin::Process;
+use crate::core::variable;
+use crate::core::variable::VariableError;
+use crate::core::variable::VariableTable;
+use serde_derive::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Stage {
+    PrePlan,
+    PostPlan,
+    PreBuild,
+    PostBuild,
+}
+
+impl std::fmt::Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PrePlan => write!(f, "pre-plan"),
+            Self::PostPlan => write!(f, "post-plan"),
+            Self::PreBuild => write!(f, "pre-build"),
+            Self::PostBuild => write!(f, "post-build"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    #[serde(rename = "pre-plan", skip_serializing_if = "Vec::is_empty", default)]
+    pre_plan: Vec<Hook>,
+    #[serde(rename = "post-plan", skip_serializing_if = "Vec::is_empty", default)]
+    post_plan: Vec<Hook>,
+    #[serde(rename = "pre-build", skip_serializing_if = "Vec::is_empty", default)]
+    pre_build: Vec<Hook>,
+    #[serde(rename = "post-build", skip_serializing_if = "Vec::is_empty", default)]
+    post_build: Vec<Hook>,
+}
+
+impl Hooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pre_plan.is_empty()
+            && self.post_plan.is_empty()
+            && self.pre_build.is_empty()
+            && self.post_build.is_empty()
+    }
+
+    /// References the list of hooks attached to the `stage`.
+    pub fn get(&self, stage: Stage) -> &Vec<Hook> {
+        match stage {
+            Stage::PrePlan => &self.pre_plan,
+            Stage::PostPlan => &self.post_plan,
+            Stage::PreBuild => &self.pre_build,
+            Stage::PostBuild => &self.post_build,
+        }
+    }
+
+    /// Sets the root directory from where every hook's command should reference paths from.
+    pub fn root(mut self, root: PathBuf) -> Self {
+        self.set_root(root);
+        self
+    }
+
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.pre_plan
+            .iter_mut()
+            .chain(self.post_plan.iter_mut())
+            .chain(self.pre_build.iter_mut())
+            .chain(self.post_build.iter_mut())
+            .for_each(|h| h.root = Some(root.clone()));
+    }
+
+    /// Runs the hooks attached to the `stage` in order from the directory `dir`.
+    ///
+    /// Stops at the first hook that fails. The `owner` describes where the hooks
+    /// were defined for reporting errors.
+    pub fn run(
+        &self,
+        stage: Stage,
+        owner: &str,
+        vtable: &VariableTable,
+        verbose: bool,
+        dir: &str,
+    ) -> Result<(), HookError> {
+        for hook in self.get(stage) {
+            let hook = match hook.clone().replace_vars(vtable) {
+                Ok(h) => h,
+                Err(e) => {
+                    return Err(HookError::Failed(
+                        stage,
+                        owner.to_string(),
+                        hook.to_string(),
+                        e.to_string(),
+                    ))
+                }
+            };
+            if verbose == true {
+                println!("info: Running {} hook from {}", stage, owner);
+            }
+            if let Err(e) = hook.execute(&[], verbose, dir) {
+                return Err(HookError::Failed(
+                    stage,
+                    owner.to_string(),
+                    hook.to_string(),
+                    e.to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Hooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for stage in [
+            Stage::PrePlan,
+            Stage::PostPlan,
+            Stage::PreBuild,
+            Stage::PostBuild,
+        ] {
+            for hook in self.get(stage) {
+                writeln!(f, "  {:<16}{}", stage.to_string(), hook)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Hook {
+    command: String,
+    args: Option<Vec<String>>,
+    #[serde(skip_serializing, skip_deserializing)]
+    root: Option<PathBuf>,
+}
+
+impl Hook {
+    /// Performs variable substitution on the command and arguments for the hook.
+    pub fn replace_vars(mut self, vtable: &VariableTable) -> Result<Self, VariableError> {
+        self.command = variable::substitute(self.command, vtable)?;
+        self.args = match self.args {
+            Some(args) => Some(
+                args.into_iter()
+                    .map(|arg| variable::substitute(arg, vtable))
+                    .collect::<Result<Vec<String>, VariableError>>()?,
+            ),
+            None => None,
+        };
+        Ok(self)
+    }
+}
+
+impl std::fmt::Display for Hook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{}",
+            self.command,
+            self.args
+                .as_ref()
+                .unwrap_or(&Vec::new())
+                .iter()
+                .fold(String::new(), |x, y| x + " " + y)
+        )
+    }
+}
+
+impl Process for Hook {
+    fn get_root(&self) -> &PathBuf {
+        self.root.as_ref().unwrap()
+    }
+
+    fn get_command(&self) -> &String {
+        &self.command
+    }
+
+    fn get_args(&self) -> Vec<&String> {
+        match &self.args {
+            Some(list) => list.iter().collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+/// Runs the hooks for the `stage` defined by the `target` ip's manifest followed
+/// by the hooks defined by the `plug`, if one is selected.
+pub fn run_hooks(
+    stage: Stage,
+    target: &Ip,
+    plug: Option<&Plugin>,
+    vtable: &VariableTable,
+    verbose: bool,
+    dir: &str,
+) -> Result<(), HookError> {
+    target
+        .get_man()
+        .get_hooks()
+        .clone()
+        .root(target.get_root().clone())
+        .run(
+            stage,
+            &format!("ip '{}'", target.get_man().get_ip().get_name()),
+            vtable,
+            verbose,
+            dir,
+        )?;
+    if let Some(p) = plug {
+        p.get_hooks().run(
+            stage,
+            &format!("plugin '{}'", p.get_alias()),
+            vtable,
+            verbose,
+            dir,
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(Debug, PartialEq)]
+pub enum HookError {
+    Failed(Stage, String, String, String),
+}
+
+impl Error for HookError {}
+
+impl std::fmt::Display for HookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Failed(stage, owner, cmd, err) => write!(
+                f,
+                "{} hook from {} failed: {}\n\nCommand: {}",
+                stage, owner, err, cmd
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const H_1: &str = r#"
+pre-plan = [{ command = "python", args = ["./scripts/gen.py", "{{ orbit.ip.name }}"] }]
+post-build = [
+    { command = "bash", args = ["collect.sh"] },
+    { command = "echo" },
+]
+"#;
+
+    #[test]
+    fn from_toml_string() {
+        let hooks: Hooks = toml::from_str(H_1).unwrap();
+        assert_eq!(hooks.get(Stage::PrePlan).len(), 1);
+        assert_eq!(hooks.get(Stage::PostPlan).len(), 0);
+        assert_eq!(hooks.get(Stage::PreBuild).len(), 0);
+        assert_eq!(hooks.get(Stage::PostBuild).len(), 2);
+        assert_eq!(hooks.is_empty(), false);
+        assert_eq!(Hooks::new().is_empty(), true);
+        // reject unknown stages
+        assert_eq!(toml::from_str::<Hooks>("pre-install = []").is_err(), true);
+    }
+
+    #[test]
+    fn replace_vars() {
+        let hooks: Hooks = toml::from_str(H_1).unwrap();
+        let mut vtable = VariableTable::new();
+        vtable.add("orbit.ip.name", "gates");
+        let hook = hooks.get(Stage::PrePlan)[0]
+            .clone()
+            .replace_vars(&vtable)
+            .unwrap();
+        assert_eq!(hook.to_string(), "python ./scripts/gen.py gates");
+        assert_eq!(hooks.get(Stage::PostBuild)[1].to_string(), "echo");
+    }
+
+    #[test]
+    fn set_root() {
+        let hooks: Hooks = toml::from_str(H_1).unwrap();
+        let hooks = hooks.root(PathBuf::from("/home/orbit"));
+        assert_eq!(
+            hooks.get(Stage::PostBuild)[1].get_root(),
+            &PathBuf::from("/home/orbit")
+        );
+    }
+}
@@ -17,6 +17,7 @@ use super::pubfile::PublicList;
 use super::pubfile::Visibility;
 use crate::core::lockfile::LockEntry;
 use crate::core::manifest::IP_MANIFEST_FILE;
+use crate::core::manifest::IP_MANIFEST_PATTERN_FILE;
 use crate::core::manifest::ORBIT_METADATA_FILE;
 use crate::core::manifest::ORBIT_SUM_FILE;
 use crate::core::uuid::Uuid;
@@ -26,6 +27,7 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::error::Error;
 use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use toml_edit::Document;
 
 // add state to `root` (make enum) to determine if is real path or not
@@ -33,6 +35,7 @@ use toml_edit::Document;
 pub enum Mapping {
     Physical,
     Virtual(Vec<u8>),
+    Imaginary,
 }
 
 impl Mapping {
@@ -43,6 +46,13 @@ impl Mapping {
         }
     }
 
+    pub fn is_imaginary(&self) -> bool {
+        match &self {
+            Self::Imaginary => true,
+            _ => false,
+        }
+    }
+
     pub fn as_bytes(&self) -> Option<&Vec<u8>> {
         match &self {
             Self::Virtual(b) => Some(b),
@@ -183,6 +193,22 @@ impl Ip {
         })
     }
 
+    /// Loads a manifest copy tracked by a vendor at `man_path`.
+    ///
+    /// The resulting [Ip] is only known to be available; none of its files
+    /// exist on the local filesystem.
+    pub fn load_available(man_path: PathBuf) -> Result<Self, Box<dyn Error>> {
+        let man = Manifest::from_file(&man_path)?;
+        let root = man_path.parent().unwrap().to_path_buf();
+        Ok(Self {
+            mapping: Mapping::Imaginary,
+            root: root,
+            data: man,
+            lock: LockFile::new(),
+            uuid: Uuid::nil(),
+        })
+    }
+
     /// Checks if the given path hosts a valid manifest file.
     pub fn is_valid(path: &PathBuf) -> Result<(), Box<dyn Error>> {
         let man_path = path.join(IP_MANIFEST_FILE);
@@ -223,6 +249,25 @@ impl Ip {
         Self::detect_all_sub(path, IP_MANIFEST_FILE, true, is_working)
     }
 
+    /// Finds all manifest copies (`Orbit-*.toml`) along the provided path `path`.
+    ///
+    /// Manifests that fail to parse are skipped with a warning.
+    pub fn detect_available(path: &PathBuf) -> Result<Vec<Self>, Fault> {
+        let mut result = Vec::new();
+        for entry in manifest::find_file(&path, IP_MANIFEST_PATTERN_FILE, false)? {
+            match Ip::load_available(entry.clone()) {
+                Ok(ip) => result.push(ip),
+                Err(e) => println!(
+                    "{}: skipping manifest \"{}\": {}",
+                    "warning".yellow().bold(),
+                    filesystem::into_std_str(entry),
+                    e
+                ),
+            }
+        }
+        Ok(result)
+    }
+
     /// Checks the metadata file for a entry for `dynamic`.
     pub fn is_dynamic(&self) -> bool {
         self.get_mapping().is_physical() == true
@@ -429,6 +474,66 @@ impl Ip {
         }
     }
 
+    /// Reads the commit that the ip's source was resolved to from the metadata
+    /// file in the directory `dir`.
+    pub fn read_commit_from_metadata(dir: &PathBuf) -> Option<String> {
+        Self::read_metadata_field(dir, "commit")
+    }
+
+    /// Records the `commit` that the ip's source was resolved to in the metadata
+    /// file in the directory `dir`, keeping any other existing metadata.
+    pub fn write_commit_to_metadata(dir: &PathBuf, commit: &str) -> Result<(), Fault> {
+        Self::write_metadata_field(dir, "commit", commit)
+    }
+
+    /// Reads the digest of the archive the ip was downloaded as from the metadata
+    /// file in the directory `dir`.
+    pub fn read_digest_from_metadata(dir: &PathBuf) -> Option<Sha256Hash> {
+        Sha256Hash::from_str(&Self::read_metadata_field(dir, "digest")?).ok()
+    }
+
+    /// Records the `digest` of the archive the ip was downloaded as in the metadata
+    /// file in the directory `dir`, keeping any other existing metadata.
+    pub fn write_digest_to_metadata(dir: &PathBuf, digest: &Sha256Hash) -> Result<(), Fault> {
+        Self::write_metadata_field(dir, "digest", &digest.to_string())
+    }
+
+    /// Reads the time the ip in the directory `dir` was last used in a plan from
+    /// the metadata file.
+    pub fn read_last_used_from_metadata(dir: &PathBuf) -> Option<SystemTime> {
+        let secs = Self::read_metadata_field(dir, "last-used")?
+            .parse::<u64>()
+            .ok()?;
+        Some(UNIX_EPOCH + Duration::from_secs(secs))
+    }
+
+    /// Records the `time` the ip in the directory `dir` was last used in a plan
+    /// in the metadata file, keeping any other existing metadata.
+    pub fn write_last_used_to_metadata(dir: &PathBuf, time: SystemTime) -> Result<(), Fault> {
+        let secs = time.duration_since(UNIX_EPOCH)?.as_secs();
+        Self::write_metadata_field(dir, "last-used", &secs.to_string())
+    }
+
+    fn read_metadata_field(dir: &PathBuf, key: &str) -> Option<String> {
+        let contents = fs::read_to_string(dir.join(ORBIT_METADATA_FILE)).ok()?;
+        let toml = contents.parse::<Document>().ok()?;
+        Some(toml.get("ip")?.get(key)?.as_str()?.to_string())
+    }
+
+    fn write_metadata_field(dir: &PathBuf, key: &str, value: &str) -> Result<(), Fault> {
+        let meta_file: PathBuf = dir.join(ORBIT_METADATA_FILE);
+        let mut toml = match fs::read_to_string(&meta_file) {
+            Ok(contents) => contents.parse::<Document>()?,
+            Err(_) => Document::new(),
+        };
+        if toml.get("ip").map_or(false, |t| t.is_table()) == false {
+            toml["ip"] = toml_edit::table();
+        }
+        toml["ip"][key] = toml_edit::value(value);
+        fs::write(&meta_file, toml.to_string())?;
+        Ok(())
+    }
+
     /// Compile a list of referenced paths to make sure are copied into a directory
     /// when moving an IP around the filesystem.
     pub fn get_files_to_keep(&self) -> HashSet<PathBuf> {
@@ -542,9 +647,9 @@ impl Serialize for IpSpec {
     }
 }
 
-use crate::core::version::AnyVersion;
+use crate::core::version::{AnyVersion, PartialVersion};
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct PartialIpSpec(PkgPart, AnyVersion);
 
 impl PartialIpSpec {
@@ -602,10 +707,89 @@ impl std::fmt::Display for PartialIpSpec {
     }
 }
 
+impl From<(PkgPart, PartialVersion)> for PartialIpSpec {
+    fn from(value: (PkgPart, PartialVersion)) -> Self {
+        Self(value.0, AnyVersion::Specific(value.1))
+    }
+}
+
+impl<'de> Deserialize<'de> for PartialIpSpec {
+    fn deserialize<D>(deserializer: D) -> Result<PartialIpSpec, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct LayerVisitor;
+
+        impl<'de> de::Visitor<'de> for LayerVisitor {
+            type Value = PartialIpSpec;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an identifier and a partial version")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match PartialIpSpec::from_str(v) {
+                    Ok(v) => Ok(v),
+                    Err(e) => Err(de::Error::custom(e)),
+                }
+            }
+        }
+
+        deserializer.deserialize_map(LayerVisitor)
+    }
+}
+
+impl Serialize for PartialIpSpec {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
 
+    #[test]
+    fn commit_metadata() {
+        let dir = tempfile::tempdir().unwrap().into_path();
+        assert_eq!(Ip::read_commit_from_metadata(&dir), None);
+
+        fs::write(dir.join(ORBIT_METADATA_FILE), "[ip]\nunits = []\n").unwrap();
+        Ip::write_commit_to_metadata(&dir, "a1b2c3d").unwrap();
+        assert_eq!(
+            Ip::read_commit_from_metadata(&dir),
+            Some(String::from("a1b2c3d"))
+        );
+        // existing metadata is kept
+        assert_eq!(
+            fs::read_to_string(dir.join(ORBIT_METADATA_FILE)).unwrap(),
+            "[ip]\nunits = []\ncommit = \"a1b2c3d\"\n"
+        );
+
+        Ip::write_digest_to_metadata(&dir, &Sha256Hash::new()).unwrap();
+        assert_eq!(Ip::read_digest_from_metadata(&dir), Some(Sha256Hash::new()));
+        assert_eq!(
+            Ip::read_commit_from_metadata(&dir),
+            Some(String::from("a1b2c3d"))
+        );
+    }
+
+    #[test]
+    fn last_used_metadata() {
+        let dir = tempfile::tempdir().unwrap().into_path();
+        assert_eq!(Ip::read_last_used_from_metadata(&dir), None);
+
+        let time = UNIX_EPOCH + Duration::from_secs(1700000000);
+        Ip::write_last_used_to_metadata(&dir, time).unwrap();
+        assert_eq!(Ip::read_last_used_from_metadata(&dir), Some(time));
+    }
+
     #[test]
     fn compute_checksum() {
         let sum = Ip::compute_checksum(&PathBuf::from("./tests/env/project1/"));
@@ -1,12 +1,18 @@
 use super::ip::Ip;
+use super::lang::{Lang, LangMode, LangUnit};
 use super::lockfile::LockFile;
 use super::manifest::Manifest;
+use super::signature::{self, ArchiveSignature, Signer, Trust, Verdict};
 use crate::util::anyerror::{AnyError, Fault};
 use crate::util::compress;
+use colored::Colorize;
 use flate2::read::ZlibDecoder;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
+use std::error::Error;
+use std::fmt::Display;
 use std::fs::{self, File};
+use std::io::Cursor;
 use std::io::Read;
 use std::io::Write;
 use std::path::Path;
@@ -14,134 +20,534 @@ use std::path::PathBuf;
 use std::str::FromStr;
 use zip::ZipArchive;
 
-const ARCHIVE_MARKER: [u8; 4] = [0xc7, 0x9e, 0xf1, 0x6b];
+const ARCHIVE_MARKER: [u8; 4] = [0xc7, 0x9e, 0xf1, 0x6c];
+
+/// Marks archives written before the header stored a format version.
+const LEGACY_MARKER: [u8; 4] = [0xc7, 0x9e, 0xf1, 0x6b];
+
+/// The format version written into the header.
+///
+/// The version only changes when the layout changes in a way older orbit
+/// releases cannot skip over; new data is added as an optional section instead.
+pub const ARCHIVE_VERSION: u16 = 1;
 
 pub const ARCHIVE_EXT: &str = "ip";
 
+/// Number of bytes to read a [u16] value.
+const U16_SIZE: usize = 2;
+
 /// Number of bytes to read a [u32] value.
 const U32_SIZE: usize = 4;
 
+/// Section tags with this bit set must be understood to read the archive.
+const REQUIRED_SECTION: u16 = 0x8000;
+
+mod section {
+    use super::REQUIRED_SECTION;
+
+    pub const MANIFEST: u16 = REQUIRED_SECTION | 0x0001;
+    pub const LOCKFILE: u16 = REQUIRED_SECTION | 0x0002;
+    pub const STATS: u16 = 0x0003;
+    pub const UNITS: u16 = 0x0004;
+    pub const README: u16 = 0x0005;
+    pub const SIGNATURE: u16 = 0x0006;
+}
+
 pub type IpBytesZipped = Vec<u8>;
 
 /// The IP archive stores the compressed version of a project along with any
-/// metadata in its 'header'. The format is: \[MARKER HEADER_LEN HEADER_BYTES PROJECT_DIR_BYTES].
+/// metadata in its 'header'. The format is: \[MARKER VERSION HEADER_LEN HEADER_BYTES PROJECT_DIR_BYTES].
+///
+/// The decompressed header is a series of sections, each stored as
+/// \[TAG LEN BYTES]. Readers skip optional sections they do not recognize.
 #[derive(Debug, PartialEq)]
 pub struct IpArchive {
     manifest: Manifest,
     lock: LockFile,
     /// Compressed data containing the [Ip].
     archive: IpBytesZipped,
+    /// The format version the archive was written with (0 for legacy archives).
+    version: u16,
+    stats: Option<ArchiveStats>,
+    units: Option<Vec<ArchiveUnit>>,
+    readme: Option<String>,
+    signature: Option<ArchiveSignature>,
+    /// The header bytes covered by the signature.
+    signed_header: Vec<u8>,
+}
+
+/// Totals about the files stored in the archive.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArchiveStats {
+    files: u64,
+    size: u64,
+}
+
+impl ArchiveStats {
+    /// Counts the files and their uncompressed size within the zipped `bytes`.
+    fn from_zip(bytes: &[u8]) -> Result<Self, Fault> {
+        let mut zip = ZipArchive::new(Cursor::new(bytes))?;
+        let mut stats = Self { files: 0, size: 0 };
+        for i in 0..zip.len() {
+            let entry = zip.by_index(i)?;
+            if entry.is_file() == true {
+                stats.files += 1;
+                stats.size += entry.size();
+            }
+        }
+        Ok(stats)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        [self.files.to_be_bytes(), self.size.to_be_bytes()].concat()
+    }
+
+    /// Reads the totals from the beginning of the section, ignoring any
+    /// trailing data added by newer versions.
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(Self {
+            files: u64::from_be_bytes(bytes.get(0..8)?.try_into().ok()?),
+            size: u64::from_be_bytes(bytes.get(8..16)?.try_into().ok()?),
+        })
+    }
+
+    /// The number of files in the archive.
+    pub fn get_files(&self) -> u64 {
+        self.files
+    }
+
+    /// The total uncompressed size of the files in bytes.
+    pub fn get_size(&self) -> u64 {
+        self.size
+    }
+}
+
+/// A primary design unit found within the archived project.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArchiveUnit {
+    language: String,
+    kind: String,
+    name: String,
+}
+
+impl ArchiveUnit {
+    pub fn get_language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn get_kind(&self) -> &str {
+        &self.kind
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl From<&LangUnit> for ArchiveUnit {
+    fn from(value: &LangUnit) -> Self {
+        Self {
+            language: value.get_lang().to_string(),
+            kind: match value.get_lang() {
+                Lang::Vhdl => value.to_string(),
+                Lang::Verilog => String::from("module"),
+            },
+            name: value.get_name().to_string(),
+        }
+    }
+}
+
+impl FromStr for ArchiveUnit {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut fields = s.splitn(3, '\t');
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(l), Some(k), Some(n)) => Ok(Self {
+                language: l.to_string(),
+                kind: k.to_string(),
+                name: n.to_string(),
+            }),
+            _ => Err(AnyError(format!("invalid unit entry \"{}\"", s))),
+        }
+    }
+}
+
+impl Display for ArchiveUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}\t{}\t{}", self.language, self.kind, self.name)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ArchiveError {
+    Corrupted,
+    Truncated,
+    UnsupportedVersion(u16),
+    UnknownSection(u16),
+    MissingSection(&'static str),
+    RepairFailed(String),
+}
+
+impl Error for ArchiveError {}
+
+impl Display for ArchiveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Corrupted => write!(f, "the file is not an orbit ip archive or is corrupted"),
+            Self::Truncated => write!(f, "the archive ended unexpectedly"),
+            Self::UnsupportedVersion(v) => write!(
+                f,
+                "the archive uses format version {} but this version of orbit supports up to {}; try upgrading orbit",
+                v, ARCHIVE_VERSION
+            ),
+            Self::UnknownSection(t) => write!(
+                f,
+                "the archive requires an unknown header section {:#06x}; try upgrading orbit",
+                t
+            ),
+            Self::MissingSection(s) => write!(f, "the archive header is missing its {}", s),
+            Self::RepairFailed(e) => write!(f, "failed to repair the archive's header: {}", e),
+        }
+    }
+}
+
+/// Reads values in order from a series of bytes, erroring instead of reading
+/// past the end.
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.offset >= self.bytes.len()
+    }
+
+    fn take(&mut self, size: usize) -> Result<&'a [u8], ArchiveError> {
+        let end = self
+            .offset
+            .checked_add(size)
+            .ok_or(ArchiveError::Truncated)?;
+        let slice = self
+            .bytes
+            .get(self.offset..end)
+            .ok_or(ArchiveError::Truncated)?;
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, ArchiveError> {
+        Ok(u16::from_be_bytes(self.take(U16_SIZE)?.try_into().unwrap()))
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ArchiveError> {
+        Ok(u32::from_be_bytes(self.take(U32_SIZE)?.try_into().unwrap()))
+    }
+
+    /// Reads a length-prefixed series of bytes.
+    fn read_sized(&mut self) -> Result<&'a [u8], ArchiveError> {
+        let len = self.read_u32()? as usize;
+        self.take(len)
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.bytes[self.offset.min(self.bytes.len())..]
+    }
+}
+
+/// The contents of the header's sections before they are interpreted.
+struct RawHeader<'a> {
+    manifest: Option<&'a [u8]>,
+    lock: Option<&'a [u8]>,
+    stats: Option<&'a [u8]>,
+    units: Option<&'a [u8]>,
+    readme: Option<&'a [u8]>,
+    signature: Option<&'a [u8]>,
+    /// Every section except the signature, as written in the header.
+    signed: Vec<u8>,
+}
+
+impl<'a> RawHeader<'a> {
+    /// Splits the decompressed header `bytes` into its sections.
+    fn split(bytes: &'a [u8]) -> Result<Self, ArchiveError> {
+        let mut header = Self {
+            manifest: None,
+            lock: None,
+            stats: None,
+            units: None,
+            readme: None,
+            signature: None,
+            signed: Vec::new(),
+        };
+        let mut reader = ByteReader::new(bytes);
+        while reader.is_empty() == false {
+            let start = reader.offset;
+            let tag = reader.read_u16()?;
+            let data = reader.read_sized()?;
+            match tag {
+                section::MANIFEST => header.manifest = Some(data),
+                section::LOCKFILE => header.lock = Some(data),
+                section::STATS => header.stats = Some(data),
+                section::UNITS => header.units = Some(data),
+                section::README => header.readme = Some(data),
+                section::SIGNATURE => {
+                    header.signature = Some(data);
+                    continue;
+                }
+                _ => {
+                    // sections from newer versions can be skipped unless they are needed
+                    if tag & REQUIRED_SECTION != 0 {
+                        return Err(ArchiveError::UnknownSection(tag));
+                    }
+                }
+            }
+            header
+                .signed
+                .extend_from_slice(&bytes[start..reader.offset]);
+        }
+        Ok(header)
+    }
 }
 
 impl IpArchive {
-    fn slice(buf: &[u8], offset: usize, size: usize) -> &[u8] {
-        &buf[offset..offset + size]
+    /// Reads the archive at `path` after checking its signature against the
+    /// `trust` policy.
+    pub fn read(path: &PathBuf, trust: &Trust) -> Result<Self, Fault> {
+        let contents = fs::read(&path)?;
+        // verify who produced the archive before reading anything else from it
+        let (signed, signature) = match Self::signed_parts(&contents) {
+            Ok(p) => p,
+            Err(e) => Err(AnyError(format!(
+                "failed to read archive {}: {}",
+                path.display(),
+                e
+            )))?,
+        };
+        trust.check(&path, &signed, signature)?;
+        match Self::parse(contents, true, &path) {
+            Ok(a) => Ok(a),
+            Err(e) => Err(AnyError(format!(
+                "failed to read archive {}: {}",
+                path.display(),
+                e
+            )))?,
+        }
     }
 
-    pub fn read(path: &PathBuf) -> Result<Self, Fault> {
+    /// Reads the archive at `path` as-is, without checking its signature or
+    /// repairing its header.
+    pub fn open(path: &PathBuf) -> Result<Self, Fault> {
         let contents = fs::read(&path)?;
-        Self::parse(contents, false, &path)
+        match Self::parse(contents, false, &path) {
+            Ok(a) => Ok(a),
+            Err(e) => Err(AnyError(format!(
+                "failed to read archive {}: {}",
+                path.display(),
+                e
+            )))?,
+        }
     }
 
-    /// Converts the series of bytes into the [String] to be read as the struct.
-    ///
-    /// Returns [None] if there is any point of failure.
-    fn parse_struct<T: FromStr>(bytes: &[u8], offset: usize) -> Option<(T, usize)> {
-        // attempt to read the size
-        let len: usize = {
-            let size_bytes: [u8; 4] = match Self::slice(&bytes, offset, U32_SIZE).try_into() {
-                Ok(arr) => arr,
-                Err(_) => return None,
-            };
-            u32::from_be_bytes(size_bytes) as usize
-        };
-        // attempt to parse from string
-        match String::from_utf8(Self::slice(&bytes, offset + U32_SIZE, len).to_vec()) {
-            Ok(s) => match T::from_str(&s) {
-                Ok(t) => Some((t, len + U32_SIZE)),
-                Err(_) => None,
-            },
-            Err(_) => None,
+    /// Determines if the archive read from `path` is signed by a key within
+    /// `trust`.
+    pub fn judge(&self, path: &Path, trust: &Trust) -> Verdict {
+        let detached = fs::read_to_string(signature::detached_path(path)).ok();
+        trust.judge(
+            &self.signed_contents(),
+            self.signature.clone(),
+            detached.as_deref(),
+        )
+    }
+
+    /// Lists the path and uncompressed size of every file in the zipped project.
+    pub fn list_files(&self) -> Result<Vec<(String, u64)>, Fault> {
+        let mut zip = ZipArchive::new(Cursor::new(&self.archive))?;
+        let mut files = Vec::new();
+        for i in 0..zip.len() {
+            let entry = zip.by_index(i)?;
+            if entry.is_file() == true {
+                files.push((entry.name().to_string(), entry.size()));
+            }
         }
+        files.sort();
+        Ok(files)
     }
 
-    /// Parses according to version of [IpArchive] format.
-    ///
-    /// The `repairing` argument should be asserted only when a repair process
-    /// is occurring.
-    fn parse(buf: Vec<u8>, repairing: bool, path: &PathBuf) -> Result<Self, Fault> {
+    /// Returns the contents of the archive that a signature covers.
+    fn signed_contents(&self) -> Vec<u8> {
+        [self.signed_header.as_slice(), self.archive.as_slice()].concat()
+    }
+
+    /// Returns the bytes covered by the archive's signature along with its
+    /// embedded signature, without interpreting any other part of the header.
+    fn signed_parts(buf: &[u8]) -> Result<(Vec<u8>, Option<ArchiveSignature>), Fault> {
+        let (version, header_bytes, header_end) = Self::split_file(buf)?;
+        let header = Self::split_header(version, &header_bytes, &buf[..header_end])?;
+        let signed = [header.signed.as_slice(), &buf[header_end..]].concat();
+        Ok((signed, Self::parse_signature(header.signature)?))
+    }
+
+    /// Divides the file `buf` into its format version, its decompressed header
+    /// bytes, and the offset where the zipped project begins.
+    fn split_file(buf: &[u8]) -> Result<(u16, Vec<u8>, usize), Fault> {
+        let mut reader = ByteReader::new(&buf);
         // read the marker back to verify the file is for orbit
-        let marker: [u8; 4] = Self::slice(&buf, 0, ARCHIVE_MARKER.len()).try_into()?;
-        if marker != ARCHIVE_MARKER {
-            return Err(AnyError(format!("{}", "The download file is corrupted")))?;
+        let marker = reader.take(ARCHIVE_MARKER.len())?;
+        let version = if marker == ARCHIVE_MARKER {
+            reader.read_u16()?
+        } else if marker == LEGACY_MARKER {
+            0
+        } else {
+            return Err(ArchiveError::Corrupted)?;
+        };
+        if version > ARCHIVE_VERSION {
+            return Err(ArchiveError::UnsupportedVersion(version))?;
         }
 
-        // read length of header
-        let header_offset = ARCHIVE_MARKER.len();
-        let header_len: usize =
-            u32::from_be_bytes(Self::slice(&buf, header_offset, U32_SIZE).try_into()?) as usize;
-
-        // slice to the bytes for the relevant zipped archive
-        let archive_offset: usize = header_offset + U32_SIZE + header_len;
-        let archive = &buf[archive_offset..];
+        // slice to the header bytes and then the relevant zipped archive
+        let compressed_header = reader.read_sized()?;
 
         // decompress the header bytes
-        let mut d = ZlibDecoder::new(Self::slice(&buf, header_offset + U32_SIZE, header_len));
+        let mut d = ZlibDecoder::new(compressed_header);
         let mut header_bytes = Vec::new();
-        d.read_to_end(&mut header_bytes)?;
+        if d.read_to_end(&mut header_bytes).is_err() == true {
+            return Err(ArchiveError::Corrupted)?;
+        }
+        Ok((version, header_bytes, reader.offset))
+    }
 
-        // parse the decompressed header bytes
+    /// Splits the decompressed header bytes according to the format `version`.
+    fn split_header<'a>(
+        version: u16,
+        header_bytes: &'a [u8],
+        prefix: &[u8],
+    ) -> Result<RawHeader<'a>, ArchiveError> {
+        match version {
+            0 => Self::split_legacy(header_bytes, prefix),
+            _ => RawHeader::split(header_bytes),
+        }
+    }
 
-        // handle manifest
-        let mut offset: usize = 0;
-        let (man, bytes_read): (Manifest, usize) = match Self::parse_struct(&header_bytes, offset) {
-            Some(t) => t,
-            None => match repairing {
-                true => panic!("Repairing function failed"),
-                false => {
-                    println!("info: {}", "Failed to parse downloaded file's header bytes; running repair function ...");
-                    let repaired_bytes = Self::repair(archive, &path)?;
-                    match Self::parse(repaired_bytes, true, &path) {
-                        Ok(rp) => {
-                            println!("info: {}", "Repair successful");
-                            return Ok(rp);
-                        }
-                        Err(e) => return Err(e)?,
-                    }
-                }
+    /// Decodes the embedded signature section, if the header has one.
+    fn parse_signature(bytes: Option<&[u8]>) -> Result<Option<ArchiveSignature>, Fault> {
+        match bytes {
+            Some(b) => match ArchiveSignature::from_bytes(b) {
+                Ok(s) => Ok(Some(s)),
+                Err(e) => Err(AnyError(format!("invalid embedded signature: {}", e)))?,
             },
-        };
-        // handle lockfile
-        offset += bytes_read;
-        let (lock, _bytes_read): (LockFile, usize) = match Self::parse_struct(&header_bytes, offset)
-        {
-            Some(t) => t,
-            None => match repairing {
-                true => panic!("Repairing function failed"),
-                false => {
+            None => Ok(None),
+        }
+    }
+
+    /// Parses according to version of [IpArchive] format.
+    ///
+    /// The `repair` argument allows the header to be rewritten when its manifest
+    /// or lockfile cannot be parsed, so it must only be set after the archive's
+    /// signature has been checked.
+    fn parse(buf: Vec<u8>, repair: bool, path: &PathBuf) -> Result<Self, Fault> {
+        let (version, header_bytes, header_end) = Self::split_file(&buf)?;
+        let archive = &buf[header_end..];
+
+        // parse the decompressed header bytes
+        let header = Self::split_header(version, &header_bytes, &buf[..header_end])?;
+
+        let parsed = Self::parse_struct::<Manifest>(header.manifest, "manifest").and_then(|man| {
+            Ok((
+                man,
+                Self::parse_struct::<LockFile>(header.lock, "lockfile")?,
+            ))
+        });
+        let (man, lock) = match parsed {
+            Ok(t) => t,
+            Err(e) => match repair {
+                false => return Err(e),
+                true => {
                     println!("info: {}", "Failed to parse downloaded file's header bytes; running repair function ...");
-                    let repaired_bytes = Self::repair(archive, &path)?;
-                    match Self::parse(repaired_bytes, true, &path) {
-                        Ok(rp) => {
-                            println!("info: {}", "Repair successful");
-                            return Ok(rp);
+                    // rewriting a signed archive would discard its signature, so
+                    // its repaired header is only kept in memory
+                    let signed = header.signature.is_some()
+                        || signature::detached_path(&path).exists() == true;
+                    let repaired_bytes = match signed {
+                        true => {
+                            let scratch = tempfile::NamedTempFile::new()?;
+                            Self::repair(archive, &scratch.path().to_path_buf())?
                         }
-                        Err(e) => return Err(e)?,
-                    }
+                        false => Self::repair(archive, &path)?,
+                    };
+                    let rp = match Self::parse(repaired_bytes, false, &path) {
+                        Ok(rp) => rp,
+                        Err(e) => return Err(ArchiveError::RepairFailed(e.to_string()))?,
+                    };
+                    println!("info: {}", "Repair successful");
+                    return Ok(rp);
                 }
             },
         };
-        // @todo: handle stats?
 
-        // offset += bytes_read;
+        // optional sections that fail to parse are treated as missing
+        let stats = header.stats.and_then(|b| ArchiveStats::from_bytes(b));
+        let units = header
+            .units
+            .and_then(|b| std::str::from_utf8(b).ok())
+            .map(|s| {
+                s.lines()
+                    .filter_map(|l| ArchiveUnit::from_str(l).ok())
+                    .collect()
+            });
+        let readme = header
+            .readme
+            .and_then(|b| String::from_utf8(b.to_vec()).ok());
+        let signature = Self::parse_signature(header.signature)?;
 
         Ok(Self {
             manifest: man,
             lock: lock,
             archive: archive.to_vec(),
+            version: version,
+            stats: stats,
+            units: units,
+            readme: readme,
+            signature: signature,
+            signed_header: header.signed,
+        })
+    }
+
+    /// Reads the header written before the format was versioned, which stores
+    /// the manifest and lockfile back-to-back.
+    ///
+    /// A legacy archive cannot embed a signature, so a detached signature covers
+    /// the `prefix` of the file preceding the zipped project.
+    fn split_legacy<'a>(bytes: &'a [u8], prefix: &[u8]) -> Result<RawHeader<'a>, ArchiveError> {
+        let mut reader = ByteReader::new(bytes);
+        Ok(RawHeader {
+            manifest: reader.read_sized().ok(),
+            lock: reader.read_sized().ok(),
+            stats: None,
+            units: None,
+            readme: None,
+            signature: None,
+            signed: prefix.to_vec(),
         })
     }
 
+    /// Converts the series of bytes into the [String] to be read as the struct.
+    fn parse_struct<T: FromStr>(bytes: Option<&[u8]>, name: &'static str) -> Result<T, Fault>
+    where
+        T::Err: Display,
+    {
+        let bytes = bytes.ok_or(ArchiveError::MissingSection(name))?;
+        let text = String::from_utf8(bytes.to_vec())?;
+        match T::from_str(&text) {
+            Ok(t) => Ok(t),
+            Err(e) => Err(AnyError(format!("invalid {}: {}", name, e)))?,
+        }
+    }
+
     /// Fixes any issues with header bytes.
     ///
     /// This function will skip the header bytes, unzip the archive into a
@@ -166,8 +572,9 @@ impl IpArchive {
             }
         };
         // re-perform a write
-        Self::write(&extracted_ip, &path)?;
+        Self::write(&extracted_ip, &path, None)?;
         let repaired_bytes = fs::read(&path)?;
+        fs::remove_dir_all(dir)?;
         Ok(repaired_bytes)
     }
 
@@ -178,6 +585,42 @@ impl IpArchive {
         (self.manifest, self.lock, self.archive)
     }
 
+    /// Access the format version the archive was written with.
+    ///
+    /// Archives written before the format was versioned report 0.
+    pub fn get_version(&self) -> u16 {
+        self.version
+    }
+
+    pub fn get_man(&self) -> &Manifest {
+        &self.manifest
+    }
+
+    pub fn get_lock(&self) -> &LockFile {
+        &self.lock
+    }
+
+    pub fn get_stats(&self) -> Option<&ArchiveStats> {
+        self.stats.as_ref()
+    }
+
+    pub fn get_units(&self) -> Option<&Vec<ArchiveUnit>> {
+        self.units.as_ref()
+    }
+
+    pub fn get_readme(&self) -> Option<&String> {
+        self.readme.as_ref()
+    }
+
+    pub fn get_signature(&self) -> Option<&ArchiveSignature> {
+        self.signature.as_ref()
+    }
+
+    /// Access the zipped project.
+    pub fn get_archive(&self) -> &IpBytesZipped {
+        &self.archive
+    }
+
     /// Unzips the archive and places it at `dest`. The `dest` path will be
     /// the root folder of the decompressed archive.
     ///
@@ -191,29 +634,100 @@ impl IpArchive {
         Ok(())
     }
 
+    /// Lists the primary design units of the `ip`, one per line.
+    ///
+    /// Returns `None` if the source code could not be read.
+    fn summarize_units(ip: &Ip) -> Option<String> {
+        let units = Ip::collect_units(
+            false,
+            ip.get_root(),
+            &LangMode::default(),
+            false,
+            ip.into_public_list(),
+        )
+        .ok()?;
+        let mut lines: Vec<String> = units
+            .values()
+            .map(|u| ArchiveUnit::from(u).to_string())
+            .collect();
+        lines.sort();
+        Some(lines.join("\n"))
+    }
+
+    /// Reads the readme file found at the root of the project, if one exists.
+    fn find_readme(root: &PathBuf) -> Option<String> {
+        let mut candidates: Vec<PathBuf> = fs::read_dir(&root)
+            .ok()?
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.is_file() == true
+                    && p.file_stem()
+                        .and_then(|s| s.to_str())
+                        .map_or(false, |s| s.eq_ignore_ascii_case("readme"))
+            })
+            .collect();
+        candidates.sort();
+        fs::read_to_string(candidates.first()?).ok()
+    }
+
     /// Stores the project's state and additional metadata into a .zip archive.
-    pub fn write(ip: &Ip, dest: &PathBuf) -> Result<(), Fault> {
+    ///
+    /// The archive is signed when a `signer` is provided.
+    pub fn write(ip: &Ip, dest: &PathBuf, signer: Option<&Signer>) -> Result<(), Fault> {
         // compress the ip package
         compress::write_zip_dir(ip.get_root(), &dest)?;
         // read back the bytes
         let archive_bytes = fs::read(&dest)?;
 
+        // collect the sections to embed in the header
+        let mut sections = vec![
+            // get the manifest bytes
+            (section::MANIFEST, ip.get_man().to_string().into_bytes()),
+            // get the lockfile bytes
+            (section::LOCKFILE, ip.get_lock().to_string().into_bytes()),
+            (
+                section::STATS,
+                ArchiveStats::from_zip(&archive_bytes)?.to_bytes(),
+            ),
+        ];
+        if let Some(units) = Self::summarize_units(ip) {
+            sections.push((section::UNITS, units.into_bytes()));
+        }
+        if let Some(readme) = Self::find_readme(ip.get_root()) {
+            sections.push((section::README, readme.into_bytes()));
+        }
+
+        let mut header_bytes = Vec::new();
+        for (tag, data) in &sections {
+            // write the kind of section
+            header_bytes.extend_from_slice(&tag.to_be_bytes());
+            // write the size of the section
+            header_bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            // write the section contents
+            header_bytes.extend_from_slice(&data);
+        }
+
+        // a stale detached signature would no longer match the new contents
+        signature::remove_detached(&dest)?;
+        if let Some(s) = signer {
+            let sig = s.sign(&[header_bytes.as_slice(), archive_bytes.as_slice()].concat());
+            match s.is_detached() {
+                true => fs::write(signature::detached_path(&dest), sig.to_detached())?,
+                false => {
+                    let data = sig.to_bytes();
+                    header_bytes.extend_from_slice(&section::SIGNATURE.to_be_bytes());
+                    header_bytes.extend_from_slice(&(data.len() as u32).to_be_bytes());
+                    header_bytes.extend_from_slice(&data);
+                }
+            }
+        }
+
         // compress the header bytes
         let header_bytes = {
             // create a Zlib encoder for compression scheme
             let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
-            let embedded_data = vec![
-                // get the manifest bytes
-                ip.get_man().to_string(),
-                // get the lockfile bytes
-                ip.get_lock().to_string(),
-            ];
-            for data in embedded_data {
-                // write the size of the string
-                e.write_all(&(data.len() as u32).to_be_bytes())?;
-                // write the string contents
-                e.write_all(&data.as_bytes())?;
-            }
+            e.write_all(&header_bytes)?;
             // complete the compression algorithm
             e.finish()?
         };
@@ -222,41 +736,292 @@ impl IpArchive {
         let mut file = File::options().write(true).truncate(true).open(&dest)?;
 
         // write the marker to start the file
-        file.write(&ARCHIVE_MARKER)?;
+        file.write_all(&ARCHIVE_MARKER)?;
+
+        // write the format version
+        file.write_all(&ARCHIVE_VERSION.to_be_bytes())?;
 
         // write the number of compressed header bytes to the archive
-        file.write(&(header_bytes.len() as u32).to_be_bytes())?;
+        file.write_all(&(header_bytes.len() as u32).to_be_bytes())?;
 
         // write the compressed bytes
-        file.write(&header_bytes)?;
+        file.write_all(&header_bytes)?;
 
         // write the entire compressed file back
-        file.write(&archive_bytes)?;
+        file.write_all(&archive_bytes)?;
 
         Ok(())
     }
 
-    /// Detects all Ip found as archives.
-    pub fn detect_all(dir: &PathBuf) -> Result<Vec<Ip>, Fault> {
+    /// Detects all Ip found as archives, checking each signature against the
+    /// `trust` policy.
+    ///
+    /// Archives that cannot be read or are refused by the policy are skipped.
+    pub fn detect_all(dir: &PathBuf, trust: &Trust) -> Result<Vec<Ip>, Fault> {
         // for each .ip file
-        fs::read_dir(&dir)?
+        Ok(fs::read_dir(&dir)?
             .filter_map(|result| if let Ok(r) = result { Some(r) } else { None })
             .map(|entry| entry.path().to_path_buf())
             .filter(|path| path.extension().is_some() && path.extension().unwrap() == ARCHIVE_EXT)
-            .map(|path| match IpArchive::read(&path) {
-                Ok(arc) => Ok(Ip::from(arc)),
-                Err(e) => Err(e),
+            .filter_map(|path| match IpArchive::read(&path, trust) {
+                Ok(arc) => Some(Ip::from(arc)),
+                // one refused archive should not hide the rest of the downloads
+                Err(e) => {
+                    println!("{}: skipping download: {}", "warning".yellow().bold(), e);
+                    None
+                }
             })
-            .collect()
+            .collect())
     }
 }
 
-// #[cfg(test)]
-// mod test {
-//     use super::*;
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::core::signature::SignaturePolicy;
+    use ed25519_dalek::SigningKey;
+
+    /// Writes an archive of a small ip into a temporary directory.
+    fn write_sample(signer: Option<&Signer>) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("gates");
+        fs::create_dir(&root).unwrap();
+        fs::write(
+            root.join("Orbit.toml"),
+            "[ip]\nname = \"gates\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+        fs::write(
+            root.join("and_gate.vhd"),
+            "entity and_gate is end entity;\narchitecture rtl of and_gate is begin end architecture;\n",
+        )
+        .unwrap();
+        fs::write(root.join("README.md"), "# gates\n").unwrap();
+        // archives hold the lockfile so they can be repaired from the project
+        let ip = Ip::load(root.clone(), true).unwrap();
+        LockFile::from_build_list(&mut vec![&ip], &ip)
+            .save_to_disk(&root)
+            .unwrap();
+        let ip = Ip::load(root, true).unwrap();
+        let dest = dir.path().join("gates.ip");
+        fs::write(&dest, "").unwrap();
+        IpArchive::write(&ip, &dest, signer).unwrap();
+        (dir, dest)
+    }
+
+    fn trust_nothing() -> Trust {
+        Trust::new(SignaturePolicy::Ignore, Vec::new())
+    }
+
+    #[test]
+    fn write_and_read() {
+        let (_dir, dest) = write_sample(None);
+        let arc = IpArchive::read(&dest, &trust_nothing()).unwrap();
+        assert_eq!(arc.get_version(), ARCHIVE_VERSION);
+        assert_eq!(arc.get_man().get_ip().get_name().to_string(), "gates");
+        let stats = arc.get_stats().unwrap();
+        assert_eq!(stats.get_files() >= 3, true);
+        assert_eq!(
+            arc.get_units().unwrap(),
+            &vec![ArchiveUnit {
+                language: String::from("vhdl"),
+                kind: String::from("entity"),
+                name: String::from("and_gate"),
+            }]
+        );
+        assert_eq!(arc.get_readme(), Some(&String::from("# gates\n")));
+        let files = arc.list_files().unwrap();
+        assert_eq!(files.contains(&(String::from("README.md"), 8)), true);
+        assert_eq!(files.len() as u64, stats.get_files());
+        assert_eq!(arc.get_signature(), None);
+    }
+
+    #[test]
+    fn read_signed() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let signer = Signer::new(key.clone(), false);
+        let (_dir, dest) = write_sample(Some(&signer));
 
-//     #[test]
-//     fn write_and_read() {
+        let trust = Trust::new(SignaturePolicy::Require, vec![key.verifying_key()]);
+        let arc = IpArchive::read(&dest, &trust).unwrap();
+        assert_eq!(arc.get_signature().is_some(), true);
+        assert_eq!(arc.judge(&dest, &trust), Verdict::Trusted);
 
-//     }
-// }
+        // any change to the contents invalidates the signature
+        let mut bytes = fs::read(&dest).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&dest, bytes).unwrap();
+        assert_eq!(IpArchive::read(&dest, &trust).is_err(), true);
+        assert_eq!(
+            IpArchive::open(&dest).unwrap().judge(&dest, &trust),
+            Verdict::Invalid
+        );
+
+        // unsigned archives are rejected when signatures are required
+        let (_dir, unsigned) = write_sample(None);
+        assert_eq!(IpArchive::read(&unsigned, &trust).is_err(), true);
+    }
+
+    #[test]
+    fn detect_all_skips_refused() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let signer = Signer::new(key.clone(), false);
+        let (dir, _) = write_sample(Some(&signer));
+        let (_other, unsigned) = write_sample(None);
+        fs::copy(&unsigned, dir.path().join("unsigned.ip")).unwrap();
+
+        let downloads = dir.path().to_path_buf();
+        let trust = Trust::new(SignaturePolicy::Require, vec![key.verifying_key()]);
+        assert_eq!(IpArchive::detect_all(&downloads, &trust).unwrap().len(), 1);
+        assert_eq!(
+            IpArchive::detect_all(&downloads, &trust_nothing())
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn repair_only_trusted() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let signer = Signer::new(key.clone(), false);
+        let trust = Trust::new(SignaturePolicy::Require, vec![key.verifying_key()]);
+        let (_dir, dest) = write_sample(None);
+        let arc = IpArchive::read(&dest, &trust_nothing()).unwrap();
+        let header = [
+            encode_section(section::MANIFEST, b"not a manifest"),
+            encode_section(section::LOCKFILE, arc.get_lock().to_string().as_bytes()),
+        ]
+        .concat();
+
+        // an unsigned archive is refused before its header is repaired
+        rewrite_header(&dest, ARCHIVE_VERSION, &header);
+        let before = fs::read(&dest).unwrap();
+        assert_eq!(IpArchive::read(&dest, &trust).is_err(), true);
+        assert_eq!(fs::read(&dest).unwrap(), before);
+
+        // a trusted archive is repaired without discarding its signature
+        let sig = signer.sign(&[header.as_slice(), arc.get_archive()].concat());
+        let header = [header, encode_section(section::SIGNATURE, &sig.to_bytes())].concat();
+        rewrite_header(&dest, ARCHIVE_VERSION, &header);
+        let before = fs::read(&dest).unwrap();
+        let repaired = IpArchive::read(&dest, &trust).unwrap();
+        assert_eq!(repaired.get_man().get_ip().get_name().to_string(), "gates");
+        assert_eq!(fs::read(&dest).unwrap(), before);
+    }
+
+    /// Rebuilds the archive at `path` with the decompressed header `header`.
+    fn rewrite_header(path: &PathBuf, version: u16, header: &[u8]) {
+        let buf = fs::read(path).unwrap();
+        let (_, _, header_end) = IpArchive::split_file(&buf).unwrap();
+        let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+        e.write_all(header).unwrap();
+        let compressed = e.finish().unwrap();
+        let mut bytes = ARCHIVE_MARKER.to_vec();
+        bytes.extend(version.to_be_bytes());
+        bytes.extend((compressed.len() as u32).to_be_bytes());
+        bytes.extend(compressed);
+        bytes.extend(&buf[header_end..]);
+        fs::write(path, bytes).unwrap();
+    }
+
+    fn encode_section(tag: u16, data: &[u8]) -> Vec<u8> {
+        [
+            tag.to_be_bytes().as_slice(),
+            (data.len() as u32).to_be_bytes().as_slice(),
+            data,
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn skip_unknown_sections() {
+        let (_dir, dest) = write_sample(None);
+        let arc = IpArchive::read(&dest, &trust_nothing()).unwrap();
+        let header = [
+            encode_section(section::MANIFEST, arc.get_man().to_string().as_bytes()),
+            encode_section(0x0042, b"from the future"),
+            encode_section(section::LOCKFILE, arc.get_lock().to_string().as_bytes()),
+        ]
+        .concat();
+        rewrite_header(&dest, ARCHIVE_VERSION, &header);
+        let arc = IpArchive::read(&dest, &trust_nothing()).unwrap();
+        assert_eq!(arc.get_stats(), None);
+        assert_eq!(arc.get_readme(), None);
+
+        // required sections cannot be skipped
+        let header = [header, encode_section(REQUIRED_SECTION | 0x0042, b"")].concat();
+        rewrite_header(&dest, ARCHIVE_VERSION, &header);
+        assert_eq!(IpArchive::read(&dest, &trust_nothing()).is_err(), true);
+    }
+
+    #[test]
+    fn reject_newer_version() {
+        let (_dir, dest) = write_sample(None);
+        let mut bytes = fs::read(&dest).unwrap();
+        bytes[ARCHIVE_MARKER.len()..ARCHIVE_MARKER.len() + U16_SIZE]
+            .copy_from_slice(&(ARCHIVE_VERSION + 1).to_be_bytes());
+        let err = IpArchive::parse(bytes, false, &dest).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            ArchiveError::UnsupportedVersion(ARCHIVE_VERSION + 1).to_string()
+        );
+    }
+
+    #[test]
+    fn read_legacy() {
+        let (_dir, dest) = write_sample(None);
+        let arc = IpArchive::read(&dest, &trust_nothing()).unwrap();
+        // write the header as it was before the format was versioned
+        let mut e = ZlibEncoder::new(Vec::new(), Compression::default());
+        for data in [arc.get_man().to_string(), arc.get_lock().to_string()] {
+            e.write_all(&(data.len() as u32).to_be_bytes()).unwrap();
+            e.write_all(data.as_bytes()).unwrap();
+        }
+        let compressed = e.finish().unwrap();
+        let mut bytes = LEGACY_MARKER.to_vec();
+        bytes.extend((compressed.len() as u32).to_be_bytes());
+        bytes.extend(compressed);
+        bytes.extend(arc.get_archive());
+
+        let legacy = IpArchive::parse(bytes, false, &dest).unwrap();
+        assert_eq!(legacy.get_version(), 0);
+        assert_eq!(legacy.get_man(), arc.get_man());
+        assert_eq!(legacy.get_stats(), None);
+    }
+
+    #[test]
+    fn corrupted_errors() {
+        let path = PathBuf::from("gates.ip");
+        // bad marker
+        assert_eq!(
+            IpArchive::parse(vec![0, 1, 2, 3, 4, 5], false, &path)
+                .unwrap_err()
+                .to_string(),
+            ArchiveError::Corrupted.to_string()
+        );
+        // too short to hold the header length
+        let mut bytes = ARCHIVE_MARKER.to_vec();
+        bytes.extend(ARCHIVE_VERSION.to_be_bytes());
+        assert_eq!(
+            IpArchive::parse(bytes.clone(), false, &path)
+                .unwrap_err()
+                .to_string(),
+            ArchiveError::Truncated.to_string()
+        );
+        // header length beyond the end of the file
+        bytes.extend(1000u32.to_be_bytes());
+        assert_eq!(
+            IpArchive::parse(bytes, false, &path)
+                .unwrap_err()
+                .to_string(),
+            ArchiveError::Truncated.to_string()
+        );
+        // a truncated section within the header
+        assert_eq!(
+            RawHeader::split(&[0x80, 0x01, 0, 0, 0, 9, b'a']).err(),
+            Some(ArchiveError::Truncated)
+        );
+    }
+}
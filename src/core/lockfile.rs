@@ -3,7 +3,10 @@ use crate::core::manifest::FromFile;
 use crate::core::manifest::Id;
 use crate::core::source::Source;
 use crate::core::uuid::Uuid;
-use crate::core::{catalog::CacheSlot, ip::IpSpec};
+use crate::core::{
+    catalog::CacheSlot,
+    ip::{IpSpec, PartialIpSpec},
+};
 use crate::core::{
     pkgid::PkgPart,
     version::{self, AnyVersion, Version},
@@ -70,6 +73,31 @@ impl LockFile {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub enum LockError {
+    OutOfDate,
+    RequiresUpdate,
+}
+
+impl Error for LockError {}
+
+impl Display for LockError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfDate => write!(
+                f,
+                "{} is missing or out of date with the manifest and cannot be updated while locked",
+                IP_LOCK_FILE
+            ),
+            Self::RequiresUpdate => write!(
+                f,
+                "{} needs to be updated but cannot be modified while locked",
+                IP_LOCK_FILE
+            ),
+        }
+    }
+}
+
 impl FromFile for LockFile {
     fn from_file(path: &PathBuf) -> Result<Self, Box<dyn Error>> {
         if path.exists() == true {
@@ -177,21 +205,24 @@ pub mod v1 {
         }
 
         /// Returns the highest compatible version from the lockfile for the given `target`.
+        ///
+        /// An unqualified `target` matches the entry with a qualified name ending
+        /// in it when no entry has the exact name.
         pub fn get_highest(&self, target: &PkgPart, version: &AnyVersion) -> Option<&LockEntry> {
+            let exact = self.ip.iter().any(|f| &f.name == target);
+            let is_match = |f: &&LockEntry| match exact {
+                true => &f.name == target,
+                false => target.refers_to(&f.name),
+            };
             // collect all versions
             let space: Vec<&Version> = self
                 .ip
                 .iter()
-                .filter_map(|f| {
-                    if &f.name == target {
-                        Some(&f.version)
-                    } else {
-                        None
-                    }
-                })
+                .filter(is_match)
+                .map(|f| &f.version)
                 .collect();
             match version::get_target_version(&version, &space) {
-                Ok(v) => self.ip.iter().find(|f| &f.name == target && f.version == v),
+                Ok(v) => self.ip.iter().filter(is_match).find(|f| f.version == v),
                 Err(_) => None,
             }
         }
@@ -223,13 +254,7 @@ pub mod v1 {
                     .filter(
                         // check if this entry is a dev dependency
                         |p| match target.get_man().get_dev_deps().get(p.get_name()) {
-                            Some(v) => {
-                                if p.get_version() == v {
-                                    false
-                                } else {
-                                    true
-                                }
-                            }
+                            Some(v) => version::is_compatible(v, p.get_version()) == false,
                             None => true,
                         },
                     )
@@ -254,7 +279,11 @@ pub mod v1 {
         checksum: Option<Sha256Hash>,
         #[serde(flatten)]
         source: Option<Source>,
-        dependencies: Vec<IpSpec>,
+        /// The revision the source was resolved to when it was fetched.
+        commit: Option<String>,
+        /// The digest of the archive the source was fetched as.
+        digest: Option<Sha256Hash>,
+        dependencies: Vec<PartialIpSpec>,
     }
 
     impl From<(&Ip, bool)> for LockEntry {
@@ -274,14 +303,22 @@ pub mod v1 {
                     )
                 },
                 source: ip.get_man().get_ip().get_source().cloned(),
+                commit: match is_working {
+                    true => None,
+                    false => Ip::read_commit_from_metadata(ip.get_root()),
+                },
+                digest: match is_working {
+                    true => None,
+                    false => Ip::read_digest_from_metadata(ip.get_root()),
+                },
                 dependencies: match ip.get_man().get_deps_list(is_working).len() {
                     0 => Vec::new(),
                     _ => {
-                        let mut result: Vec<IpSpec> = ip
+                        let mut result: Vec<PartialIpSpec> = ip
                             .get_man()
                             .get_deps_list(is_working)
                             .into_iter()
-                            .map(|e| IpSpec::new(e.0.clone(), e.1.clone()))
+                            .map(|e| PartialIpSpec::from((e.0.clone(), e.1.clone())))
                             .collect();
                         result.sort_by(|x, y| match x.get_name().cmp(&y.get_name()) {
                             std::cmp::Ordering::Less => std::cmp::Ordering::Less,
@@ -307,7 +344,7 @@ pub mod v1 {
                 && self.get_deps() == other.get_deps()
         }
 
-        pub fn get_deps(&self) -> &Vec<IpSpec> {
+        pub fn get_deps(&self) -> &Vec<PartialIpSpec> {
             self.dependencies.as_ref()
         }
 
@@ -323,6 +360,27 @@ pub mod v1 {
             self.source.as_ref()
         }
 
+        pub fn get_commit(&self) -> Option<&String> {
+            self.commit.as_ref()
+        }
+
+        pub fn get_digest(&self) -> Option<&Sha256Hash> {
+            self.digest.as_ref()
+        }
+
+        /// Creates the source to fetch the entry from, pinned to the commit and
+        /// archive digest recorded when it was first downloaded.
+        ///
+        /// A digest given by the source itself takes priority over the recorded digest.
+        pub fn get_pinned_source(&self) -> Option<Source> {
+            let src = self.source.as_ref()?;
+            Some(
+                src.clone()
+                    .commit(self.commit.clone())
+                    .sha256(src.get_sha256().or(self.digest.as_ref()).cloned()),
+            )
+        }
+
         pub fn get_name(&self) -> &Id {
             &self.name
         }
@@ -347,6 +405,7 @@ pub mod v1 {
     #[cfg(test)]
     mod test {
         use super::*;
+        use crate::core::version::PartialVersion;
 
         #[test]
         fn to_string() {
@@ -359,15 +418,17 @@ pub mod v1 {
                         uuid: Uuid::nil(),
                         checksum: None,
                         source: Some(Source::from_str("https://go1.here").unwrap()),
+                        commit: None,
+                        digest: None,
                         dependencies: vec![
-                            IpSpec::new(
+                            PartialIpSpec::from((
                                 PkgPart::from_str("lab4").unwrap(),
-                                Version::from_str("0.5.19").unwrap(),
-                            ),
-                            IpSpec::new(
+                                PartialVersion::from_str("0.5.19").unwrap(),
+                            )),
+                            PartialIpSpec::from((
                                 PkgPart::from_str("lab2").unwrap(),
-                                Version::from_str("1.0.0").unwrap(),
-                            ),
+                                PartialVersion::from_str("1.0.0").unwrap(),
+                            )),
                         ],
                     },
                     LockEntry {
@@ -376,6 +437,8 @@ pub mod v1 {
                         uuid: Uuid::nil(),
                         checksum: Some(Sha256Hash::new()),
                         source: Some(Source::from_str("https://go2.here").unwrap()),
+                        commit: None,
+                        digest: None,
                         dependencies: Vec::new(),
                     },
                     LockEntry {
@@ -384,6 +447,8 @@ pub mod v1 {
                         uuid: Uuid::nil(),
                         checksum: Some(Sha256Hash::new()),
                         source: None,
+                        commit: None,
+                        digest: None,
                         dependencies: Vec::new(),
                     },
                     LockEntry {
@@ -392,10 +457,12 @@ pub mod v1 {
                         uuid: Uuid::nil(),
                         checksum: Some(Sha256Hash::new()),
                         source: None,
-                        dependencies: vec![IpSpec::new(
+                        commit: None,
+                        digest: None,
+                        dependencies: vec![PartialIpSpec::from((
                             PkgPart::from_str("lab3").unwrap(),
-                            Version::from_str("2.3.1").unwrap(),
-                        )],
+                            PartialVersion::from_str("2.3.1").unwrap(),
+                        ))],
                     },
                 ],
             };
@@ -414,15 +481,17 @@ pub mod v1 {
                         checksum: None,
                         uuid: Uuid::nil(),
                         source: Some(Source::from_str("https://go1.here").unwrap()),
+                        commit: None,
+                        digest: None,
                         dependencies: vec![
-                            IpSpec::new(
+                            PartialIpSpec::from((
                                 PkgPart::from_str("lab4").unwrap(),
-                                Version::from_str("0.5.19").unwrap(),
-                            ),
-                            IpSpec::new(
+                                PartialVersion::from_str("0.5.19").unwrap(),
+                            )),
+                            PartialIpSpec::from((
                                 PkgPart::from_str("lab2").unwrap(),
-                                Version::from_str("1.0.0").unwrap(),
-                            ),
+                                PartialVersion::from_str("1.0.0").unwrap(),
+                            )),
                         ],
                     },
                     LockEntry {
@@ -431,6 +500,8 @@ pub mod v1 {
                         uuid: Uuid::nil(),
                         checksum: Some(Sha256Hash::new()),
                         source: Some(Source::from_str("https://go2.here").unwrap()),
+                        commit: None,
+                        digest: None,
                         dependencies: Vec::new(),
                     },
                     LockEntry {
@@ -439,6 +510,8 @@ pub mod v1 {
                         uuid: Uuid::nil(),
                         checksum: Some(Sha256Hash::new()),
                         source: None,
+                        commit: None,
+                        digest: None,
                         dependencies: Vec::new(),
                     },
                     LockEntry {
@@ -447,16 +520,146 @@ pub mod v1 {
                         uuid: Uuid::nil(),
                         checksum: Some(Sha256Hash::new()),
                         source: None,
-                        dependencies: vec![IpSpec::new(
+                        commit: None,
+                        digest: None,
+                        dependencies: vec![PartialIpSpec::from((
                             PkgPart::from_str("lab3").unwrap(),
-                            Version::from_str("2.3.1").unwrap(),
-                        )],
+                            PartialVersion::from_str("2.3.1").unwrap(),
+                        ))],
                     },
                 ],
             };
             assert_eq!(&LockFile::from_str(&DATA1).unwrap(), &lock);
         }
 
+        #[test]
+        fn qualified_highest() {
+            let data = r#"version = 1
+
+[[ip]]
+name = "ks-tech.rary.fifo"
+version = "1.0.0"
+uuid = "00000000-0000-0000-0000-000000000000"
+checksum = "0000000000000000000000000000000000000000000000000000000000000000"
+dependencies = []
+
+[[ip]]
+name = "ks-tech.rary.fifo"
+version = "1.2.0"
+uuid = "00000000-0000-0000-0000-000000000000"
+checksum = "0000000000000000000000000000000000000000000000000000000000000000"
+dependencies = []
+"#;
+            let lock = LockFile::from_str(data).unwrap();
+            // an unqualified name falls back to the qualified entries
+            let entry = lock
+                .get_highest(&PkgPart::from_str("fifo").unwrap(), &AnyVersion::Latest)
+                .unwrap();
+            assert_eq!(entry.get_version(), &Version::from_str("1.2.0").unwrap());
+            assert_eq!(
+                entry.to_cache_slot_key().to_string(),
+                "ks-tech.rary.fifo-1.2.0-0000000000"
+            );
+            assert_eq!(
+                lock.get_highest(
+                    &PkgPart::from_str("other.fifo").unwrap(),
+                    &AnyVersion::Latest
+                ),
+                None
+            );
+        }
+
+        #[test]
+        fn commit_roundtrip() {
+            let data = r#"version = 1
+
+[[ip]]
+name = "lab2"
+version = "1.0.0"
+uuid = "00000000-0000-0000-0000-000000000000"
+checksum = "0000000000000000000000000000000000000000000000000000000000000000"
+url = "https://go2.here"
+protocol = "git"
+tag = "1.0.0"
+commit = "0123456789abcdef0123456789abcdef01234567"
+digest = "0000000000000000000000000000000000000000000000000000000000000000"
+dependencies = []
+"#;
+            let lock = LockFile::from_str(data).unwrap();
+            let entry = lock.inner().first().unwrap();
+            assert_eq!(
+                entry.get_commit(),
+                Some(&String::from("0123456789abcdef0123456789abcdef01234567"))
+            );
+            assert_eq!(
+                entry.get_source().unwrap().get_protocol(),
+                Some(&String::from("git"))
+            );
+            // the recorded values are used to fetch the entry again
+            let src = entry.get_pinned_source().unwrap();
+            assert_eq!(src.get_commit(), entry.get_commit());
+            assert_eq!(src.get_sha256(), Some(&Sha256Hash::new()));
+            assert_eq!(&lock.to_string(), data);
+        }
+
+        #[test]
+        fn exact_deps_still_resolve() {
+            // lockfiles written before partial versions list exact dependencies
+            let lock = LockFile::from_str(&DATA1).unwrap();
+            let lab4 = lock
+                .get(
+                    &PkgPart::from_str("lab4").unwrap(),
+                    &Version::from_str("0.5.19").unwrap(),
+                )
+                .unwrap();
+            assert_eq!(
+                lab4.get_deps(),
+                &vec![PartialIpSpec::from((
+                    PkgPart::from_str("lab3").unwrap(),
+                    PartialVersion::from_str("2.3.1").unwrap(),
+                ))]
+            );
+            let dep = lab4.get_deps().first().unwrap();
+            assert_eq!(
+                lock.get_highest(dep.get_name(), dep.get_version())
+                    .unwrap()
+                    .get_version(),
+                &Version::from_str("2.3.1").unwrap()
+            );
+            // a partial requirement resolves to the recorded version
+            let lock = LockFile::from_str(&DATA1.replace("lab3:2.3.1", "lab3:2")).unwrap();
+            let dep = lock.inner().last().unwrap().get_deps().first().unwrap();
+            assert_eq!(dep.to_string(), "lab3:2");
+            assert_eq!(
+                lock.get_highest(dep.get_name(), dep.get_version())
+                    .unwrap()
+                    .get_version(),
+                &Version::from_str("2.3.1").unwrap()
+            );
+        }
+
+        #[test]
+        fn keep_dev_dep_entries() {
+            let dir = tempfile::tempdir().unwrap();
+            std::fs::write(
+                dir.path().join("Orbit.toml"),
+                "[ip]\nname = \"lab1\"\nversion = \"0.5.0\"\n\n[dev-dependencies]\nlab3 = \"2\"\nlab2 = \"1.1\"\n",
+            )
+            .unwrap();
+            let ip = Ip::load(dir.path().to_path_buf(), true).unwrap();
+            let lock = LockFile::from_str(&DATA1).unwrap();
+
+            assert_eq!(lock.keep_dev_dep_entries(&ip, true), lock);
+            // only entries satisfying a dev-dependency's requirement are removed
+            let kept: Vec<String> = lock
+                .keep_dev_dep_entries(&ip, false)
+                .inner()
+                .iter()
+                .map(|e| e.to_ip_spec().to_string())
+                .collect();
+            assert_eq!(kept, vec!["lab1:0.5.0", "lab2:1.0.0", "lab4:0.5.19"]);
+        }
+
         const DATA1: &str = r#"version = 1
 
 [[ip]]
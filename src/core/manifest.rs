@@ -1,7 +1,10 @@
 #![allow(dead_code)]
 
+use crate::core::hook::Hooks;
 use crate::core::ip::IpSpec;
 use crate::core::pkgid::PkgPart;
+use crate::core::plugin::{Plugin, Plugins};
+use crate::core::profile::{Profile, Profiles};
 use crate::core::source;
 use crate::core::source::Source;
 use crate::util::anyerror::{AnyError, Fault};
@@ -15,11 +18,12 @@ use super::lang::vhdl::token::identifier::Identifier;
 
 pub type Id = PkgPart;
 pub type Version = crate::core::version::Version;
+pub type PartialVersion = crate::core::version::PartialVersion;
 
-type Dependencies = HashMap<Id, Version>;
+type Dependencies = HashMap<Id, PartialVersion>;
 
 pub const IP_MANIFEST_FILE: &str = "Orbit.toml";
-// pub const IP_MANIFEST_PATTERN_FILE : &str = "Orbit-*.toml";
+pub const IP_MANIFEST_PATTERN_FILE: &str = "Orbit-*.toml";
 pub const ORBIT_SUM_FILE: &str = ".orbit-checksum";
 pub const ORBIT_METADATA_FILE: &str = ".orbit-metadata";
 
@@ -37,6 +41,16 @@ pub struct Manifest {
         default
     )]
     dev_dependencies: Dependencies,
+    #[serde(skip_serializing_if = "Libraries::is_default", default)]
+    libraries: Libraries,
+    #[serde(skip_serializing_if = "Hooks::is_empty", default)]
+    hooks: Hooks,
+    #[serde(skip_serializing_if = "vec_is_empty", default)]
+    plugin: Plugins,
+    #[serde(skip_serializing_if = "vec_is_empty", default)]
+    target: Plugins,
+    #[serde(skip_serializing_if = "map_is_empty", default)]
+    profile: Profiles,
 }
 
 pub trait FromFile: FromStr
@@ -73,6 +87,14 @@ impl FromFile for Manifest {
                 IP_MANIFEST_FILE, path, e
             )))?;
         }
+        // set roots for plugins and targets
+        let mut man = man;
+        if let Some(base) = path.parent() {
+            man.plugin
+                .iter_mut()
+                .chain(man.target.iter_mut())
+                .for_each(|p| p.set_root(base.to_path_buf()));
+        }
         Ok(man)
     }
 }
@@ -103,6 +125,11 @@ impl Manifest {
             },
             dependencies: Dependencies::new(),
             dev_dependencies: Dependencies::new(),
+            libraries: Libraries::default(),
+            hooks: Hooks::new(),
+            plugin: Plugins::new(),
+            target: Plugins::new(),
+            profile: Profiles::new(),
         }
     }
 
@@ -144,6 +171,35 @@ version = "0.1.0"
         &self.dev_dependencies
     }
 
+    /// Returns the list of plugins defined under the "plugin" and "target"
+    /// arrays of tables.
+    pub fn get_plugins(&self) -> Vec<&Plugin> {
+        self.plugin.iter().chain(self.target.iter()).collect()
+    }
+
+    /// Transforms the manifest into its list of plugins defined under the
+    /// "plugin" and "target" arrays of tables.
+    pub fn into_plugins(self) -> Plugins {
+        let mut plugs = self.plugin;
+        plugs.extend(self.target);
+        plugs
+    }
+
+    /// References the library mapping defined under the "libraries" table.
+    pub fn get_libraries(&self) -> &Libraries {
+        &self.libraries
+    }
+
+    /// Returns the map of profiles defined under the "profile" table by their names.
+    pub fn get_profiles(&self) -> HashMap<&str, &Profile> {
+        self.profile.iter().map(|(k, v)| (k.as_str(), v)).collect()
+    }
+
+    /// References the lifecycle hooks defined under the "hooks" table.
+    pub fn get_hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+
     pub fn is_deps_valid(&self) -> Result<(), AnyError> {
         for (key, _) in &self.dependencies {
             if let Some(_) = self.dev_dependencies.get(key) {
@@ -163,7 +219,7 @@ version = "0.1.0"
 
     /// Returns the list of dependencies found under "dependencies" and
     /// "dev-dependencies".
-    pub fn get_deps_list(&self, include_dev: bool) -> Vec<(&PkgPart, &Version)> {
+    pub fn get_deps_list(&self, include_dev: bool) -> Vec<(&PkgPart, &PartialVersion)> {
         let mut result = Vec::with_capacity(
             self.dependencies.len()
                 + match include_dev {
@@ -185,6 +241,71 @@ impl Display for Manifest {
     }
 }
 
+/// The rule for assigning an HDL library to each dependency in the blueprint.
+#[derive(Deserialize, Serialize, Debug, PartialEq, Clone)]
+pub enum LibraryPolicy {
+    /// Use the library declared in each dependency's manifest, or else "work".
+    #[serde(rename = "manifest")]
+    Manifest,
+    /// Derive the library from each dependency's name.
+    #[serde(rename = "ip-name")]
+    IpName,
+    /// Place every dependency into the same library.
+    #[serde(rename = "single")]
+    Single,
+}
+
+impl Default for LibraryPolicy {
+    fn default() -> Self {
+        Self::Manifest
+    }
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Libraries {
+    #[serde(default)]
+    policy: LibraryPolicy,
+    name: Option<Id>,
+    #[serde(skip_serializing_if = "map_is_empty", default)]
+    map: HashMap<Id, Id>,
+}
+
+impl Libraries {
+    pub fn is_default(&self) -> bool {
+        self == &Self::default()
+    }
+
+    pub fn get_policy(&self) -> &LibraryPolicy {
+        &self.policy
+    }
+
+    /// Returns the library every dependency is placed into for the "single" policy.
+    pub fn get_single_library(&self) -> Identifier {
+        match &self.name {
+            Some(n) => Identifier::from(n),
+            None => Identifier::new_working(),
+        }
+    }
+
+    /// Determines the HDL library for a dependency with the manifest `man`.
+    ///
+    /// An entry in the map for the dependency's name takes precedence over the policy.
+    pub fn assign(&self, man: &Manifest) -> Identifier {
+        let name = man.get_ip().get_name();
+        if let Some((_, lib)) = self.map.iter().find(|(k, _)| k.refers_to(name)) {
+            return Identifier::from(lib);
+        }
+        match self.policy {
+            LibraryPolicy::Manifest => man.get_hdl_library(),
+            LibraryPolicy::IpName => {
+                Identifier::Basic(name.to_normal().to_string().replace('.', "_"))
+            }
+            LibraryPolicy::Single => self.get_single_library(),
+        }
+    }
+}
+
 fn vec_is_empty<T>(field: &Vec<T>) -> bool {
     field.is_empty()
 }
@@ -415,6 +536,83 @@ mod test {
             );
         }
 
+        #[test]
+        fn ut_partial_deps() {
+            let man: Manifest = toml::from_str(EX8).unwrap();
+
+            assert_eq!(man.dependencies.len(), 3);
+            assert_eq!(
+                man.dependencies.get(&PkgPart::from_str("gates").unwrap()),
+                Some(&PartialVersion::new().major(1))
+            );
+            assert_eq!(
+                man.dependencies.get(&PkgPart::from_str("mux").unwrap()),
+                Some(&PartialVersion::new().major(2).minor(1))
+            );
+            assert_eq!(
+                man.dependencies.get(&PkgPart::from_str("alu").unwrap()),
+                Some(&PartialVersion::new().major(0).minor(3).micro(2))
+            );
+        }
+
+        #[test]
+        fn ut_hooks() {
+            use crate::core::hook::Stage;
+
+            let man: Manifest = toml::from_str(EX9).unwrap();
+            assert_eq!(man.get_hooks().get(Stage::PrePlan).len(), 1);
+            assert_eq!(man.get_hooks().get(Stage::PostBuild).len(), 1);
+            assert_eq!(man.get_hooks().get(Stage::PreBuild).len(), 0);
+
+            let man: Manifest = toml::from_str(EX2).unwrap();
+            assert_eq!(man.get_hooks().is_empty(), true);
+        }
+
+        #[test]
+        fn ut_libraries() {
+            let man: Manifest = toml::from_str(EX11).unwrap();
+            let libs = man.get_libraries();
+            assert_eq!(libs.get_policy(), &LibraryPolicy::IpName);
+            assert_eq!(libs.is_default(), false);
+
+            let dep: Manifest = toml::from_str(
+                "[ip]\nname = \"ks-tech.rary.fifo\"\nversion = \"1.0.0\"\nlibrary = \"rary\"\n",
+            )
+            .unwrap();
+            assert_eq!(libs.assign(&dep).to_string(), "ks_tech_rary_fifo");
+            // mapped names take precedence over the policy
+            let dep: Manifest =
+                toml::from_str("[ip]\nname = \"gates\"\nversion = \"1.0.0\"\n").unwrap();
+            assert_eq!(libs.assign(&dep).to_string(), "logic");
+
+            let man: Manifest = toml::from_str(EX2).unwrap();
+            assert_eq!(man.get_libraries().is_default(), true);
+            assert_eq!(man.get_libraries().assign(&dep).to_string(), "work");
+
+            let libs: Libraries = toml::from_str("policy = \"single\"\nname = \"deps\"\n").unwrap();
+            assert_eq!(libs.assign(&dep).to_string(), "deps");
+            assert_eq!(
+                toml::from_str::<Libraries>("policy = \"vendor\"").is_err(),
+                true
+            );
+        }
+
+        #[test]
+        fn ut_plugins() {
+            let man: Manifest = toml::from_str(EX10).unwrap();
+            let plugs = man.get_plugins();
+            assert_eq!(plugs.len(), 3);
+            assert_eq!(plugs[0].get_alias(), "lint");
+            assert_eq!(plugs[1].get_alias(), "sim");
+            assert_eq!(plugs[2].get_alias(), "synth");
+
+            let plugs = man.into_plugins();
+            assert_eq!(plugs.len(), 3);
+
+            let man: Manifest = toml::from_str(EX2).unwrap();
+            assert_eq!(man.get_plugins().len(), 0);
+        }
+
         #[test]
         #[should_panic]
         fn ut_source_missing_url() {
@@ -491,5 +689,54 @@ version = "1.20.0"
 source = { protocol = "ktsp" }
 "#;
 
+const EX8: &str = r#"[ip]
+name = "lab3"
+version = "0.5.0"
+
+[dependencies]
+gates = "1"
+mux = "2.1"
+alu = "0.3.2"
+"#;
+
+const EX9: &str = r#"[ip]
+name = "lab4"
+version = "0.1.0"
+
+[hooks]
+pre-plan = [{ command = "python", args = ["./scripts/gen_regs.py"] }]
+post-build = [{ command = "bash", args = ["./scripts/collect.sh", "{{ orbit.build.dir }}"] }]
+"#;
+
+const EX10: &str = r#"[ip]
+name = "lab5"
+version = "0.1.0"
+
+[[plugin]]
+name = "lint"
+command = "python"
+args = ["./tools/lint.py"]
+
+[[target]]
+name = "sim"
+command = "python"
+args = ["./tools/sim.py"]
+fileset.py-model = "{{orbit.bench}}.py"
+
+[[target]]
+name = "synth"
+command = "vivado"
+args = ["-mode", "batch", "-source", "./tools/synth.tcl"]
+"#;
+
+const EX11: &str = r#"[ip]
+name = "lab6"
+version = "0.1.0"
+
+[libraries]
+policy = "ip-name"
+map = { gates = "logic" }
+"#;
+
 const ERR1: &str = r#"[ip]
 "#;
@@ -3,6 +3,7 @@ pub mod catalog;
 pub mod config;
 pub mod context;
 pub mod fileset;
+pub mod hook;
 pub mod ip;
 pub mod iparchive;
 pub mod lang;
@@ -10,9 +11,12 @@ pub mod lockfile;
 pub mod manifest;
 pub mod pkgid;
 pub mod plugin;
+pub mod profile;
 pub mod protocol;
 pub mod pubfile;
+pub mod signature;
 pub mod source;
 pub mod uuid;
 pub mod variable;
+pub mod vendor;
 pub mod version;
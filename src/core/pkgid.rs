@@ -40,6 +40,44 @@ impl PkgPart {
             .to_string()
             .starts_with(rhs.to_normal().as_ref())
     }
+
+    /// Checks if the name is qualified by a vendor and/or library (`vendor.lib.name`).
+    pub fn is_qualified(&self) -> bool {
+        self.0.contains('.')
+    }
+
+    /// Returns the name without any vendor or library qualification.
+    pub fn get_base(&self) -> PkgPart {
+        PkgPart(self.0.rsplit('.').next().unwrap().to_owned())
+    }
+
+    /// Checks if the current [PkgPart] names the ip `other`. A less qualified name
+    /// refers to any name ending with the same parts, so `fifo` and `lib.fifo`
+    /// both refer to `vendor.lib.fifo`.
+    pub fn refers_to(&self, other: &Self) -> bool {
+        let lhs = self.to_normal();
+        let rhs = other.to_normal();
+        lhs.0 == rhs.0 || rhs.0.ends_with(&format!(".{}", lhs.0))
+    }
+
+    /// Verifies a single part follows the `PkgId` specification.
+    fn validate(s: &str) -> Result<(), PkgIdError> {
+        use PkgIdError::*;
+
+        if let Some(c) = s.chars().next() {
+            if c.is_ascii_alphabetic() == false {
+                return Err(NotAlphabeticFirst(s.to_owned()));
+            }
+        }
+        // find first char in pkgid part not following spec
+        let result = s
+            .chars()
+            .find(|&c| !c.is_ascii_alphanumeric() && !(c == '_') && !(c == '-'));
+        match result {
+            Some(r) => Err(InvalidChar(s.to_owned(), r)),
+            None => Ok(()),
+        }
+    }
 }
 
 impl AsRef<std::path::Path> for PkgPart {
@@ -60,24 +98,21 @@ impl std::str::FromStr for PkgPart {
     /// Verifies a part follows the `PkgId` specification.
     ///
     /// First character must be `alphabetic`. Remaining characters must be
-    /// `ascii alphanumeric`, `-`, or `_`.
+    /// `ascii alphanumeric`, `-`, or `_`. The name may be qualified by up to
+    /// two more parts separated by `.` for its vendor and library.
     fn from_str(s: &str) -> Result<Self, PkgIdError> {
-        use PkgIdError::*;
-
-        if let Some(c) = s.chars().next() {
-            if c.is_ascii_alphabetic() == false {
-                return Err(NotAlphabeticFirst(s.to_owned()));
-            }
+        let parts: Vec<&str> = s.split('.').collect();
+        if parts.len() > 3 {
+            return Err(PkgIdError::BadLen(s.to_owned(), parts.len()));
         }
-        // find first char in pkgid part not following spec
-        let result = s
-            .chars()
-            .find(|&c| !c.is_ascii_alphanumeric() && !(c == '_') && !(c == '-'));
-        if let Some(r) = result {
-            Err(InvalidChar(s.to_owned(), r))
-        } else {
-            Ok(PkgPart(s.to_owned()))
+        for part in &parts {
+            // every part of a qualified name must be given
+            if parts.len() > 1 && part.is_empty() == true {
+                return Err(PkgIdError::EmptyPart(s.to_owned()));
+            }
+            Self::validate(part)?;
         }
+        Ok(PkgPart(s.to_owned()))
     }
 }
 
@@ -346,6 +381,7 @@ pub enum PkgIdError {
     NotAlphabeticFirst(String),
     BadLen(String, usize),
     Empty,
+    EmptyPart(String),
     InvalidChar(String, char),
     MissingVendor,
     MissingLibrary,
@@ -361,6 +397,7 @@ impl Display for PkgIdError {
             BadLen(id, len) => write!(f, "bad length for pkgid '{}'; expecting 3 parts but found {}", id, len),
             InvalidChar(part, ch) => write!(f, "invalid character '{}' in pkgid part '{}'; can only contain alphanumeric characters, dashes, or underscores", ch, part),
             Empty => write!(f, "missing name part"),
+            EmptyPart(id) => write!(f, "empty part in pkgid '{}'", id),
             MissingLibrary => write!(f, "missing library part"),
             MissingVendor => write!(f, "missing vendor part"),
         }
@@ -570,6 +607,8 @@ mod test {
         assert_eq!(PkgPart::from_str(s), Ok(PkgPart(s.to_owned())));
         let s = "N9A-ME_1N--A432ME";
         assert_eq!(PkgPart::from_str(s), Ok(PkgPart(s.to_owned())));
+        let s = "ks-tech.rary.gates";
+        assert_eq!(PkgPart::from_str(s), Ok(PkgPart(s.to_owned())));
 
         //errors
         assert!(PkgPart::from_str("ven dor").is_err());
@@ -578,6 +617,37 @@ mod test {
         assert!(PkgPart::from_str("-name").is_err());
         assert!(PkgPart::from_str("path/name").is_err());
         assert!(PkgPart::from_str("na!me").is_err());
+        assert!(PkgPart::from_str("a.b.c.name").is_err());
+        assert!(PkgPart::from_str("vendor..name").is_err());
+        assert!(PkgPart::from_str("vendor.2lib.name").is_err());
+    }
+
+    #[test]
+    fn qualified_names() {
+        let full = PkgPart::from_str("ks-tech.rary.gates").unwrap();
+        assert_eq!(full.is_qualified(), true);
+        assert_eq!(full.get_base(), PkgPart::from_str("gates").unwrap());
+
+        // less qualified names refer to the full name
+        assert_eq!(PkgPart::from_str("gates").unwrap().refers_to(&full), true);
+        assert_eq!(
+            PkgPart::from_str("rary.gates").unwrap().refers_to(&full),
+            true
+        );
+        assert_eq!(
+            PkgPart::from_str("KS_tech.rary.gates")
+                .unwrap()
+                .refers_to(&full),
+            true
+        );
+        // but never the other way around
+        assert_eq!(full.refers_to(&PkgPart::from_str("gates").unwrap()), false);
+        // parts must match entirely
+        assert_eq!(PkgPart::from_str("ates").unwrap().refers_to(&full), false);
+        assert_eq!(
+            PkgPart::from_str("other.gates").unwrap().refers_to(&full),
+            false
+        );
     }
 
     #[test]
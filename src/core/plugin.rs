@@ -3,7 +3,8 @@
 
 use crate::core::context::Context;
 use crate::core::fileset::Fileset;
-use crate::core::fileset::Style;
+use crate::core::fileset::Rule;
+use crate::core::hook::Hooks;
 use crate::util::anyerror::AnyError;
 use crate::util::anyerror::Fault;
 use crate::util::filesystem;
@@ -16,7 +17,7 @@ use std::str::FromStr;
 
 pub type Plugins = Vec<Plugin>;
 
-type Filesets = HashMap<String, Style>;
+type Filesets = HashMap<String, Rule>;
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
@@ -27,6 +28,8 @@ pub struct Plugin {
     fileset: Option<Filesets>,
     description: Option<String>,
     explanation: Option<String>,
+    #[serde(skip_serializing_if = "Hooks::is_empty", default)]
+    hooks: Hooks,
     #[serde(skip_serializing, skip_deserializing)]
     root: Option<PathBuf>,
 }
@@ -36,6 +39,11 @@ impl Plugin {
         self.fileset.as_ref()
     }
 
+    /// References the lifecycle hooks defined for the plugin.
+    pub fn get_hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+
     /// Displays a plugin's information in a single line for quick glance.
     pub fn quick_info(&self) -> String {
         format!(
@@ -59,11 +67,12 @@ impl Plugin {
 
     /// Sets the root directory from where the command should reference paths from.
     pub fn root(mut self, root: PathBuf) -> Self {
-        self.root = Some(root);
+        self.set_root(root);
         self
     }
 
     pub fn set_root(&mut self, root: PathBuf) {
+        self.hooks.set_root(root.clone());
         self.root = Some(root);
     }
 
@@ -82,7 +91,7 @@ Name:    {}
 Command: {} {}
 Root:    {}
 Filesets:
-{}{}{}",
+{}{}{}{}",
             self.name,
             self.command,
             self.args
@@ -100,10 +109,17 @@ Filesets:
                         .unwrap()
                         .iter()
                         .fold(String::new(), |x, (n, p)| {
-                            x + &format!("  {:<16}{}\n", Fileset::standardize_name(n), p.inner())
+                            x + &format!("  {:<16}{}\n", Fileset::standardize_name(n), p)
                         })
                 }
             },
+            {
+                if self.hooks.is_empty() == true {
+                    String::new()
+                } else {
+                    format!("Hooks:\n{}", self.hooks)
+                }
+            },
             {
                 if let Some(text) = &self.description {
                     format!("\n{}\n", text)
@@ -266,11 +282,12 @@ args = ["~/scripts/download.bash"]
                 fileset: Some(HashMap::from([
                     (
                         String::from("py-model"),
-                        Style::from_str("{{orbit.bench}}.py").unwrap()
+                        Rule::from_str("{{orbit.bench}}.py").unwrap()
                     ),
-                    (String::from("text"), Style::from_str("*.txt").unwrap()),
+                    (String::from("text"), Rule::from_str("*.txt").unwrap()),
                 ])),
                 explanation: None,
+                hooks: Hooks::new(),
                 root: None,
             }
         );
@@ -285,6 +302,7 @@ args = ["~/scripts/download.bash"]
                 description: None,
                 fileset: None,
                 explanation: None,
+                hooks: Hooks::new(),
                 root: None,
             }
         );
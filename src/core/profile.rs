@@ -0,0 +1,195 @@
+//! A profile is a named set of planning and building settings, such as a
+//! simulation or synthesis setup, to select together with `--profile`.
+
+use crate::core::config::Config;
+use crate::core::fileset::Fileset;
+use crate::core::lang::vhdl::token::Identifier;
+use crate::core::manifest::Manifest;
+use crate::util::anyerror::Fault;
+use crate::util::environment::{EnvVar, Environment, ORBIT_ENV_PREFIX};
+use serde_derive::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+use std::str::FromStr;
+
+pub type Profiles = HashMap<String, Profile>;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    top: Option<String>,
+    bench: Option<String>,
+    plugin: Option<String>,
+    fileset: Option<HashMap<String, String>>,
+    args: Option<Vec<String>>,
+    env: Option<HashMap<String, String>>,
+}
+
+impl Profile {
+    pub fn get_top(&self) -> Result<Option<Identifier>, Fault> {
+        Ok(match &self.top {
+            Some(t) => Some(Identifier::from_str(t)?),
+            None => None,
+        })
+    }
+
+    pub fn get_bench(&self) -> Result<Option<Identifier>, Fault> {
+        Ok(match &self.bench {
+            Some(b) => Some(Identifier::from_str(b)?),
+            None => None,
+        })
+    }
+
+    pub fn get_plugin(&self) -> Option<&String> {
+        self.plugin.as_ref()
+    }
+
+    /// Returns the list of filesets sorted by name.
+    pub fn get_filesets(&self) -> Result<Vec<Fileset>, Fault> {
+        let mut fsets = Vec::new();
+        if let Some(map) = &self.fileset {
+            for (name, pattern) in map {
+                fsets.push(Fileset::from_str(&format!("{}={}", name, pattern))?);
+            }
+        }
+        fsets.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+        Ok(fsets)
+    }
+
+    /// Returns the arguments to pass to the plugin or command before any
+    /// arguments from the command-line.
+    pub fn get_args(&self) -> Vec<String> {
+        self.args.clone().unwrap_or(Vec::new())
+    }
+
+    /// Collects the profile's environment variables under the `ORBIT_ENV_` prefix.
+    pub fn get_env(&self) -> Environment {
+        let mut env = Environment::new();
+        if let Some(map) = &self.env {
+            map.iter().for_each(|(key, val)| {
+                env.insert(
+                    EnvVar::new()
+                        .key(&format!("{}{}", ORBIT_ENV_PREFIX, key))
+                        .value(val),
+                );
+            });
+        }
+        env
+    }
+
+    /// Merges the profile `rhs` into `self`, keeping any value already set in `self`.
+    pub fn merge(&mut self, rhs: Self) {
+        if self.top.is_none() == true {
+            self.top = rhs.top;
+        }
+        if self.bench.is_none() == true {
+            self.bench = rhs.bench;
+        }
+        if self.plugin.is_none() == true {
+            self.plugin = rhs.plugin;
+        }
+        if self.fileset.is_none() == true {
+            self.fileset = rhs.fileset;
+        }
+        if self.args.is_none() == true {
+            self.args = rhs.args;
+        }
+        if self.env.is_none() == true {
+            self.env = rhs.env;
+        }
+    }
+}
+
+/// Finds the profile `name` from the configuration, falling back to the
+/// profiles defined in the manifest.
+pub fn find<'a>(
+    name: &str,
+    config: &'a Config,
+    man: &'a Manifest,
+) -> Result<&'a Profile, ProfileError> {
+    match config.get_profiles().get(name) {
+        Some(p) => Ok(p),
+        None => match man.get_profiles().get(name) {
+            Some(p) => Ok(p),
+            None => Err(ProfileError::Missing(name.to_string())),
+        },
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ProfileError {
+    Missing(String),
+}
+
+impl Error for ProfileError {}
+
+impl std::fmt::Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Missing(name) => write!(
+                f,
+                "No profile named '{}'\n\nTry defining it under [profile.{0}] in a configuration file or the manifest",
+                name
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const P_1: &str = r#"
+bench = "adder_tb"
+plugin = "ghdl"
+fileset.py-model = "{{orbit.bench}}.py"
+fileset.waves = "*.gtkw"
+args = ["--wave", "--stop-time=10us"]
+env.seed = "4"
+"#;
+
+    const P_2: &str = r#"
+top = "adder"
+plugin = "vivado"
+args = ["--synth-only"]
+"#;
+
+    #[test]
+    fn from_toml_string() {
+        let prof: Profile = toml::from_str(P_1).unwrap();
+        assert_eq!(prof.get_top().unwrap(), None);
+        assert_eq!(
+            prof.get_bench().unwrap(),
+            Some(Identifier::from_str("adder_tb").unwrap())
+        );
+        assert_eq!(prof.get_plugin(), Some(&String::from("ghdl")));
+        let fsets = prof.get_filesets().unwrap();
+        assert_eq!(fsets.len(), 2);
+        assert_eq!(fsets[0].get_name(), "PY-MODEL");
+        assert_eq!(fsets[1].get_name(), "WAVES");
+        assert_eq!(prof.get_args(), vec!["--wave", "--stop-time=10us"]);
+        assert_eq!(
+            prof.get_env().get("ORBIT_ENV_SEED").unwrap().get_value(),
+            "4"
+        );
+
+        assert_eq!(toml::from_str::<Profile>("generics = []").is_err(), true);
+    }
+
+    #[test]
+    fn merge() {
+        let mut prof: Profile = toml::from_str(P_2).unwrap();
+        prof.merge(toml::from_str(P_1).unwrap());
+        assert_eq!(
+            prof.get_top().unwrap(),
+            Some(Identifier::from_str("adder").unwrap())
+        );
+        assert_eq!(
+            prof.get_bench().unwrap(),
+            Some(Identifier::from_str("adder_tb").unwrap())
+        );
+        assert_eq!(prof.get_plugin(), Some(&String::from("vivado")));
+        assert_eq!(prof.get_args(), vec!["--synth-only"]);
+        assert_eq!(prof.get_filesets().unwrap().len(), 2);
+    }
+}
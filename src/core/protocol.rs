@@ -32,17 +32,17 @@ impl FromStr for Protocol {
 
 impl Protocol {
     /// Performs variable substitution on the provided arguments for the protocol.
-    pub fn replace_vars_in_args(mut self, vtable: &VariableTable) -> Self {
+    pub fn replace_vars_in_args(mut self, vtable: &VariableTable) -> Result<Self, VariableError> {
         self.args = if let Some(args) = self.args {
             Some(
                 args.into_iter()
                     .map(|arg| variable::substitute(arg, vtable))
-                    .collect(),
+                    .collect::<Result<Vec<String>, VariableError>>()?,
             )
         } else {
             self.args
         };
-        self
+        Ok(self)
     }
 }
 
@@ -65,12 +65,18 @@ impl Process for Protocol {
 
 use crate::commands::orbit::UpgradeError;
 use crate::commands::orbit::RESPONSE_OKAY;
-use crate::util::anyerror::Fault;
+use crate::core::config::Http;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::extract;
+use crate::util::filesystem;
+use crate::util::sha256::{Sha256Hash, Sha256Hasher};
 use curl::easy::Easy;
+use std::cell::Cell;
+use std::fs::{self, File, OpenOptions};
 use std::io::Write;
-use tempfile;
-use zip::ZipArchive;
+use std::time::Duration;
 
+use super::variable::VariableError;
 use super::variable::VariableTable;
 
 impl Protocol {
@@ -116,42 +122,372 @@ impl Protocol {
 
     /// Performs the default behavior for a protocol.
     ///
-    /// This will attempt to download the url as a zip file and extract it to
-    /// its queue directory.
-    pub fn single_download(url: &str, dst: &PathBuf) -> Result<(), Fault> {
-        let mut body_bytes = Vec::new();
-        {
-            let mut easy = Easy::new();
-            easy.url(&url).unwrap();
-            easy.follow_location(true).unwrap();
-            {
-                let mut transfer = easy.transfer();
-                transfer
-                    .write_function(|data| {
-                        body_bytes.extend_from_slice(data);
-                        Ok(data.len())
-                    })
-                    .unwrap();
-
-                transfer.perform()?;
+    /// This will attempt to download the url as an archive (zip, tar, gzip, xz,
+    /// or zstd) and extract it to its queue directory. The body is streamed into
+    /// the file `partial`, which is resumed when left behind by an earlier
+    /// interrupted transfer. Transient failures are retried after an exponential
+    /// backoff.
+    ///
+    /// The archive is rejected if its digest does not match the `expected` digest.
+    /// Returns the digest of the archive.
+    pub fn single_download(
+        url: &str,
+        dst: &PathBuf,
+        partial: &PathBuf,
+        expected: Option<&Sha256Hash>,
+        http: &Http,
+    ) -> Result<Sha256Hash, Fault> {
+        let mut attempt = 0;
+        let (content_type, digest) = loop {
+            match Self::transfer(url, partial, http) {
+                Ok(r) => break r,
+                Err(FetchError::Transient(e)) if attempt + 1 < DOWNLOAD_ATTEMPTS => {
+                    let wait = Self::backoff(attempt);
+                    println!(
+                        "info: Retrying {} in {:.1}s ({}) ...",
+                        url,
+                        wait.as_secs_f32(),
+                        e
+                    );
+                    std::thread::sleep(wait);
+                    attempt += 1;
+                }
+                Err(FetchError::Transient(e)) => return Err(e),
+                Err(FetchError::Fatal(e)) => {
+                    // nothing written for a request that cannot succeed is worth resuming
+                    if partial.exists() == true {
+                        fs::remove_file(&partial)?;
+                    }
+                    return Err(e);
+                }
+            }
+        };
+        // verify the archive is the one that was expected
+        if let Some(expected) = expected {
+            if &digest != expected {
+                fs::remove_file(&partial)?;
+                return Err(AnyError(format!(
+                    "archive from url {} does not match its expected sha256\n\nexpected: {}\ncomputed: {}",
+                    url, expected, digest
+                )))?;
+            }
+        }
+        // decompress the archive to the queue
+        let extracted = extract::extract(partial, dst, content_type.as_deref(), url);
+        // the partial file is either complete or unusable at this point
+        fs::remove_file(&partial)?;
+        extracted?;
+        Ok(digest)
+    }
+
+    /// Computes how long to wait before making the next attempt after the
+    /// `attempt`-th failed attempt (starting from 0).
+    pub fn backoff(attempt: u32) -> Duration {
+        Duration::from_millis(BACKOFF_BASE_MS * 2_u64.pow(attempt.min(BACKOFF_MAX_EXP)))
+    }
+
+    /// Streams the body at `url` onto the end of the file `partial`, asking the
+    /// server to only send the bytes not already written.
+    ///
+    /// Returns the content type reported by the server, if any, and the digest
+    /// of the entire file.
+    fn transfer(
+        url: &str,
+        partial: &PathBuf,
+        http: &Http,
+    ) -> Result<(Option<String>, Sha256Hash), FetchError> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&partial)?;
+        let offset = file.metadata()?.len();
+
+        // hash the bytes kept from an earlier attempt before the ones that follow
+        let mut hasher = Sha256Hasher::new();
+        if offset > 0 {
+            std::io::copy(&mut File::open(&partial)?, &mut hasher)?;
+        }
+
+        let mut easy = Easy::new();
+        easy.url(&url)?;
+        easy.follow_location(true)?;
+        // keep error pages out of the partial file
+        easy.fail_on_error(true)?;
+        // abandon stalled connections so they can be retried
+        easy.connect_timeout(Duration::from_secs(30))?;
+        easy.low_speed_limit(1)?;
+        easy.low_speed_time(Duration::from_secs(30))?;
+        if let Some(proxy) = http.get_proxy() {
+            easy.proxy(proxy)?;
+        }
+        if let Some(hosts) = http.get_no_proxy() {
+            easy.noproxy(hosts)?;
+        }
+        if let Some(ca) = http.get_ca_bundle() {
+            easy.cainfo(ca)?;
+        }
+        if offset > 0 {
+            easy.resume_from(offset)?;
+        }
+
+        let restart = Cell::new(false);
+        let mut failure: Option<std::io::Error> = None;
+        let result = {
+            let mut transfer = easy.transfer();
+            transfer.header_function(|header| {
+                // a complete response to a ranged request means the server cannot resume
+                if let Some(code) = Self::parse_status_line(header) {
+                    restart.set(offset > 0 && code == RESPONSE_OKAY);
+                }
+                true
+            })?;
+            transfer.write_function(|data| {
+                if restart.replace(false) == true {
+                    if let Err(e) = file.set_len(0) {
+                        failure = Some(e);
+                        return Ok(0);
+                    }
+                    hasher = Sha256Hasher::new();
+                }
+                hasher.update(data);
+                let written = file.write_all(data);
+                match written {
+                    Ok(()) => Ok(data.len()),
+                    Err(e) => {
+                        failure = Some(e);
+                        // signal to curl to abort the transfer
+                        Ok(0)
+                    }
+                }
+            })?;
+            transfer.perform()
+        };
+        if let Some(e) = failure {
+            return Err(FetchError::Fatal(Box::new(e)));
+        }
+        match result {
+            Ok(()) => Ok((easy.content_type()?.map(|c| c.to_string()), hasher.finish())),
+            Err(e) if e.is_http_returned_error() == true => {
+                let rc = easy.response_code()?;
+                let err = Box::new(UpgradeError::FailedConnection(url.to_string(), rc));
+                match rc {
+                    // the partial file cannot be resumed so start over
+                    RESPONSE_RANGE_NOT_SATISFIABLE => {
+                        drop(file);
+                        fs::remove_file(&partial)?;
+                        Err(FetchError::Transient(err))
+                    }
+                    RESPONSE_TOO_MANY_REQUESTS | 500..=599 => Err(FetchError::Transient(err)),
+                    _ => Err(FetchError::Fatal(err)),
+                }
+            }
+            Err(e) => Err(FetchError::from(e)),
+        }
+    }
+
+    /// Performs the built-in behavior for the git protocol.
+    ///
+    /// Shallow clones the repository at `url` into the directory `dst` using the
+    /// local git binary and checks out the `rev`, which may be a branch, tag, or
+    /// commit. The remote's default branch is used when no `rev` is given. When
+    /// an `expected` commit is given, it is fetched instead of `rev` and the
+    /// checked out commit must match it. The proxy, the hosts that bypass it,
+    /// and the certificate authorities from `http` are forwarded to git.
+    ///
+    /// Returns the full hash of the commit that was checked out.
+    pub fn git_download(
+        url: &str,
+        rev: Option<&str>,
+        expected: Option<&str>,
+        dst: &PathBuf,
+        http: &Http,
+    ) -> Result<String, Fault> {
+        // values from a dependency's manifest must never be read as options to git
+        for arg in [Some(url), rev, expected].into_iter().flatten() {
+            if arg.starts_with('-') == true {
+                return Err(AnyError(format!(
+                    "invalid value \"{}\" for the built-in \"{}\" protocol: values cannot start with '-'",
+                    arg, GIT_PROTOCOL
+                )))?;
             }
-            let rc = easy.response_code()?;
-            if rc != RESPONSE_OKAY {
-                return Err(Box::new(UpgradeError::FailedConnection(
-                    url.to_string(),
-                    rc,
-                )));
+        }
+        fs::create_dir_all(&dst)?;
+        Self::git(dst, &["init", "--quiet"])?;
+        // store the network settings in the repository's local configuration
+        if let Some(proxy) = http.get_proxy() {
+            Self::git(dst, &["config", "http.proxy", proxy])?;
+        }
+        if let Some(ca) = http.get_ca_bundle() {
+            Self::git(
+                dst,
+                &[
+                    "config",
+                    "http.sslCAInfo",
+                    &filesystem::into_std_str(ca.clone()),
+                ],
+            )?;
+        }
+        // git has no setting for hosts that bypass the proxy, but curl reads them from the environment
+        let envs: Vec<(&str, &str)> = match http.get_no_proxy() {
+            Some(hosts) => vec![("no_proxy", hosts), ("NO_PROXY", hosts)],
+            None => Vec::new(),
+        };
+        // a locked commit takes priority over the user-defined revision
+        let target = expected.or(rev).unwrap_or("HEAD");
+        // not every server allows fetching an arbitrary commit by its hash
+        if let Err(e) = Self::git_with_env(
+            dst,
+            &["fetch", "--quiet", "--depth", "1", "--", url, target],
+            &envs,
+        ) {
+            // fetch every branch and tag since the commit may not be on the default branch
+            match Self::is_commit_like(target) {
+                true => Self::git_with_env(
+                    dst,
+                    &[
+                        "fetch",
+                        "--quiet",
+                        "--tags",
+                        "--",
+                        url,
+                        "+refs/heads/*:refs/remotes/origin/*",
+                    ],
+                    &envs,
+                )?,
+                false => return Err(e),
+            };
+            Self::git(
+                dst,
+                &[
+                    "-c",
+                    "advice.detachedHead=false",
+                    "checkout",
+                    "--quiet",
+                    "--detach",
+                    target,
+                ],
+            )?;
+        } else {
+            Self::git(
+                dst,
+                &[
+                    "-c",
+                    "advice.detachedHead=false",
+                    "checkout",
+                    "--quiet",
+                    "--detach",
+                    "FETCH_HEAD",
+                ],
+            )?;
+        }
+        let commit = Self::git(dst, &["rev-parse", "HEAD"])?;
+
+        // verify the checked out commit is the one that was locked; a rev that
+        // only looks like a commit may be a tag or branch
+        if let Some(wanted) = expected {
+            if commit.starts_with(&wanted.to_lowercase()) == false {
+                return Err(AnyError(format!(
+                    "resolved commit {} from {} does not match expected commit {}",
+                    commit, url, wanted
+                )))?;
             }
         }
-        // place the bytes into a file
-        let mut temp_file = tempfile::tempfile()?;
-        temp_file.write_all(&body_bytes)?;
-        let mut zip_archive = ZipArchive::new(temp_file)?;
+        // the repository history is not part of the ip
+        fs::remove_dir_all(dst.join(".git"))?;
+        Ok(commit)
+    }
+
+    /// Runs git with the `args` in the directory `dir`.
+    ///
+    /// Returns the trimmed standard output.
+    fn git(dir: &PathBuf, args: &[&str]) -> Result<String, Fault> {
+        Self::git_with_env(dir, args, &[])
+    }
+
+    /// Runs git with the `args` in the directory `dir` with the additional
+    /// environment variables `envs`.
+    ///
+    /// Returns the trimmed standard output.
+    fn git_with_env(dir: &PathBuf, args: &[&str], envs: &[(&str, &str)]) -> Result<String, Fault> {
+        let output = match std::process::Command::new("git")
+            .current_dir(&dir)
+            .args(args)
+            .envs(envs.iter().copied())
+            .output()
+        {
+            Ok(o) => o,
+            Err(e) => Err(AnyError(format!(
+                "failed to run git for the built-in \"{}\" protocol: {}",
+                GIT_PROTOCOL, e
+            )))?,
+        };
+        match output.status.success() {
+            true => Ok(String::from_utf8_lossy(&output.stdout).trim().to_string()),
+            false => Err(AnyError(format!(
+                "git {} failed: {}",
+                args.join(" "),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )))?,
+        }
+    }
 
-        // decompress the zip file to the queue
-        zip_archive.extract(&dst)?;
+    /// Checks if the `rev` looks like an abbreviated or full commit hash.
+    fn is_commit_like(rev: &str) -> bool {
+        rev.len() >= 7 && rev.len() <= 40 && rev.chars().all(|c| c.is_ascii_hexdigit())
+    }
 
-        Ok(())
+    /// Reads the status code from a raw HTTP `header` line if it is a status line.
+    fn parse_status_line(header: &[u8]) -> Option<u32> {
+        let line = std::str::from_utf8(header).ok()?;
+        match line.starts_with("HTTP/") {
+            true => line.split_whitespace().nth(1)?.parse::<u32>().ok(),
+            false => None,
+        }
+    }
+}
+
+/// The name of the protocol handled by orbit when not defined in the configuration.
+pub const GIT_PROTOCOL: &str = "git";
+
+/// The number of times a transfer is attempted before giving up.
+const DOWNLOAD_ATTEMPTS: u32 = 4;
+
+/// The time to wait after the first failed attempt.
+const BACKOFF_BASE_MS: u64 = 500;
+
+/// The highest power of 2 applied to the backoff base.
+const BACKOFF_MAX_EXP: u32 = 4;
+
+const RESPONSE_RANGE_NOT_SATISFIABLE: u32 = 416;
+const RESPONSE_TOO_MANY_REQUESTS: u32 = 429;
+
+/// A failed attempt at transferring a file from the internet.
+enum FetchError {
+    /// The attempt may succeed if tried again later.
+    Transient(Fault),
+    Fatal(Fault),
+}
+
+impl From<curl::Error> for FetchError {
+    fn from(e: curl::Error) -> Self {
+        match e.is_couldnt_connect()
+            || e.is_couldnt_resolve_host()
+            || e.is_operation_timedout()
+            || e.is_partial_file()
+            || e.is_got_nothing()
+            || e.is_send_error()
+            || e.is_recv_error()
+            || e.is_ssl_connect_error()
+        {
+            true => Self::Transient(Box::new(e)),
+            false => Self::Fatal(Box::new(e)),
+        }
+    }
+}
+
+impl From<std::io::Error> for FetchError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Fatal(Box::new(e))
     }
 }
 
@@ -283,4 +619,193 @@ args = ["~/scripts/download.bash"]
             }
         );
     }
+
+    #[test]
+    fn backoff() {
+        assert_eq!(Protocol::backoff(0), Duration::from_millis(500));
+        assert_eq!(Protocol::backoff(1), Duration::from_millis(1000));
+        assert_eq!(Protocol::backoff(3), Duration::from_millis(4000));
+        // the wait time stops growing
+        assert_eq!(Protocol::backoff(4), Duration::from_millis(8000));
+        assert_eq!(Protocol::backoff(10), Duration::from_millis(8000));
+    }
+
+    #[test]
+    fn parse_status_line() {
+        assert_eq!(
+            Protocol::parse_status_line(b"HTTP/1.1 206 Partial Content\r\n"),
+            Some(206)
+        );
+        assert_eq!(Protocol::parse_status_line(b"HTTP/2 200\r\n"), Some(200));
+        assert_eq!(
+            Protocol::parse_status_line(b"Content-Length: 1024\r\n"),
+            None
+        );
+        assert_eq!(Protocol::parse_status_line(b"\r\n"), None);
+    }
+
+    #[test]
+    fn is_commit_like() {
+        assert_eq!(Protocol::is_commit_like("a1b2c3d"), true);
+        assert_eq!(
+            Protocol::is_commit_like("0123456789abcdef0123456789abcdef01234567"),
+            true
+        );
+        assert_eq!(Protocol::is_commit_like("v1.0.0"), false);
+        assert_eq!(Protocol::is_commit_like("main"), false);
+        assert_eq!(Protocol::is_commit_like("abc"), false);
+    }
+
+    /// Runs git with the `args` in the directory `dir` for setting up a test.
+    fn run_git(dir: &PathBuf, args: &[&str]) -> String {
+        Protocol::git(dir, args).unwrap()
+    }
+
+    #[test]
+    fn git_download() {
+        let remote = tempfile::tempdir().unwrap().into_path();
+        run_git(&remote, &["init", "--quiet"]);
+        std::fs::write(remote.join("Orbit.toml"), "[ip]\n").unwrap();
+        run_git(&remote, &["add", "."]);
+        run_git(
+            &remote,
+            &[
+                "-c",
+                "user.name=orbit",
+                "-c",
+                "user.email=orbit@localhost",
+                "commit",
+                "--quiet",
+                "-m",
+                "first",
+            ],
+        );
+        run_git(&remote, &["tag", "1.0.0"]);
+        let first = run_git(&remote, &["rev-parse", "HEAD"]);
+        let url = format!("file://{}", remote.display());
+
+        // resolve a tag to its commit
+        let queue = tempfile::tempdir().unwrap().into_path();
+        let commit =
+            Protocol::git_download(&url, Some("1.0.0"), None, &queue, &Http::new()).unwrap();
+        assert_eq!(commit, first);
+        assert_eq!(queue.join("Orbit.toml").is_file(), true);
+        assert_eq!(queue.join(".git").exists(), false);
+
+        // a locked commit must match what is checked out
+        let queue = tempfile::tempdir().unwrap().into_path();
+        let commit =
+            Protocol::git_download(&url, Some("1.0.0"), Some(&first), &queue, &Http::new())
+                .unwrap();
+        assert_eq!(commit, first);
+
+        let queue = tempfile::tempdir().unwrap().into_path();
+        assert_eq!(
+            Protocol::git_download(&url, None, Some("0123456789abcdef"), &queue, &Http::new())
+                .is_err(),
+            true
+        );
+
+        // a locked commit only reachable from another branch
+        run_git(&remote, &["checkout", "--quiet", "-b", "feature"]);
+        std::fs::write(remote.join("rtl.vhd"), "").unwrap();
+        run_git(&remote, &["add", "."]);
+        run_git(
+            &remote,
+            &[
+                "-c",
+                "user.name=orbit",
+                "-c",
+                "user.email=orbit@localhost",
+                "commit",
+                "--quiet",
+                "-m",
+                "second",
+            ],
+        );
+        let second = run_git(&remote, &["rev-parse", "HEAD"]);
+        run_git(&remote, &["checkout", "--quiet", "-"]);
+        // an abbreviated hash cannot be fetched directly, so every branch is fetched
+        let queue = tempfile::tempdir().unwrap().into_path();
+        let commit =
+            Protocol::git_download(&url, None, Some(&second[..12]), &queue, &Http::new()).unwrap();
+        assert_eq!(commit, second);
+        assert_eq!(queue.join("rtl.vhd").is_file(), true);
+
+        // a tag made of hex digits is not mistaken for a commit
+        run_git(&remote, &["tag", "1234567", &second]);
+        let queue = tempfile::tempdir().unwrap().into_path();
+        let commit =
+            Protocol::git_download(&url, Some("1234567"), None, &queue, &Http::new()).unwrap();
+        assert_eq!(commit, second);
+
+        // values cannot pass options to git
+        for (url, rev) in [
+            ("--upload-pack=touch pwned", None),
+            (url.as_str(), Some("--upload-pack=touch pwned")),
+        ] {
+            let queue = tempfile::tempdir().unwrap().into_path();
+            assert_eq!(
+                Protocol::git_download(url, rev, None, &queue, &Http::new()).is_err(),
+                true
+            );
+            assert_eq!(queue.join(".git").exists(), false);
+        }
+    }
+
+    #[test]
+    fn single_download_verifies_sha256() {
+        let dir = tempfile::tempdir().unwrap().into_path();
+        // package a directory into an archive to serve
+        let pkg = dir.join("pkg");
+        std::fs::create_dir_all(&pkg).unwrap();
+        std::fs::write(pkg.join("Orbit.toml"), "[ip]\n").unwrap();
+        let archive = dir.join("pkg.zip");
+        crate::util::compress::write_zip_dir(&pkg, &archive).unwrap();
+        let bytes = std::fs::read(&archive).unwrap();
+        let digest = crate::util::sha256::compute_sha256(&bytes);
+        let url = format!("file://{}", archive.display());
+
+        let partial = dir.join("pkg.part");
+        let queue = dir.join("queue1");
+        assert_eq!(
+            Protocol::single_download(
+                &url,
+                &queue,
+                &partial,
+                Some(&Sha256Hash::new()),
+                &Http::new()
+            )
+            .is_err(),
+            true
+        );
+        // a rejected archive is not kept for resuming
+        assert_eq!(partial.exists(), false);
+        assert_eq!(queue.join("Orbit.toml").exists(), false);
+
+        let queue = dir.join("queue2");
+        assert_eq!(
+            Protocol::single_download(&url, &queue, &partial, Some(&digest), &Http::new()).unwrap(),
+            digest
+        );
+        assert_eq!(queue.join("Orbit.toml").is_file(), true);
+        assert_eq!(partial.exists(), false);
+
+        // a request that cannot succeed leaves no partial file behind
+        let missing = format!("file://{}", dir.join("missing.zip").display());
+        assert_eq!(
+            Protocol::single_download(&missing, &queue, &partial, None, &Http::new()).is_err(),
+            true
+        );
+        assert_eq!(partial.exists(), false);
+
+        // the bytes kept from an interrupted transfer count toward the digest
+        std::fs::write(&partial, &bytes[..bytes.len() / 2]).unwrap();
+        let queue = dir.join("queue3");
+        assert_eq!(
+            Protocol::single_download(&url, &queue, &partial, Some(&digest), &Http::new()).unwrap(),
+            digest
+        );
+        assert_eq!(queue.join("Orbit.toml").is_file(), true);
+    }
 }
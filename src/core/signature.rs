@@ -0,0 +1,392 @@
+//! Ed25519 signatures that authenticate who produced an ip archive.
+//!
+//! A signature is either embedded in the archive's header as a section holding
+//! \[PUBLIC_KEY SIGNATURE] or stored in a detached `<archive>.sig` text file
+//! next to the archive. Both forms sign the same contents, which are every other
+//! header section followed by the zipped project.
+
+use crate::util::anyerror::{AnyError, Fault};
+use colored::Colorize;
+use ed25519_dalek::Signer as _;
+use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const SIGNATURE_EXT: &str = "sig";
+
+/// The label that begins a detached signature file.
+const DETACHED_LABEL: &str = "ed25519";
+
+const KEY_LEN: usize = 32;
+
+const SIG_LEN: usize = 64;
+
+/// Determines what happens when an archive is unsigned or its signature cannot
+/// be trusted.
+#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum SignaturePolicy {
+    #[serde(rename = "ignore")]
+    Ignore,
+    #[serde(rename = "warn")]
+    Warn,
+    #[serde(rename = "require")]
+    Require,
+}
+
+impl Default for SignaturePolicy {
+    fn default() -> Self {
+        Self::Ignore
+    }
+}
+
+/// A public key and the signature it produced over an archive.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ArchiveSignature {
+    key: VerifyingKey,
+    signature: Signature,
+}
+
+impl ArchiveSignature {
+    /// Signs the `message` with the secret `key`.
+    pub fn sign(message: &[u8], key: &SigningKey) -> Self {
+        Self {
+            key: key.verifying_key(),
+            signature: key.sign(message),
+        }
+    }
+
+    /// Checks the signature was produced over `message` by its public key.
+    pub fn verify(&self, message: &[u8]) -> bool {
+        self.key.verify_strict(message, &self.signature).is_ok()
+    }
+
+    pub fn get_key(&self) -> &VerifyingKey {
+        &self.key
+    }
+
+    /// Parses a signature embedded in an archive's header.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Fault> {
+        if bytes.len() != KEY_LEN + SIG_LEN {
+            return Err(AnyError(format!(
+                "expected {} bytes for an embedded signature but found {}",
+                KEY_LEN + SIG_LEN,
+                bytes.len()
+            )))?;
+        }
+        let key: [u8; KEY_LEN] = bytes[..KEY_LEN].try_into()?;
+        let signature: [u8; SIG_LEN] = bytes[KEY_LEN..].try_into()?;
+        Ok(Self {
+            key: VerifyingKey::from_bytes(&key)?,
+            signature: Signature::from_bytes(&signature),
+        })
+    }
+
+    /// Serializes the signature to be embedded in an archive's header.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(KEY_LEN + SIG_LEN);
+        bytes.extend_from_slice(self.key.as_bytes());
+        bytes.extend_from_slice(&self.signature.to_bytes());
+        bytes
+    }
+
+    /// Parses the contents of a detached signature file.
+    pub fn from_detached(text: &str) -> Result<Self, Fault> {
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        match fields.as_slice() {
+            [DETACHED_LABEL, key, signature] => Ok(Self {
+                key: parse_verifying_key(key)?,
+                signature: Signature::from_bytes(&from_hex::<SIG_LEN>(signature)?),
+            }),
+            _ => Err(AnyError(format!(
+                "expected \"{} <public-key> <signature>\"",
+                DETACHED_LABEL
+            )))?,
+        }
+    }
+
+    /// Serializes the signature into the contents of a detached signature file.
+    pub fn to_detached(&self) -> String {
+        format!(
+            "{} {} {}\n",
+            DETACHED_LABEL,
+            to_hex(self.key.as_bytes()),
+            to_hex(&self.signature.to_bytes())
+        )
+    }
+}
+
+/// Returns the path to the detached signature file for the `archive`.
+pub fn detached_path(archive: &Path) -> PathBuf {
+    let mut name = archive.as_os_str().to_os_string();
+    name.push(".");
+    name.push(SIGNATURE_EXT);
+    PathBuf::from(name)
+}
+
+/// Deletes the detached signature file for the `archive`, if one exists.
+pub fn remove_detached(archive: &Path) -> Result<(), Fault> {
+    let sig = detached_path(archive);
+    if sig.is_file() == true {
+        fs::remove_file(&sig)?;
+    }
+    Ok(())
+}
+
+/// Reads a hexadecimal secret key from the file at `path`.
+pub fn load_signing_key(path: &Path) -> Result<SigningKey, Fault> {
+    let text = match fs::read_to_string(&path) {
+        Ok(t) => t,
+        Err(e) => Err(AnyError(format!(
+            "failed to read signing key {}: {}",
+            path.display(),
+            e
+        )))?,
+    };
+    match from_hex::<KEY_LEN>(text.trim()) {
+        Ok(seed) => Ok(SigningKey::from_bytes(&seed)),
+        Err(e) => Err(AnyError(format!(
+            "invalid signing key {}: {}",
+            path.display(),
+            e
+        )))?,
+    }
+}
+
+/// Parses a hexadecimal public key.
+pub fn parse_verifying_key(s: &str) -> Result<VerifyingKey, Fault> {
+    Ok(VerifyingKey::from_bytes(&from_hex::<KEY_LEN>(s)?)?)
+}
+
+/// Settings for signing archives written by orbit.
+#[derive(Debug)]
+pub struct Signer {
+    key: SigningKey,
+    detached: bool,
+}
+
+impl Signer {
+    pub fn new(key: SigningKey, detached: bool) -> Self {
+        Self { key, detached }
+    }
+
+    /// Signs the contents of an archive.
+    pub fn sign(&self, message: &[u8]) -> ArchiveSignature {
+        ArchiveSignature::sign(message, &self.key)
+    }
+
+    /// Checks if the signature is stored in a detached file rather than
+    /// embedded in the archive.
+    pub fn is_detached(&self) -> bool {
+        self.detached
+    }
+}
+
+/// The outcome of checking the signature of an archive.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Trusted,
+    Unsigned,
+    Invalid,
+    Untrusted(String),
+}
+
+/// The public keys trusted to sign archives and how to handle archives that
+/// are not signed by one of them.
+#[derive(Debug, PartialEq)]
+pub struct Trust {
+    policy: SignaturePolicy,
+    keys: Vec<VerifyingKey>,
+}
+
+impl Trust {
+    pub fn new(policy: SignaturePolicy, keys: Vec<VerifyingKey>) -> Self {
+        Self { policy, keys }
+    }
+
+    pub fn get_policy(&self) -> &SignaturePolicy {
+        &self.policy
+    }
+
+    /// Determines if the `message` read from an archive is signed by a
+    /// trusted key, using the `embedded` signature or else a detached
+    /// signature file.
+    pub fn judge(
+        &self,
+        message: &[u8],
+        embedded: Option<ArchiveSignature>,
+        detached: Option<&str>,
+    ) -> Verdict {
+        let sig = match embedded {
+            Some(s) => s,
+            None => match detached.map(|d| ArchiveSignature::from_detached(d)) {
+                Some(Ok(s)) => s,
+                Some(Err(_)) => return Verdict::Invalid,
+                None => return Verdict::Unsigned,
+            },
+        };
+        if sig.verify(message) == false {
+            Verdict::Invalid
+        } else if self.keys.contains(sig.get_key()) == false {
+            Verdict::Untrusted(to_hex(sig.get_key().as_bytes()))
+        } else {
+            Verdict::Trusted
+        }
+    }
+
+    /// Checks the signature of the archive at `path` according to the policy.
+    ///
+    /// Errors if the policy requires a trusted signature and the archive does
+    /// not have one.
+    pub fn check(
+        &self,
+        path: &Path,
+        message: &[u8],
+        embedded: Option<ArchiveSignature>,
+    ) -> Result<(), Fault> {
+        if self.policy == SignaturePolicy::Ignore {
+            return Ok(());
+        }
+        let detached = fs::read_to_string(detached_path(path)).ok();
+        let reason = match self.judge(message, embedded, detached.as_deref()) {
+            Verdict::Trusted => return Ok(()),
+            Verdict::Unsigned => format!("archive {} is not signed", path.display()),
+            Verdict::Invalid => format!(
+                "archive {} has a signature that does not match its contents",
+                path.display()
+            ),
+            Verdict::Untrusted(key) => format!(
+                "archive {} is signed by untrusted key {}",
+                path.display(),
+                key
+            ),
+        };
+        match self.policy {
+            SignaturePolicy::Require => Err(AnyError(reason))?,
+            _ => {
+                println!("{}: {}", "warning".yellow().bold(), reason);
+                Ok(())
+            }
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex<const N: usize>(s: &str) -> Result<[u8; N], Fault> {
+    if s.len() != N * 2 || s.is_ascii() == false {
+        return Err(AnyError(format!(
+            "expected {} hexadecimal digits but found {}",
+            N * 2,
+            s.len()
+        )))?;
+    }
+    let mut bytes = [0; N];
+    for i in 0..N {
+        bytes[i] = u8::from_str_radix(&s[2 * i..2 * i + 2], 16)?;
+    }
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(seed: u8) -> SigningKey {
+        SigningKey::from_bytes(&[seed; KEY_LEN])
+    }
+
+    #[test]
+    fn embedded_roundtrip() {
+        let sig = ArchiveSignature::sign(b"archive contents", &key(1));
+        let bytes = sig.to_bytes();
+        assert_eq!(bytes.len(), KEY_LEN + SIG_LEN);
+        assert_eq!(ArchiveSignature::from_bytes(&bytes).unwrap(), sig);
+        assert_eq!(ArchiveSignature::from_bytes(&bytes[1..]).is_err(), true);
+    }
+
+    #[test]
+    fn detached_roundtrip() {
+        let sig = ArchiveSignature::sign(b"archive contents", &key(2));
+        let text = sig.to_detached();
+        assert_eq!(text.starts_with("ed25519 "), true);
+        assert_eq!(ArchiveSignature::from_detached(&text).unwrap(), sig);
+        assert_eq!(ArchiveSignature::from_detached("ed25519 00").is_err(), true);
+        assert_eq!(
+            detached_path(Path::new("downloads/gates-1.0.0-ab.ip")),
+            PathBuf::from("downloads/gates-1.0.0-ab.ip.sig")
+        );
+    }
+
+    #[test]
+    fn judge_signatures() {
+        let trust = Trust::new(SignaturePolicy::Require, vec![key(1).verifying_key()]);
+        let message = b"archive contents";
+
+        let trusted = ArchiveSignature::sign(message, &key(1));
+        assert_eq!(
+            trust.judge(message, Some(trusted.clone()), None),
+            Verdict::Trusted
+        );
+        assert_eq!(
+            trust.judge(message, None, Some(&trusted.to_detached())),
+            Verdict::Trusted
+        );
+        assert_eq!(
+            trust.judge(b"tampered", Some(trusted), None),
+            Verdict::Invalid
+        );
+        assert_eq!(trust.judge(message, None, None), Verdict::Unsigned);
+        assert_eq!(
+            trust.judge(message, None, Some("not a signature")),
+            Verdict::Invalid
+        );
+
+        let untrusted = ArchiveSignature::sign(message, &key(3));
+        assert_eq!(
+            trust.judge(message, Some(untrusted), None),
+            Verdict::Untrusted(to_hex(key(3).verifying_key().as_bytes()))
+        );
+    }
+
+    #[test]
+    fn hex_keys() {
+        let public = to_hex(key(4).verifying_key().as_bytes());
+        assert_eq!(
+            parse_verifying_key(&public).unwrap(),
+            key(4).verifying_key()
+        );
+        assert_eq!(parse_verifying_key("abc").is_err(), true);
+        assert_eq!(parse_verifying_key(&"zz".repeat(KEY_LEN)).is_err(), true);
+    }
+
+    #[test]
+    fn check_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gates.ip");
+        let trust = Trust::new(SignaturePolicy::Require, vec![key(5).verifying_key()]);
+
+        // embedded
+        let sig = Signer::new(key(5), false).sign(b"archive contents");
+        assert_eq!(
+            trust
+                .check(&path, b"archive contents", Some(sig.clone()))
+                .is_ok(),
+            true
+        );
+
+        // detached
+        fs::write(detached_path(&path), sig.to_detached()).unwrap();
+        assert_eq!(trust.check(&path, b"archive contents", None).is_ok(), true);
+        remove_detached(&path).unwrap();
+        assert_eq!(trust.check(&path, b"archive contents", None).is_err(), true);
+        // warnings do not fail
+        let lenient = Trust::new(SignaturePolicy::Warn, Vec::new());
+        assert_eq!(
+            lenient.check(&path, b"archive contents", None).is_ok(),
+            true
+        );
+    }
+}
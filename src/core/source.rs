@@ -1,4 +1,5 @@
 use crate::util::anyerror::AnyError;
+use crate::util::sha256::Sha256Hash;
 use serde_derive::Deserialize;
 use std::str::FromStr;
 
@@ -10,9 +11,14 @@ pub struct Source {
     url: String,
     /// A `tag`is optional user-defined information that is needed to proceed with the protocol.
     tag: Option<String>,
+    /// The expected digest of the archive fetched from the `url`.
+    sha256: Option<Sha256Hash>,
     // Valid is triggered true when built with a function other than "default".
     #[serde(skip, default = "set_true")]
     valid: bool,
+    /// The exact revision to fetch, as previously recorded in a lockfile.
+    #[serde(skip)]
+    commit: Option<String>,
 }
 
 fn set_true() -> bool {
@@ -35,12 +41,24 @@ impl Source {
         self
     }
 
+    pub fn sha256(mut self, sha256: Option<Sha256Hash>) -> Self {
+        self.sha256 = sha256;
+        self
+    }
+
+    pub fn commit(mut self, commit: Option<String>) -> Self {
+        self.commit = commit;
+        self
+    }
+
     pub fn new() -> Self {
         Self {
             protocol: None,
             url: String::new(),
             valid: true,
             tag: None,
+            sha256: None,
+            commit: None,
         }
     }
 
@@ -60,6 +78,14 @@ impl Source {
         self.tag.as_ref()
     }
 
+    pub fn get_sha256(&self) -> Option<&Sha256Hash> {
+        self.sha256.as_ref()
+    }
+
+    pub fn get_commit(&self) -> Option<&String> {
+        self.commit.as_ref()
+    }
+
     pub fn is_default(&self) -> bool {
         self.protocol.is_none()
     }
@@ -71,9 +97,9 @@ impl Source {
         }
     }
 
-    pub fn replace_vars_in_url(mut self, vtable: &VariableTable) -> Self {
-        self.url = variable::substitute(self.url, vtable);
-        self
+    pub fn replace_vars_in_url(mut self, vtable: &VariableTable) -> Result<Self, VariableError> {
+        self.url = variable::substitute(self.url, vtable)?;
+        Ok(self)
     }
 }
 
@@ -106,6 +132,8 @@ impl Default for Source {
             url: String::new(),
             valid: false,
             tag: None,
+            sha256: None,
+            commit: None,
         }
     }
 }
@@ -118,7 +146,9 @@ impl FromStr for Source {
             url: s.to_string(),
             protocol: None,
             tag: None,
+            sha256: None,
             valid: true,
+            commit: None,
         })
     }
 }
@@ -168,7 +198,7 @@ where
 
 use serde::ser::SerializeMap;
 
-use super::variable::{self, VariableTable};
+use super::variable::{self, VariableError, VariableTable};
 
 impl Serialize for Source {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -191,6 +221,9 @@ impl Serialize for Source {
                 if let Some(p) = self.get_tag() {
                     map.serialize_entry("tag", p)?;
                 }
+                if let Some(p) = self.get_sha256() {
+                    map.serialize_entry("sha256", p)?;
+                }
                 map.end()
             }
             false => serializer.serialize_none(),
@@ -211,8 +244,10 @@ mod test {
             Source {
                 protocol: None,
                 tag: None,
+                sha256: None,
                 url: String::from("https://some.url"),
                 valid: true,
+                commit: None,
             }
         );
     }
@@ -227,6 +262,24 @@ mod test {
         assert_eq!(src.is_valid(), true);
     }
 
+    #[test]
+    fn deser_sha256() {
+        let src: Source = toml::from_str(EX2).unwrap();
+        assert_eq!(
+            src.get_sha256(),
+            Some(
+                &Sha256Hash::from_str(
+                    "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef"
+                )
+                .unwrap()
+            )
+        );
+        assert_eq!(toml::from_str::<Source>(EX1).unwrap().get_sha256(), None);
+    }
+
     const EX1: &str = r#"url = "https://some.url"
 protocol = "ktsp""#;
+
+    const EX2: &str = r#"url = "https://some.url/gates-1.0.0.tar.gz"
+sha256 = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef""#;
 }
@@ -1,6 +1,17 @@
+//! Variable substitution replaces `{{ name }}` in text with the value of the
+//! variable `name`.
+//!
+//! An expression may pass its value through filters, such as
+//! `{{ orbit.ip.name | upper }}`, and look up environment variables with the
+//! `env.` prefix. Blocks written as `{% if cond %} ... {% else %} ... {% endif %}`
+//! include text based on a condition. A literal `{{` or `{%` is written as a
+//! quoted string within an expression, such as `{{ '{{' }}`.
+
 use crate::util::{anyerror::Fault, environment::Environment};
 use std::collections::HashMap;
+use std::error::Error;
 
+#[derive(Clone)]
 pub struct VariableTable(HashMap<String, String>);
 
 impl VariableTable {
@@ -27,34 +38,61 @@ impl VariableTable {
 
 const L_VAR_DELIMITER: char = '{';
 const R_VAR_DELIMITER: char = '}';
+const BLOCK_DELIMITER: char = '%';
+
+/// The prefix for looking up a variable from the process's environment.
+const ENV_PREFIX: &str = "env.";
 
 /// Performs variable replacement on the given `text`, looking up variables in
 /// the `code` to swap with their values.
-pub fn substitute(text: String, code: &VariableTable) -> String {
+///
+/// Errors if a variable is not defined and has no default value, or if the
+/// syntax is invalid.
+pub fn substitute(text: String, code: &VariableTable) -> Result<String, VariableError> {
+    let nodes = parse(tokenize(&text)?)?;
     let mut result = String::new();
+    render(&nodes, code, &mut result)?;
+    Ok(result)
+}
+
+/// A piece of text split apart by its delimiters.
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Text(String),
+    Expr(String),
+    Block(String),
+}
+
+/// Splits the `text` into plain text, expressions, and blocks.
+fn tokenize(text: &str) -> Result<Vec<Segment>, VariableError> {
+    let mut segments = Vec::new();
+    let mut buf = String::new();
 
-    let mut chars = text.chars();
+    let mut chars = text.chars().peekable();
     while let Some(c) = chars.next() {
-        // check if there is a valid variable replacement
         match c {
-            L_VAR_DELIMITER => {
-                match gather_variable(&mut chars, c, R_VAR_DELIMITER) {
-                    Ok(r) => {
-                        // remove delimiters and surrounding whitespace to get key name
-                        let key = &r[2..r.len() - 2].trim();
-                        // look up the key in the code book
-                        match code.get(*key) {
-                            Some(value) => result.push_str(value),
-                            None => result.push_str(&r),
-                        }
-                    }
-                    Err(e) => result.push_str(&e),
-                }
+            L_VAR_DELIMITER if chars.peek() == Some(&BLOCK_DELIMITER) => {
+                chars.next();
+                let block = gather_block(&mut chars)
+                    .map_err(|e| VariableError::Unclosed(format!("{{%{}", e)))?;
+                segments.push(Segment::Text(std::mem::take(&mut buf)));
+                segments.push(Segment::Block(block.trim().to_string()));
             }
-            _ => result.push(c),
+            L_VAR_DELIMITER => match gather_variable(&mut chars, c, R_VAR_DELIMITER) {
+                Ok(r) => {
+                    segments.push(Segment::Text(std::mem::take(&mut buf)));
+                    // remove delimiters and surrounding whitespace
+                    segments.push(Segment::Expr(r[2..r.len() - 2].trim().to_string()));
+                }
+                // a variable was started but never closed
+                Err(e) if e.starts_with("{{") == true => return Err(VariableError::Unclosed(e)),
+                Err(e) => buf.push_str(&e),
+            },
+            _ => buf.push(c),
         }
     }
-    result
+    segments.push(Segment::Text(buf));
+    Ok(segments)
 }
 
 /// Builds a variable following the syntax `c0c0*c_nc_n`.
@@ -92,6 +130,367 @@ fn gather_variable<T: Iterator<Item = char>>(
     Err(var)
 }
 
+/// Collects the contents of a block until the closing `%}`.
+///
+/// Assumes the opening `{%` was already consumed.
+///
+/// Errors with the collected text if the block is never closed.
+fn gather_block<T: Iterator<Item = char>>(chars: &mut T) -> Result<String, String> {
+    let mut block = String::new();
+    let mut last: Option<char> = None;
+    while let Some(c) = chars.next() {
+        if c == R_VAR_DELIMITER && last == Some(BLOCK_DELIMITER) {
+            block.pop();
+            return Ok(block);
+        }
+        block.push(c);
+        last = Some(c);
+    }
+    Err(block)
+}
+
+#[derive(Debug, PartialEq)]
+enum Node {
+    Text(String),
+    Expr(String),
+    If(String, Vec<Node>, Vec<Node>),
+}
+
+/// Arranges the segments into a tree of nodes by matching the conditional blocks.
+fn parse(segments: Vec<Segment>) -> Result<Vec<Node>, VariableError> {
+    // every open conditional with its condition, its nodes, and its else nodes
+    let mut stack: Vec<(String, Vec<Node>, Option<Vec<Node>>)> = Vec::new();
+    let mut root = Vec::new();
+
+    for seg in segments {
+        let node = match seg {
+            Segment::Text(t) if t.is_empty() == true => continue,
+            Segment::Text(t) => Node::Text(t),
+            Segment::Expr(e) => Node::Expr(e),
+            Segment::Block(b) => {
+                let (keyword, rest) = b.split_once(char::is_whitespace).unwrap_or((&b, ""));
+                match (keyword, rest.trim()) {
+                    ("if", cond) if cond.is_empty() == false => {
+                        stack.push((cond.to_string(), Vec::new(), None));
+                        continue;
+                    }
+                    ("else", "") => match stack.last_mut() {
+                        Some(frame) if frame.2.is_none() == true => {
+                            frame.2 = Some(Vec::new());
+                            continue;
+                        }
+                        _ => return Err(VariableError::Unmatched(b)),
+                    },
+                    ("endif", "") => match stack.pop() {
+                        Some((cond, then, other)) => {
+                            Node::If(cond, then, other.unwrap_or_default())
+                        }
+                        None => return Err(VariableError::Unmatched(b)),
+                    },
+                    _ => return Err(VariableError::UnknownBlock(b)),
+                }
+            }
+        };
+        // place the node into the innermost open conditional
+        match stack.last_mut() {
+            Some((_, _, Some(other))) => other.push(node),
+            Some((_, then, None)) => then.push(node),
+            None => root.push(node),
+        }
+    }
+    match stack.pop() {
+        Some((cond, _, _)) => Err(VariableError::Unterminated(cond)),
+        None => Ok(root),
+    }
+}
+
+/// Writes the evaluated `nodes` into `result`.
+fn render(nodes: &[Node], code: &VariableTable, result: &mut String) -> Result<(), VariableError> {
+    for node in nodes {
+        match node {
+            Node::Text(t) => result.push_str(t),
+            Node::Expr(e) => match evaluate(e, code)? {
+                Value::Set(v) => result.push_str(&v),
+                Value::Missing(name) => return Err(VariableError::Unknown(name)),
+            },
+            Node::If(cond, then, other) => match is_true(cond, code)? {
+                true => render(then, code, result)?,
+                false => render(other, code, result)?,
+            },
+        }
+    }
+    Ok(())
+}
+
+/// The result of evaluating an expression.
+#[derive(Debug, PartialEq)]
+enum Value {
+    Set(String),
+    /// Remembers the name of the variable that was not found.
+    Missing(String),
+}
+
+impl Value {
+    fn unwrap_or_default(self) -> String {
+        match self {
+            Self::Set(s) => s,
+            Self::Missing(_) => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Str(String),
+    Ident(String),
+    Pipe,
+    LParen,
+    RParen,
+    Comma,
+    Eq,
+    Ne,
+}
+
+/// Splits the expression `expr` into its tokens.
+fn lex(expr: &str) -> Result<Vec<Token>, VariableError> {
+    let invalid = |reason: &str| VariableError::Invalid(expr.to_string(), reason.to_string());
+    let mut tokens = Vec::new();
+    let mut chars = expr.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' | '\r' => (),
+            '|' => tokens.push(Token::Pipe),
+            '(' => tokens.push(Token::LParen),
+            ')' => tokens.push(Token::RParen),
+            ',' => tokens.push(Token::Comma),
+            '=' | '!' => match chars.next() {
+                Some('=') if c == '=' => tokens.push(Token::Eq),
+                Some('=') => tokens.push(Token::Ne),
+                _ => return Err(invalid("expected '==' or '!='")),
+            },
+            '"' | '\'' => {
+                let mut s = String::new();
+                loop {
+                    match chars.next() {
+                        Some(q) if q == c => break,
+                        Some(x) => s.push(x),
+                        None => return Err(invalid("missing closing quote")),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            _ if c.is_alphanumeric() == true || "_.-".contains(c) == true => {
+                let mut s = String::from(c);
+                while let Some(&x) = chars.peek() {
+                    if x.is_alphanumeric() == false && "_.-".contains(x) == false {
+                        break;
+                    }
+                    s.push(x);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(s));
+            }
+            _ => return Err(invalid(&format!("unexpected character '{}'", c))),
+        }
+    }
+    Ok(tokens)
+}
+
+/// Evaluates the expression `expr`, which is a variable or quoted string
+/// followed by any number of filters.
+fn evaluate(expr: &str, code: &VariableTable) -> Result<Value, VariableError> {
+    let tokens = lex(expr)?;
+    let mut tokens = tokens.iter().peekable();
+    let value = eval_pipeline(expr, &mut tokens, code)?;
+    match tokens.next() {
+        Some(_) => Err(VariableError::Invalid(
+            expr.to_string(),
+            String::from("unexpected trailing input"),
+        )),
+        None => Ok(value),
+    }
+}
+
+/// Evaluates the condition `cond` of a conditional block.
+///
+/// A value is true when it is defined and not empty. Two values can also be
+/// compared with `==` or `!=`, and `not` negates the condition.
+fn is_true(cond: &str, code: &VariableTable) -> Result<bool, VariableError> {
+    let (negate, cond) = match cond.strip_prefix("not ") {
+        Some(rest) => (true, rest),
+        None => (false, cond),
+    };
+    let tokens = lex(cond)?;
+    let mut tokens = tokens.iter().peekable();
+    let lhs = eval_pipeline(cond, &mut tokens, code)?.unwrap_or_default();
+    let result = match tokens.next() {
+        None => lhs.is_empty() == false,
+        Some(op) if op == &Token::Eq || op == &Token::Ne => {
+            let rhs = eval_pipeline(cond, &mut tokens, code)?.unwrap_or_default();
+            if tokens.next().is_some() == true {
+                return Err(VariableError::Invalid(
+                    cond.to_string(),
+                    String::from("unexpected trailing input"),
+                ));
+            }
+            (lhs == rhs) == (op == &Token::Eq)
+        }
+        Some(_) => {
+            return Err(VariableError::Invalid(
+                cond.to_string(),
+                String::from("expected '==' or '!='"),
+            ))
+        }
+    };
+    Ok(result != negate)
+}
+
+/// Evaluates a term and the filters that follow it, stopping before any
+/// comparison operator.
+fn eval_pipeline<'a, T: Iterator<Item = &'a Token>>(
+    expr: &str,
+    tokens: &mut std::iter::Peekable<T>,
+    code: &VariableTable,
+) -> Result<Value, VariableError> {
+    let mut value = eval_term(expr, tokens, code)?;
+    while tokens.peek() == Some(&&Token::Pipe) {
+        tokens.next();
+        let name = match tokens.next() {
+            Some(Token::Ident(n)) => n.clone(),
+            _ => {
+                return Err(VariableError::Invalid(
+                    expr.to_string(),
+                    String::from("expected a filter after '|'"),
+                ))
+            }
+        };
+        // collect the filter's arguments
+        let mut args = Vec::new();
+        if tokens.peek() == Some(&&Token::LParen) {
+            tokens.next();
+            loop {
+                args.push(eval_term(expr, tokens, code)?);
+                match tokens.next() {
+                    Some(Token::Comma) => continue,
+                    Some(Token::RParen) => break,
+                    _ => {
+                        return Err(VariableError::Invalid(
+                            expr.to_string(),
+                            format!("missing ')' for filter '{}'", name),
+                        ))
+                    }
+                }
+            }
+        }
+        value = apply_filter(expr, &name, value, args)?;
+    }
+    Ok(value)
+}
+
+/// Evaluates a single quoted string or variable name.
+fn eval_term<'a, T: Iterator<Item = &'a Token>>(
+    expr: &str,
+    tokens: &mut std::iter::Peekable<T>,
+    code: &VariableTable,
+) -> Result<Value, VariableError> {
+    match tokens.next() {
+        Some(Token::Str(s)) => Ok(Value::Set(s.clone())),
+        Some(Token::Ident(name)) => {
+            let value = match name.strip_prefix(ENV_PREFIX) {
+                Some(key) => std::env::var(key).ok(),
+                None => code.get(name).cloned(),
+            };
+            Ok(match value {
+                Some(v) => Value::Set(v),
+                None => Value::Missing(name.clone()),
+            })
+        }
+        _ => Err(VariableError::Invalid(
+            expr.to_string(),
+            String::from("expected a variable or quoted string"),
+        )),
+    }
+}
+
+/// Transforms the `value` with the filter `name`.
+///
+/// Filters other than `default` leave a missing value as missing.
+fn apply_filter(
+    expr: &str,
+    name: &str,
+    value: Value,
+    args: Vec<Value>,
+) -> Result<Value, VariableError> {
+    let arity = match name {
+        "default" => 1,
+        "upper" | "lower" => 0,
+        "replace" => 2,
+        _ => return Err(VariableError::UnknownFilter(name.to_string())),
+    };
+    if args.len() != arity {
+        return Err(VariableError::Invalid(
+            expr.to_string(),
+            format!("filter '{}' expects {} argument(s)", name, arity),
+        ));
+    }
+    let mut args = args.into_iter();
+    Ok(match (name, value) {
+        ("default", Value::Missing(_)) => args.next().unwrap(),
+        (_, Value::Missing(m)) => Value::Missing(m),
+        ("upper", Value::Set(v)) => Value::Set(v.to_uppercase()),
+        ("lower", Value::Set(v)) => Value::Set(v.to_lowercase()),
+        ("replace", Value::Set(v)) => {
+            let from = args.next().unwrap().unwrap_or_default();
+            let to = args.next().unwrap().unwrap_or_default();
+            Value::Set(v.replace(&from, &to))
+        }
+        (_, v) => v,
+    })
+}
+
+#[derive(Debug, PartialEq)]
+pub enum VariableError {
+    Unknown(String),
+    UnknownFilter(String),
+    UnknownBlock(String),
+    Unclosed(String),
+    Unmatched(String),
+    Unterminated(String),
+    Invalid(String, String),
+}
+
+impl Error for VariableError {}
+
+impl std::fmt::Display for VariableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unknown(name) => write!(
+                f,
+                "unknown variable '{}'\n\nTry giving it a fallback value with '{{{{ {0} | default(\"...\") }}}}'",
+                name
+            ),
+            Self::UnknownFilter(name) => write!(
+                f,
+                "unknown filter '{}' (expected one of: default, upper, lower, replace)",
+                name
+            ),
+            Self::UnknownBlock(block) => write!(f, "unknown block '{{% {} %}}'", block),
+            Self::Unclosed(text) => write!(f, "missing closing delimiter for '{}'", text),
+            Self::Unmatched(block) => write!(
+                f,
+                "block '{{% {} %}}' has no matching '{{% if %}}'",
+                block
+            ),
+            Self::Unterminated(cond) => write!(
+                f,
+                "block '{{% if {} %}}' is missing its '{{% endif %}}'",
+                cond
+            ),
+            Self::Invalid(expr, reason) => write!(f, "invalid expression '{}': {}", expr, reason),
+        }
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -138,14 +537,135 @@ mod test {
         let code = create_code();
         assert_eq!(
             substitute(text.to_owned(), &code),
-            "The quick brown fox jumped over the lazy gates.".to_owned()
+            Ok("The quick brown fox jumped over the lazy gates.".to_owned())
         );
 
+        // unknown variables are reported instead of left in place
         let text = "A duck, a bear, and a {{ animal }} walk into a {{  orbit.place   }}...";
         let code = create_code();
         assert_eq!(
             substitute(text.to_owned(), &code),
-            "A duck, a bear, and a {{ animal }} walk into a bar...".to_owned()
+            Err(VariableError::Unknown(String::from("animal")))
+        );
+
+        let text = "single { braces } are left alone";
+        assert_eq!(substitute(text.to_owned(), &code), Ok(text.to_owned()));
+
+        let text = "never closed {{ orbit.name";
+        assert_eq!(
+            substitute(text.to_owned(), &code),
+            Err(VariableError::Unclosed(String::from("{{ orbit.name")))
+        );
+    }
+
+    #[test]
+    fn filters() {
+        let code = create_code();
+        assert_eq!(
+            substitute("{{ animal | default('fox') }}".to_owned(), &code),
+            Ok("fox".to_owned())
+        );
+        assert_eq!(
+            substitute("{{ orbit.name | default(\"fox\") }}".to_owned(), &code),
+            Ok("gates".to_owned())
+        );
+        // fall back to another variable
+        assert_eq!(
+            substitute(
+                "{{ animal | default(orbit.place) | upper }}".to_owned(),
+                &code
+            ),
+            Ok("BAR".to_owned())
+        );
+        assert_eq!(
+            substitute(
+                "{{ 'A-B' | lower | replace(\"-\", '_') }}".to_owned(),
+                &code
+            ),
+            Ok("a_b".to_owned())
+        );
+        assert_eq!(
+            substitute("{{ animal | upper }}".to_owned(), &code),
+            Err(VariableError::Unknown(String::from("animal")))
+        );
+        assert_eq!(
+            substitute("{{ orbit.name | title }}".to_owned(), &code),
+            Err(VariableError::UnknownFilter(String::from("title")))
+        );
+        assert_eq!(
+            substitute("{{ orbit.name | replace('a') }}".to_owned(), &code).is_err(),
+            true
+        );
+        assert_eq!(
+            substitute("{{ orbit.name orbit.place }}".to_owned(), &code).is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn env_lookup() {
+        let code = create_code();
+        std::env::set_var("ORBIT_TEST_VARIABLE_LOOKUP", "hello");
+        assert_eq!(
+            substitute("{{ env.ORBIT_TEST_VARIABLE_LOOKUP }}".to_owned(), &code),
+            Ok("hello".to_owned())
+        );
+        assert_eq!(
+            substitute("{{ env.ORBIT_TEST_VARIABLE_MISSING }}".to_owned(), &code),
+            Err(VariableError::Unknown(String::from(
+                "env.ORBIT_TEST_VARIABLE_MISSING"
+            )))
+        );
+    }
+
+    #[test]
+    fn conditionals() {
+        let mut code = create_code();
+        code.add("orbit.bench", "");
+        let text = "{% if orbit.name %}lib={{ orbit.library }}{% else %}none{% endif %}";
+        assert_eq!(
+            substitute(text.to_owned(), &code),
+            Ok("lib=rary".to_owned())
+        );
+        // empty and missing values are false
+        let text = "{% if orbit.bench %}tb{% endif %}{% if not animal %}!{% endif %}";
+        assert_eq!(substitute(text.to_owned(), &code), Ok("!".to_owned()));
+        // comparisons and nesting
+        let text = "{% if orbit.place == 'bar' %}a{% if orbit.name != \"gates\" %}b{% else %}c{% endif %}{% endif %}";
+        assert_eq!(substitute(text.to_owned(), &code), Ok("ac".to_owned()));
+
+        assert_eq!(
+            substitute("{% if orbit.name %}open".to_owned(), &code),
+            Err(VariableError::Unterminated(String::from("orbit.name")))
+        );
+        assert_eq!(
+            substitute("{% endif %}".to_owned(), &code),
+            Err(VariableError::Unmatched(String::from("endif")))
+        );
+        assert_eq!(
+            substitute("{% for x %}".to_owned(), &code),
+            Err(VariableError::UnknownBlock(String::from("for x")))
+        );
+    }
+
+    #[test]
+    fn escape_braces() {
+        let code = create_code();
+        assert_eq!(
+            substitute(
+                "{{ '{{' }} orbit.name }} is {{ orbit.name }}".to_owned(),
+                &code
+            ),
+            Ok("{{ orbit.name }} is gates".to_owned())
+        );
+        assert_eq!(
+            substitute("{{ \"{%\" }} if %}".to_owned(), &code),
+            Ok("{% if %}".to_owned())
+        );
+        // backslashes are kept so windows-style paths still substitute
+        assert_eq!(
+            substitute("dir\\{{ orbit.name }}\\rtl".to_owned(), &code),
+            Ok("dir\\gates\\rtl".to_owned())
         );
     }
 }
@@ -0,0 +1,215 @@
+//! A vendor is a directory that tracks a collection of IP by storing copies of
+//! each IP's manifest file.
+
+use crate::core::ip::Ip;
+use crate::core::manifest::FromFile;
+use crate::core::pkgid::PkgPart;
+use crate::util::anyerror::{AnyError, Fault};
+use crate::util::filesystem::Standardize;
+use serde_derive::{Deserialize, Serialize};
+use std::error::Error;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+pub const VENDOR_INDEX_FILE: &str = "index.toml";
+/// The conventional directory name for vendors placed under ORBIT_HOME.
+pub const VENDOR_DIR: &str = "vendor";
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Index {
+    vendor: VendorDetails,
+    hook: Option<Hooks>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct VendorDetails {
+    name: PkgPart,
+    summary: Option<String>,
+    repository: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Hooks {
+    #[serde(rename = "pre-publish")]
+    pre_publish: Option<PathBuf>,
+    #[serde(rename = "post-publish")]
+    post_publish: Option<PathBuf>,
+}
+
+impl FromStr for Index {
+    type Err = toml::de::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        toml::from_str(s)
+    }
+}
+
+impl FromFile for Index {
+    fn from_file(path: &PathBuf) -> Result<Self, Box<dyn Error>> {
+        // open file
+        let contents = std::fs::read_to_string(&path)?;
+        // parse toml syntax
+        match Self::from_str(&contents) {
+            Ok(r) => Ok(r),
+            Err(e) => Err(AnyError(format!(
+                "Failed to parse {} file at path {:?}: {}",
+                VENDOR_INDEX_FILE, path, e
+            )))?,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Vendor {
+    /// The directory holding the vendor's `index.toml` file.
+    root: PathBuf,
+    index: Index,
+}
+
+impl Vendor {
+    /// Loads the vendor whose index file is directly under the directory `root`.
+    pub fn load(root: &PathBuf) -> Result<Self, Fault> {
+        let index_path = root.join(VENDOR_INDEX_FILE);
+        if index_path.is_file() == false {
+            return Err(AnyError(format!(
+                "directory {:?} is not a vendor (missing {} file)",
+                PathBuf::standardize(root),
+                VENDOR_INDEX_FILE
+            )))?;
+        }
+        Ok(Self {
+            root: root.clone(),
+            index: Index::from_file(&index_path)?,
+        })
+    }
+
+    /// Checks if the directory `path` hosts an index file.
+    pub fn is_vendor(path: &PathBuf) -> bool {
+        path.join(VENDOR_INDEX_FILE).is_file()
+    }
+
+    pub fn get_name(&self) -> &PkgPart {
+        &self.index.vendor.name
+    }
+
+    pub fn get_summary(&self) -> Option<&String> {
+        self.index.vendor.summary.as_ref()
+    }
+
+    pub fn get_repository(&self) -> Option<&String> {
+        self.index.vendor.repository.as_ref()
+    }
+
+    pub fn get_root(&self) -> &PathBuf {
+        &self.root
+    }
+
+    /// Finds all the IP manifest copies tracked by the vendor.
+    pub fn read_ip(&self) -> Result<Vec<Ip>, Fault> {
+        Ip::detect_available(&self.root)
+    }
+}
+
+/// Collects every vendor listed in `paths` along with any vendors found one level
+/// deep within the conventional vendor directory `home_vendors`.
+///
+/// Errors if a listed path is not a valid vendor. Directories within `home_vendors`
+/// that do not have an index file are silently skipped.
+pub fn collect_vendors(home_vendors: &PathBuf, paths: Vec<&PathBuf>) -> Result<Vec<Vendor>, Fault> {
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for p in paths {
+        let p = PathBuf::standardize(p);
+        if roots.contains(&p) == false {
+            roots.push(p);
+        }
+    }
+    // search the conventional location
+    if let Ok(mut rd) = std::fs::read_dir(&home_vendors) {
+        while let Some(d) = rd.next() {
+            if let Ok(e) = d {
+                let p = PathBuf::standardize(e.path());
+                if p.is_dir() == true
+                    && Vendor::is_vendor(&p) == true
+                    && roots.contains(&p) == false
+                {
+                    roots.push(p);
+                }
+            }
+        }
+    }
+
+    let mut vendors: Vec<Vendor> = Vec::with_capacity(roots.len());
+    for root in &roots {
+        let v = Vendor::load(root)?;
+        // verify the vendor name is not already taken
+        if let Some(dupe) = vendors.iter().find(|w| w.get_name() == v.get_name()) {
+            return Err(AnyError(format!(
+                "duplicate vendor '{}' found at {:?} and {:?}",
+                v.get_name(),
+                dupe.get_root(),
+                v.get_root()
+            )))?;
+        }
+        vendors.push(v);
+    }
+    Ok(vendors)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const INDEX_1: &str = r#"
+[vendor]
+name = "ks-tech"
+summary = "in-house IP for space communications"
+repository = "https://github.com/ks-tech/vendor.git"
+"#;
+
+    const INDEX_2: &str = r#"
+[vendor]
+name = "ks-tech"
+
+[hook]
+pre-publish = "./pre-publish.hook"
+post-publish = "./post-publish.hook"
+"#;
+
+    const INDEX_ERR: &str = r#"
+[vendor]
+summary = "missing required name field"
+"#;
+
+    #[test]
+    fn parse_index() {
+        let idx = Index::from_str(INDEX_1).unwrap();
+        assert_eq!(idx.vendor.name, PkgPart::from_str("ks-tech").unwrap());
+        assert_eq!(
+            idx.vendor.repository,
+            Some(String::from("https://github.com/ks-tech/vendor.git"))
+        );
+        assert_eq!(idx.hook, None);
+
+        let idx = Index::from_str(INDEX_2).unwrap();
+        assert_eq!(idx.vendor.summary, None);
+        assert_eq!(
+            idx.hook.unwrap().pre_publish,
+            Some(PathBuf::from("./pre-publish.hook"))
+        );
+
+        assert_eq!(Index::from_str(INDEX_ERR).is_err(), true);
+    }
+
+    #[test]
+    fn load_vendor() {
+        let v = Vendor::load(&PathBuf::from("./tests/data/vendor1")).unwrap();
+        assert_eq!(v.get_name(), &PkgPart::from_str("ks-tech").unwrap());
+        let ips = v.read_ip().unwrap();
+        assert_eq!(ips.len(), 2);
+
+        assert_eq!(Vendor::load(&PathBuf::from("./tests/data")).is_err(), true);
+    }
+}
@@ -114,6 +114,12 @@ impl From<&Version> for AnyVersion {
     }
 }
 
+impl From<&PartialVersion> for AnyVersion {
+    fn from(value: &PartialVersion) -> Self {
+        Self::Specific(value.clone())
+    }
+}
+
 #[derive(Debug, PartialEq, PartialOrd, Clone, Eq, Ord)]
 pub struct PartialVersion {
     major: VerNum,
@@ -325,6 +331,44 @@ impl Serialize for Version {
     }
 }
 
+impl<'de> Deserialize<'de> for PartialVersion {
+    fn deserialize<D>(deserializer: D) -> Result<PartialVersion, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        struct LayerVisitor;
+
+        impl<'de> de::Visitor<'de> for LayerVisitor {
+            type Value = PartialVersion;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a full or partial semantic version number")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                match PartialVersion::from_str(v) {
+                    Ok(v) => Ok(v),
+                    Err(e) => Err(de::Error::custom(e)),
+                }
+            }
+        }
+
+        deserializer.deserialize_map(LayerVisitor)
+    }
+}
+
+impl Serialize for PartialVersion {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
 impl Version {
     pub fn new() -> Self {
         Version {
@@ -1,8 +1,10 @@
 use crate::core::config::Config;
+use crate::util::anyerror::AnyError;
 use crate::util::anyerror::Fault;
 use std::hash::Hash;
 use std::io::Read;
 use std::io::Write;
+use std::str::FromStr;
 
 use crate::core::ip::Ip;
 use std::collections::btree_set::IntoIter;
@@ -80,6 +82,74 @@ impl EnvVar {
             self.value.to_owned(),
         )
     }
+
+    /// Writes the variable as a statement that sets it in the given `format`.
+    ///
+    /// The value is quoted so it is read literally.
+    pub fn to_export(&self, format: &ExportFormat) -> String {
+        match format {
+            ExportFormat::Sh => {
+                format!(
+                    "export {}='{}'",
+                    self.key,
+                    self.value.replace('\'', "'\\''")
+                )
+            }
+            ExportFormat::Fish => format!(
+                "set -gx {} '{}'",
+                self.key,
+                self.value.replace('\\', "\\\\").replace('\'', "\\'")
+            ),
+            ExportFormat::PowerShell => {
+                format!("$env:{} = '{}'", self.key, self.value.replace('\'', "''"))
+            }
+            ExportFormat::Dotenv => format!(
+                "{}=\"{}\"",
+                self.key,
+                self.value
+                    .replace('\\', "\\\\")
+                    .replace('"', "\\\"")
+                    .replace('\n', "\\n")
+            ),
+        }
+    }
+}
+
+/// The syntax to write environment variables in for other programs to read.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExportFormat {
+    Sh,
+    Fish,
+    PowerShell,
+    Dotenv,
+}
+
+impl FromStr for ExportFormat {
+    type Err = AnyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sh" => Ok(Self::Sh),
+            "fish" => Ok(Self::Fish),
+            "powershell" => Ok(Self::PowerShell),
+            "dotenv" => Ok(Self::Dotenv),
+            _ => Err(AnyError(format!(
+                "unknown export format '{}' (expected one of: sh, fish, powershell, dotenv)",
+                s
+            ))),
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sh => write!(f, "sh"),
+            Self::Fish => write!(f, "fish"),
+            Self::PowerShell => write!(f, "powershell"),
+            Self::Dotenv => write!(f, "dotenv"),
+        }
+    }
 }
 
 impl std::fmt::Debug for EnvVar {
@@ -218,10 +288,12 @@ impl Environment {
 }
 
 pub const ORBIT_PLUGIN: &str = "ORBIT_PLUGIN";
+pub const ORBIT_PROFILE: &str = "ORBIT_PROFILE";
 pub const ORBIT_TOP: &str = "ORBIT_TOP";
 pub const ORBIT_BENCH: &str = "ORBIT_BENCH";
 pub const ORBIT_BUILD_DIR: &str = "ORBIT_BUILD_DIR";
 pub const ORBIT_CACHE: &str = "ORBIT_CACHE";
+pub const ORBIT_CACHE_PATH: &str = "ORBIT_CACHE_PATH";
 pub const ORBIT_QUEUE: &str = "ORBIT_QUEUE";
 pub const ORBIT_DOWNLOADS: &str = "ORBIT_DOWNLOADS";
 pub const ORBIT_HOME: &str = "ORBIT_HOME";
@@ -232,3 +304,35 @@ pub const ORBIT_WIN_LITERAL_CMD: &str = "ORBIT_WIN_LITERAL_CMD";
 pub const ORBIT_ENV_PREFIX: &str = "ORBIT_ENV_";
 
 pub const DOT_ENV_FILE: &str = ".env";
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn to_export() {
+        let var = EnvVar::new().key("orbit-env-msg").value("it's \"done\"");
+        assert_eq!(
+            var.to_export(&ExportFormat::Sh),
+            "export ORBIT_ENV_MSG='it'\\''s \"done\"'"
+        );
+        assert_eq!(
+            var.to_export(&ExportFormat::Fish),
+            "set -gx ORBIT_ENV_MSG 'it\\'s \"done\"'"
+        );
+        assert_eq!(
+            var.to_export(&ExportFormat::PowerShell),
+            "$env:ORBIT_ENV_MSG = 'it''s \"done\"'"
+        );
+        assert_eq!(
+            var.to_export(&ExportFormat::Dotenv),
+            "ORBIT_ENV_MSG=\"it's \\\"done\\\"\""
+        );
+        let var = EnvVar::new().key("ORBIT_HOME").value("C:\\orbit");
+        assert_eq!(
+            var.to_export(&ExportFormat::Fish),
+            "set -gx ORBIT_HOME 'C:\\\\orbit'"
+        );
+        assert_eq!(ExportFormat::from_str("pwsh").is_err(), true);
+    }
+}
@@ -0,0 +1,345 @@
+//! Unpacks the archive formats understood by the default protocol.
+//!
+//! The format is identified from the leading bytes of the file, falling back
+//! to the reported content type and then the file extension of the url.
+
+use crate::util::anyerror::{AnyError, Fault};
+use flate2::read::MultiGzDecoder;
+use std::fs::{self, File};
+use std::io::{BufReader, Read};
+use std::path::PathBuf;
+use xz2::read::XzDecoder;
+use zip::ZipArchive;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ArchiveFormat {
+    Zip,
+    Tar,
+    TarGz,
+    TarXz,
+    TarZst,
+    /// A single file compressed with gzip.
+    Gz,
+}
+
+/// The number of leading bytes required to identify any supported format.
+///
+/// A tar file stores its magic string at byte offset 257.
+const MAGIC_LEN: usize = 262;
+
+impl ArchiveFormat {
+    /// Identifies the format from the `magic` leading bytes of the file, the
+    /// `content_type` reported by the server, or the `url`, in that order.
+    pub fn detect(magic: &[u8], content_type: Option<&str>, url: &str) -> Option<Self> {
+        Self::from_magic(magic)
+            .or_else(|| content_type.and_then(|c| Self::from_content_type(c)))
+            .or_else(|| Self::from_url(url))
+    }
+
+    fn from_magic(magic: &[u8]) -> Option<Self> {
+        if magic.starts_with(&[0x50, 0x4b, 0x03, 0x04])
+            || magic.starts_with(&[0x50, 0x4b, 0x05, 0x06])
+        {
+            Some(Self::Zip)
+        } else if magic.starts_with(&[0x1f, 0x8b]) {
+            Some(Self::TarGz)
+        } else if magic.starts_with(&[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00]) {
+            Some(Self::TarXz)
+        } else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Self::TarZst)
+        } else if magic.get(257..262) == Some(b"ustar") {
+            Some(Self::Tar)
+        } else {
+            None
+        }
+    }
+
+    fn from_content_type(content_type: &str) -> Option<Self> {
+        // ignore any parameters such as the charset
+        let mime = content_type.split(';').next()?.trim().to_lowercase();
+        match mime.as_str() {
+            "application/zip" | "application/x-zip-compressed" => Some(Self::Zip),
+            "application/x-tar" => Some(Self::Tar),
+            "application/gzip" | "application/x-gzip" | "application/x-gtar" => Some(Self::TarGz),
+            "application/x-xz" => Some(Self::TarXz),
+            "application/zstd" | "application/x-zstd" => Some(Self::TarZst),
+            _ => None,
+        }
+    }
+
+    fn from_url(url: &str) -> Option<Self> {
+        // ignore any query or fragment trailing the path
+        let path = url.split(['?', '#']).next()?.to_lowercase();
+        if path.ends_with(".zip") {
+            Some(Self::Zip)
+        } else if path.ends_with(".tar") {
+            Some(Self::Tar)
+        } else if path.ends_with(".gz") || path.ends_with(".tgz") {
+            Some(Self::TarGz)
+        } else if path.ends_with(".tar.xz") || path.ends_with(".txz") {
+            Some(Self::TarXz)
+        } else if path.ends_with(".tar.zst") || path.ends_with(".tzst") {
+            Some(Self::TarZst)
+        } else {
+            None
+        }
+    }
+
+    /// Unpacks the `archive` file downloaded from `url` into the directory `dst`.
+    fn unpack(&self, archive: &PathBuf, dst: &PathBuf, url: &str) -> Result<(), Fault> {
+        let file = BufReader::new(File::open(&archive)?);
+        match self {
+            Self::Gz => {
+                let name = match gz_file_name(url) {
+                    Some(n) => n,
+                    None => Err(AnyError(format!(
+                        "failed to name the file decompressed from url {}",
+                        url
+                    )))?,
+                };
+                let mut out = File::create(dst.join(name))?;
+                std::io::copy(&mut MultiGzDecoder::new(file), &mut out)?;
+            }
+            Self::Zip => ZipArchive::new(file)?.extract(&dst)?,
+            Self::Tar => tar::Archive::new(file).unpack(&dst)?,
+            Self::TarGz => tar::Archive::new(MultiGzDecoder::new(file)).unpack(&dst)?,
+            Self::TarXz => tar::Archive::new(XzDecoder::new(file)).unpack(&dst)?,
+            Self::TarZst => tar::Archive::new(zstd::Decoder::with_buffer(file)?).unpack(&dst)?,
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Zip => write!(f, "zip"),
+            Self::Tar => write!(f, "tar"),
+            Self::TarGz => write!(f, "tar.gz"),
+            Self::TarXz => write!(f, "tar.xz"),
+            Self::TarZst => write!(f, "tar.zst"),
+            Self::Gz => write!(f, "gz"),
+        }
+    }
+}
+
+/// Extracts the `archive` file downloaded from `url` into the directory `dst`.
+///
+/// When every entry of the archive is nested under one top-level directory,
+/// that directory is stripped so its contents are placed directly in `dst`.
+pub fn extract(
+    archive: &PathBuf,
+    dst: &PathBuf,
+    content_type: Option<&str>,
+    url: &str,
+) -> Result<ArchiveFormat, Fault> {
+    let mut magic = Vec::with_capacity(MAGIC_LEN);
+    File::open(&archive)?
+        .take(MAGIC_LEN as u64)
+        .read_to_end(&mut magic)?;
+
+    let format = match ArchiveFormat::detect(&magic, content_type, url) {
+        Some(f) => f,
+        None => Err(AnyError(format!(
+            "unsupported archive format from url {}; expected zip, tar, gzip, xz, or zstd",
+            url
+        )))?,
+    };
+    // a gzip stream holds either a tarball or a single file
+    let format = match format == ArchiveFormat::TarGz && holds_tar(archive) == false {
+        true => ArchiveFormat::Gz,
+        false => format,
+    };
+
+    fs::create_dir_all(&dst)?;
+    let staging = tempfile::Builder::new()
+        .prefix(".extract")
+        .tempdir_in(&dst)?;
+    let staging_path = staging.path().to_path_buf();
+    if let Err(e) = format.unpack(archive, &staging_path, url) {
+        return Err(AnyError(format!(
+            "failed to extract {} archive from url {}: {}",
+            format, url, e
+        )))?;
+    }
+    place_contents(&staging_path, dst)?;
+    Ok(format)
+}
+
+/// Checks if the gzipped `archive` decompresses into a tarball.
+///
+/// Streams that cannot be decompressed are assumed to be tarballs so the tar
+/// reader reports the error.
+fn holds_tar(archive: &PathBuf) -> bool {
+    let mut magic = Vec::with_capacity(MAGIC_LEN);
+    let read = File::open(&archive).and_then(|f| {
+        MultiGzDecoder::new(BufReader::new(f))
+            .take(MAGIC_LEN as u64)
+            .read_to_end(&mut magic)
+    });
+    match read {
+        Ok(_) => magic.get(257..262) == Some(b"ustar"),
+        Err(_) => true,
+    }
+}
+
+/// Names the file decompressed from the gzipped file at `url` after the last
+/// segment of its path without the `.gz` extension.
+fn gz_file_name(url: &str) -> Option<&str> {
+    // ignore any query or fragment trailing the path
+    let path = url.split(['?', '#']).next()?;
+    let name = path.rsplit(['/', '\\']).next()?;
+    // the extension is ascii, so a match always ends on a char boundary
+    let stem = match name
+        .get(name.len().saturating_sub(3)..)
+        .map_or(false, |ext| ext.eq_ignore_ascii_case(".gz"))
+    {
+        true => &name[..name.len() - 3],
+        false => name,
+    };
+    match stem.is_empty() {
+        true => None,
+        false => Some(stem),
+    }
+}
+
+/// Moves the extracted contents of `staging` into `dst`, stripping the
+/// top-level directory if it is the only entry.
+fn place_contents(staging: &PathBuf, dst: &PathBuf) -> Result<(), Fault> {
+    let entries: Vec<PathBuf> = fs::read_dir(&staging)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    let root = match entries.len() == 1 && entries[0].is_dir() == true {
+        true => entries[0].clone(),
+        false => staging.clone(),
+    };
+    for entry in fs::read_dir(&root)? {
+        let entry = entry?;
+        fs::rename(entry.path(), dst.join(entry.file_name()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    /// Builds a gzipped tarball at `path` holding the files at each relative path.
+    fn write_tar_gz(path: &PathBuf, files: &[&str]) {
+        let enc = GzEncoder::new(File::create(path).unwrap(), Compression::default());
+        let mut builder = tar::Builder::new(enc);
+        for f in files {
+            let data = b"library ieee;\n";
+            let mut header = tar::Header::new_gnu();
+            header.set_size(data.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, f, &data[..]).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn detect_format() {
+        assert_eq!(
+            ArchiveFormat::detect(&[0x50, 0x4b, 0x03, 0x04, 0x00], None, ""),
+            Some(ArchiveFormat::Zip)
+        );
+        assert_eq!(
+            ArchiveFormat::detect(&[0x1f, 0x8b, 0x08], None, "https://a.com/gates.zip"),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::detect(&[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00], None, ""),
+            Some(ArchiveFormat::TarXz)
+        );
+        assert_eq!(
+            ArchiveFormat::detect(&[0x28, 0xb5, 0x2f, 0xfd], None, ""),
+            Some(ArchiveFormat::TarZst)
+        );
+        let mut tar = vec![0; MAGIC_LEN];
+        tar[257..262].copy_from_slice(b"ustar");
+        assert_eq!(
+            ArchiveFormat::detect(&tar, None, ""),
+            Some(ArchiveFormat::Tar)
+        );
+        // fall back to the content type and then the url
+        assert_eq!(
+            ArchiveFormat::detect(&[], Some("application/x-xz; charset=binary"), ""),
+            Some(ArchiveFormat::TarXz)
+        );
+        assert_eq!(
+            ArchiveFormat::detect(
+                &[],
+                Some("application/octet-stream"),
+                "https://a.com/gates-1.0.0.tgz?raw=true"
+            ),
+            Some(ArchiveFormat::TarGz)
+        );
+        assert_eq!(
+            ArchiveFormat::detect(&[], None, "https://a.com/gates-1.0.0.tar.zst"),
+            Some(ArchiveFormat::TarZst)
+        );
+        assert_eq!(
+            ArchiveFormat::detect(&[0x00], None, "https://a.com/gates"),
+            None
+        );
+    }
+
+    #[test]
+    fn extract_strips_single_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("gates.tar.gz");
+        write_tar_gz(
+            &archive,
+            &["gates-1.0.0/Orbit.toml", "gates-1.0.0/rtl/and_gate.vhd"],
+        );
+
+        let dst = dir.path().join("queue");
+        let format = extract(&archive, &dst, None, "").unwrap();
+        assert_eq!(format, ArchiveFormat::TarGz);
+        assert_eq!(dst.join("Orbit.toml").is_file(), true);
+        assert_eq!(dst.join("rtl").join("and_gate.vhd").is_file(), true);
+        // only the extracted contents remain
+        assert_eq!(fs::read_dir(&dst).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn extract_single_gz_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("download.part");
+        let mut enc = GzEncoder::new(File::create(&archive).unwrap(), Compression::default());
+        std::io::Write::write_all(&mut enc, b"library ieee;\n").unwrap();
+        enc.finish().unwrap();
+
+        let dst = dir.path().join("queue");
+        let format = extract(&archive, &dst, None, "https://a.com/and_gate.vhd.gz?raw=1").unwrap();
+        assert_eq!(format, ArchiveFormat::Gz);
+        assert_eq!(
+            fs::read_to_string(dst.join("and_gate.vhd")).unwrap(),
+            "library ieee;\n"
+        );
+        assert_eq!(fs::read_dir(&dst).unwrap().count(), 1);
+
+        assert_eq!(gz_file_name("https://a.com/gates.GZ"), Some("gates"));
+        assert_eq!(gz_file_name("https://a.com/"), None);
+        // names that are not split on a char boundary are kept whole
+        assert_eq!(gz_file_name("https://a.com/résumé"), Some("résumé"));
+        assert_eq!(gz_file_name("https://a.com/résumé.gz"), Some("résumé"));
+    }
+
+    #[test]
+    fn extract_keeps_multiple_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let archive = dir.path().join("gates.tar.gz");
+        write_tar_gz(&archive, &["Orbit.toml", "rtl/and_gate.vhd"]);
+
+        let dst = dir.path().join("queue");
+        extract(&archive, &dst, None, "").unwrap();
+        assert_eq!(dst.join("Orbit.toml").is_file(), true);
+        assert_eq!(dst.join("rtl").join("and_gate.vhd").is_file(), true);
+    }
+}
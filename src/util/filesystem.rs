@@ -159,7 +159,10 @@ pub fn remove_base(base: &PathBuf, full: &PathBuf) -> PathBuf {
 }
 
 pub fn is_orbit_metadata(s: &str) -> bool {
-    s == manifest::IP_MANIFEST_FILE || s == ORBIT_IGNORE_FILE || s == lockfile::IP_LOCK_FILE
+    s == manifest::IP_MANIFEST_FILE
+        || s == ORBIT_IGNORE_FILE
+        || s == lockfile::IP_LOCK_FILE
+        || s == manifest::ORBIT_METADATA_FILE
 }
 
 pub fn is_minimal(name: &str) -> bool {
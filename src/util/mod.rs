@@ -2,6 +2,7 @@ pub mod anyerror;
 pub mod checksum;
 pub mod compress;
 pub mod environment;
+pub mod extract;
 pub mod filesystem;
 pub mod graph;
 pub mod graphmap;
@@ -111,138 +111,179 @@ impl Display for Sha256Hash {
 
 /// Compute the SHA-256 hash function for a slice of bytes.
 pub fn compute_sha256(s: &[u8]) -> Sha256Hash {
-    // [1] preprocessing
-    // convert the string into bytes
-    let mut bytes = s.to_vec();
-    // compute the input bytes total length to store in 8 bytes
-    let message_len: u64 = (bytes.len() as u64) * 8;
-    // append a single '1' as 1000 0000 (0x80)
-    bytes.push(128);
-
-    // small boost if vector has not already reserved enough space for adding another chunk
-    bytes.reserve(64 - (bytes.len() % 64));
-
-    // pad with zeros until data is a multiple of 512 -> 64 bytes
-    let zero_cnt_bytes = {
-        let zero_cnt = 512 - ((bytes.len() * 8 + 64) % 512);
-        if zero_cnt == 512 {
-            0
-        } else {
-            zero_cnt
-        }
-    } / 8;
+    let mut hasher = Sha256Hasher::new();
+    hasher.update(s);
+    hasher.finish()
+}
 
-    for _ in 0..zero_cnt_bytes {
-        bytes.push(0);
-    }
+/// The size in bytes of a single chunk of the message (512 bits).
+const CHUNK_SIZE: usize = 64;
+
+/// The initial hash values.
+const INITIAL_HASHES: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+/// The round constants.
+const ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Computes the SHA-256 hash function over a message that is given in pieces,
+/// so the entire message never needs to be held in memory.
+pub struct Sha256Hasher {
+    hashes: [u32; 8],
+    /// Bytes of the message that do not yet fill a complete chunk.
+    pending: Vec<u8>,
+    /// The total number of bytes in the message so far.
+    len: u64,
+}
 
-    // append 64 bits to the end, where 64 bits represent integer length of original input in binary
-    {
-        // store as big-endian
-        for i in (0..8).rev() {
-            bytes.push((message_len >> (8 * i)) as u8);
+impl Sha256Hasher {
+    pub fn new() -> Self {
+        Self {
+            hashes: INITIAL_HASHES,
+            pending: Vec::with_capacity(CHUNK_SIZE),
+            len: 0,
         }
     }
-    assert_eq!(bytes.len() % 64, 0); // 512 bits -> 64 bytes
-
-    // [2] initialize hash values
-    let mut hashes: [u32; 8] = [
-        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
-        0x5be0cd19,
-    ];
-
-    // [3] initialize array of round constants
-    let k: [u32; 64] = [
-        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
-        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
-        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
-        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
-        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
-        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
-        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
-        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
-        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
-        0xc67178f2,
-    ];
-
-    // [4] chunk loop
-    let chunk_count = bytes.len() / (512 / 8);
-    //dbg!(chunk_count);
-    for n in 0..chunk_count {
-        // access correct slice of chunk from byte vector
-        let chunk = &bytes[n * 64..(n + 1) * 64];
-        // create 64-entry message schedule array w[0..63] of 32-bit words
-        let mut message: [u32; 64] = [0; 64];
-        // copy chunk into first 16 words
-        for j in 0..16 {
-            message[j] = (chunk[4 * j] as u32) << 24
-                | (chunk[4 * j + 1] as u32) << 16
-                | (chunk[4 * j + 2] as u32) << 8
-                | (chunk[4 * j + 3] as u32);
+
+    /// Appends `data` to the message.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.len += data.len() as u64;
+        // complete the chunk left over from the previous update
+        if self.pending.is_empty() == false {
+            let take = (CHUNK_SIZE - self.pending.len()).min(data.len());
+            self.pending.extend_from_slice(&data[..take]);
+            data = &data[take..];
+            if self.pending.len() < CHUNK_SIZE {
+                return;
+            }
+            compress(&mut self.hashes, &self.pending);
+            self.pending.clear();
+        }
+        let mut chunks = data.chunks_exact(CHUNK_SIZE);
+        for chunk in &mut chunks {
+            compress(&mut self.hashes, chunk);
         }
+        self.pending.extend_from_slice(chunks.remainder());
+    }
 
-        // extend the first 16 words into the remaining 48 words w[16..63] of the message
-        for j in 16..64 {
-            //s0 := (w[i-15] rightrotate  7) xor (w[i-15] rightrotate 18) xor (w[i-15] rightshift  3)
-            let s0: u32 = message[j - 15].rotate_right(7)
-                ^ message[j - 15].rotate_right(18)
-                ^ (message[j - 15] >> 3);
-            //s1 := (w[i-2] rightrotate 17) xor (w[i-2] rightrotate 19) xor (w[i-2] rightshift 10)
-            let s1: u32 = message[j - 2].rotate_right(17)
-                ^ message[j - 2].rotate_right(19)
-                ^ (message[j - 2] >> 10);
-            message[j] = message[j - 16]
-                .wrapping_add(s0)
-                .wrapping_add(message[j - 7])
-                .wrapping_add(s1);
+    /// Pads the message and produces its hash value.
+    pub fn finish(mut self) -> Sha256Hash {
+        // [1] preprocessing
+        // compute the input bytes total length to store in 8 bytes
+        let message_len: u64 = self.len * 8;
+        let mut bytes = std::mem::take(&mut self.pending);
+        // append a single '1' as 1000 0000 (0x80)
+        bytes.push(128);
+        // pad with zeros until 8 bytes remain for a multiple of 512 bits -> 64 bytes
+        while bytes.len() % CHUNK_SIZE != CHUNK_SIZE - 8 {
+            bytes.push(0);
         }
+        // append 64 bits to the end, where 64 bits represent integer length of original input in binary (big-endian)
+        bytes.extend_from_slice(&message_len.to_be_bytes());
+        assert_eq!(bytes.len() % CHUNK_SIZE, 0);
 
-        // initialize current working variables to the current hash values
-        let mut wh = hashes;
-
-        // compression function main loop
-        for i in 0..64 {
-            // S1 := (e rightrotate 6) xor (e rightrotate 11) xor (e rightrotate 25)
-            let s1 = wh[4].rotate_right(6) ^ wh[4].rotate_right(11) ^ wh[4].rotate_right(25);
-            // ch := (e and f) xor ((not e) and g)
-            let ch = (wh[4] & wh[5]) ^ ((!wh[4]) & wh[6]);
-            // temp1 := h + S1 + ch + k[i] + w[i]
-            let temp1 = wh[7]
-                .wrapping_add(s1)
-                .wrapping_add(ch)
-                .wrapping_add(k[i])
-                .wrapping_add(message[i]);
-            // S0 := (a rightrotate 2) xor (a rightrotate 13) xor (a rightrotate 22)
-            let s0 = wh[0].rotate_right(2) ^ wh[0].rotate_right(13) ^ wh[0].rotate_right(22);
-            // maj := (a and b) xor (a and c) xor (b and c)
-            let maj = (wh[0] & wh[1]) ^ (wh[0] & wh[2]) ^ (wh[1] & wh[2]);
-            // temp2 := S0 + maj
-            let temp2 = s0.wrapping_add(maj);
-
-            // h := g
-            wh[7] = wh[6];
-            // g := f
-            wh[6] = wh[5];
-            // f := e
-            wh[5] = wh[4];
-            // e := d + temp1
-            wh[4] = wh[3].wrapping_add(temp1);
-            // d := c
-            wh[3] = wh[2];
-            // c := b
-            wh[2] = wh[1];
-            // b := a
-            wh[1] = wh[0];
-            // a := temp1 + temp2
-            wh[0] = temp1.wrapping_add(temp2);
+        for chunk in bytes.chunks_exact(CHUNK_SIZE) {
+            compress(&mut self.hashes, chunk);
         }
-        // add the compressed chunk to the current hash value
-        for i in 0..8 {
-            hashes[i] = hashes[i].wrapping_add(wh[i]);
+        // produce the final hash value (big-endian)
+        Sha256Hash {
+            digest: self.hashes,
         }
     }
-    // produce the final hash value (big-endian)
-    Sha256Hash { digest: hashes }
+}
+
+impl std::io::Write for Sha256Hasher {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Updates the `hashes` with a single 64-byte `chunk` of the padded message.
+fn compress(hashes: &mut [u32; 8], chunk: &[u8]) {
+    // create 64-entry message schedule array w[0..63] of 32-bit words
+    let mut message: [u32; 64] = [0; 64];
+    // copy chunk into first 16 words
+    for j in 0..16 {
+        message[j] = (chunk[4 * j] as u32) << 24
+            | (chunk[4 * j + 1] as u32) << 16
+            | (chunk[4 * j + 2] as u32) << 8
+            | (chunk[4 * j + 3] as u32);
+    }
+
+    // extend the first 16 words into the remaining 48 words w[16..63] of the message
+    for j in 16..64 {
+        //s0 := (w[i-15] rightrotate  7) xor (w[i-15] rightrotate 18) xor (w[i-15] rightshift  3)
+        let s0: u32 = message[j - 15].rotate_right(7)
+            ^ message[j - 15].rotate_right(18)
+            ^ (message[j - 15] >> 3);
+        //s1 := (w[i-2] rightrotate 17) xor (w[i-2] rightrotate 19) xor (w[i-2] rightshift 10)
+        let s1: u32 = message[j - 2].rotate_right(17)
+            ^ message[j - 2].rotate_right(19)
+            ^ (message[j - 2] >> 10);
+        message[j] = message[j - 16]
+            .wrapping_add(s0)
+            .wrapping_add(message[j - 7])
+            .wrapping_add(s1);
+    }
+
+    // initialize current working variables to the current hash values
+    let mut wh = *hashes;
+
+    // compression function main loop
+    for i in 0..64 {
+        // S1 := (e rightrotate 6) xor (e rightrotate 11) xor (e rightrotate 25)
+        let s1 = wh[4].rotate_right(6) ^ wh[4].rotate_right(11) ^ wh[4].rotate_right(25);
+        // ch := (e and f) xor ((not e) and g)
+        let ch = (wh[4] & wh[5]) ^ ((!wh[4]) & wh[6]);
+        // temp1 := h + S1 + ch + ROUND_CONSTANTS[i] + w[i]
+        let temp1 = wh[7]
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(ROUND_CONSTANTS[i])
+            .wrapping_add(message[i]);
+        // S0 := (a rightrotate 2) xor (a rightrotate 13) xor (a rightrotate 22)
+        let s0 = wh[0].rotate_right(2) ^ wh[0].rotate_right(13) ^ wh[0].rotate_right(22);
+        // maj := (a and b) xor (a and c) xor (b and c)
+        let maj = (wh[0] & wh[1]) ^ (wh[0] & wh[2]) ^ (wh[1] & wh[2]);
+        // temp2 := S0 + maj
+        let temp2 = s0.wrapping_add(maj);
+
+        // h := g
+        wh[7] = wh[6];
+        // g := f
+        wh[6] = wh[5];
+        // f := e
+        wh[5] = wh[4];
+        // e := d + temp1
+        wh[4] = wh[3].wrapping_add(temp1);
+        // d := c
+        wh[3] = wh[2];
+        // c := b
+        wh[2] = wh[1];
+        // b := a
+        wh[1] = wh[0];
+        // a := temp1 + temp2
+        wh[0] = temp1.wrapping_add(temp2);
+    }
+    // add the compressed chunk to the current hash value
+    for i in 0..8 {
+        hashes[i] = hashes[i].wrapping_add(wh[i]);
+    }
 }
 
 #[derive(Debug)]
@@ -448,6 +489,16 @@ An interview."
         )
     }
 
+    #[test]
+    fn in_pieces() {
+        let message: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        for size in [1, 7, 63, 64, 65, 200] {
+            let mut hasher = Sha256Hasher::new();
+            message.chunks(size).for_each(|piece| hasher.update(piece));
+            assert_eq!(hasher.finish(), compute_sha256(&message));
+        }
+    }
+
     #[test]
     fn str_repr() {
         assert_eq!(
@@ -29,6 +29,13 @@ COMMANDS = [
     'env',
     'config',
     'remove',
+    'vendor',
+    'update',
+    'outdated',
+    'verify',
+    'inspect',
+    'unpack',
+    'cache',
 ]
 
 CWD, _ = os.path.split(sys.argv[0])